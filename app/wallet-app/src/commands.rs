@@ -0,0 +1,844 @@
+// E:\Rust\Projects\wallet\app\wallet_app\src\commands.rs
+
+use clap::{Parser, Subcommand};
+use futures_util::StreamExt;
+use std::path::{Path, PathBuf};
+use stellar_wallet_tools_lib::authorization::list_asset_holders;
+use stellar_wallet_tools_lib::batch::{read_payment_rows, resolve_destinations, BatchPayout};
+use stellar_wallet_tools_lib::data::{decode_data_value, get_data_entry, list_data_entries, DataEntry};
+use stellar_wallet_tools_lib::liquidity_pool::{compute_pool_id, get_pool, get_pool_position};
+use stellar_wallet_tools_lib::path_payment::{find_strict_receive_paths, find_strict_send_paths};
+use stellar_wallet_tools_lib::{
+    Amount, AssetId, FederationMemo, FederationResolver, NetworkProfile, PathAssets, PaymentEvent, PaymentWatcher,
+    ResolvedDestination, StellarUri, StellarWalletTools, TrustlineAuthorization, UriRequest, WebAuthClient,
+};
+
+/// Non-interactive entry points of the wallet.
+/// Running `wallet_app` without a subcommand starts the interactive menu instead.
+#[derive(Parser)]
+#[command(name = "wallet_app", about = "Wallet Pilot Stellar Project CLI")]
+pub struct Cli {
+    /// Network profile to use (testnet, futurenet, local, public); defaults to $STELLAR_NETWORK or testnet
+    #[arg(long, global = true)]
+    pub network: Option<String>,
+    /// Also show keys, requests and transactions as a QR code: "terminal", or a .png/.svg file to write
+    #[cfg(feature = "qr")]
+    #[arg(long, global = true)]
+    pub qr: Option<crate::qr::QrTarget>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// Resolves the network profile selected on the command line or in the environment.
+    pub fn network_profile(&self) -> Result<NetworkProfile, Box<dyn std::error::Error>> {
+        match &self.network {
+            Some(name) => NetworkProfile::from_name(name)
+                .map(NetworkProfile::with_env_overrides)
+                .ok_or_else(|| format!("Unknown network profile '{}'", name).into()),
+            None => NetworkProfile::active(),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Generate a new Stellar key pair
+    Generate {
+        /// Create the account on-chain using the network's Friendbot
+        #[arg(long)]
+        fund: bool,
+    },
+    /// Inspect and configure accounts
+    Account {
+        #[command(subcommand)]
+        command: AccountCommand,
+    },
+    /// Authenticate with an anchor (SEP-10) and print the token
+    Auth {
+        /// Anchor home domain (e.g. testanchor.stellar.org)
+        domain: String,
+        /// Account to authenticate (defaults to the account of --secret)
+        #[arg(long)]
+        account: Option<String>,
+        /// Additional signer secret keys, for multisig accounts
+        #[arg(long = "signer")]
+        signers: Vec<String>,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+    /// Send many payments at once
+    Batch {
+        #[command(subcommand)]
+        command: BatchCommand,
+    },
+    /// Manage the holders of an asset you issue (authorization and clawback)
+    Asset {
+        #[command(subcommand)]
+        command: AssetCommand,
+    },
+    /// Inspect and use AMM liquidity pools
+    Pool {
+        #[command(subcommand)]
+        command: PoolCommand,
+    },
+    /// Build and sign transactions
+    Tx {
+        #[command(subcommand)]
+        command: TxCommand,
+    },
+    /// Create and run SEP-7 (web+stellar:) payment and transaction requests
+    Uri {
+        #[command(subcommand)]
+        command: UriCommand,
+    },
+    /// Follow the payments received by an account and print them or run a hook for each one
+    Watch {
+        /// Account to watch (G...)
+        account: String,
+        /// Horizon server to stream from (defaults to the network profile's Horizon)
+        #[arg(long)]
+        horizon: Option<String>,
+        /// Paging token to start from (defaults to "now")
+        #[arg(long)]
+        cursor: Option<String>,
+        /// File used to persist the cursor between runs
+        #[arg(long)]
+        cursor_file: Option<PathBuf>,
+        /// Shell command run for each payment; details are passed as STELLAR_PAYMENT_* variables
+        #[arg(long)]
+        exec: Option<String>,
+        /// Also report payments sent by the account
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TxCommand {
+    /// Pay an account, optionally converting from another asset through the DEX
+    Pay {
+        /// Destination account (G...) or federation address (name*domain)
+        #[arg(long)]
+        to: String,
+        /// Amount the destination receives (e.g. 10.5)
+        #[arg(long)]
+        amount: Amount,
+        /// Asset the destination receives ("XLM" or CODE:ISSUER)
+        #[arg(long, default_value = "XLM")]
+        asset: AssetId,
+        /// Pay with another asset using the cheapest path found (strict receive)
+        #[arg(long, requires = "send_asset")]
+        via_path: bool,
+        /// Asset to pay with when using --via-path
+        #[arg(long)]
+        send_asset: Option<AssetId>,
+        /// Maximum price slippage for path payments, in basis points
+        #[arg(long, default_value_t = 100)]
+        slippage_bps: u32,
+        /// Secret key of the paying account (S...)
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+    /// Convert an exact amount of one asset into another (strict send to yourself)
+    Swap {
+        /// Asset to sell ("XLM" or CODE:ISSUER)
+        #[arg(long)]
+        send: AssetId,
+        /// Exact amount to sell
+        #[arg(long)]
+        amount: Amount,
+        /// Asset to receive
+        #[arg(long)]
+        receive: AssetId,
+        /// Maximum price slippage, in basis points
+        #[arg(long, default_value_t = 100)]
+        slippage_bps: u32,
+        /// Secret key of the account (S...)
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum UriCommand {
+    /// Print a payment request URI for others to pay you
+    Pay {
+        /// Account to be paid (G...)
+        #[arg(long)]
+        to: String,
+        /// Amount requested; omit to let the payer choose
+        #[arg(long)]
+        amount: Option<Amount>,
+        /// Asset requested ("XLM" or CODE:ISSUER)
+        #[arg(long, default_value = "XLM")]
+        asset: AssetId,
+        /// Text memo the payment must carry
+        #[arg(long)]
+        memo: Option<String>,
+        /// Message shown to the payer
+        #[arg(long)]
+        message: Option<String>,
+    },
+    /// Show a web+stellar: URI, ask for confirmation, then sign and submit it
+    Run {
+        /// The web+stellar: URI
+        uri: String,
+        /// Amount to pay, for payment requests that leave it open
+        #[arg(long)]
+        amount: Option<Amount>,
+        /// Don't ask for confirmation, unless the transaction is from another account
+        #[arg(long)]
+        yes: bool,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AccountCommand {
+    /// Read and write the data entries stored on an account
+    Data {
+        #[command(subcommand)]
+        command: DataCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DataCommand {
+    /// Print one data entry, or all of them
+    Get {
+        /// Account to read (G...)
+        account: String,
+        /// Entry name; omit to list every entry
+        name: Option<String>,
+    },
+    /// Create or replace a data entry on your account
+    Set {
+        /// Entry name (up to 64 bytes)
+        name: String,
+        /// Entry value (up to 64 bytes)
+        value: String,
+        /// The value is base64-encoded binary data
+        #[arg(long)]
+        base64: bool,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+    /// Delete a data entry from your account
+    Rm {
+        name: String,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BatchCommand {
+    /// Pay every row of a CSV or JSON file (destination, amount, asset, memo)
+    ///
+    /// Progress is saved after each transaction; run the same command again to
+    /// resume an interrupted batch.
+    Pay {
+        /// Payments file (.csv with a header row, or .json array)
+        file: PathBuf,
+        /// Progress file (defaults to <FILE>.state.json)
+        #[arg(long)]
+        state: Option<PathBuf>,
+        /// Results report (defaults to <FILE>.report.csv)
+        #[arg(long)]
+        report: Option<PathBuf>,
+        /// Only validate the file and show how the payments would be grouped
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AssetCommand {
+    /// Fully authorize a holder's trustline
+    Authorize {
+        /// Holder account (G...)
+        trustor: String,
+        /// Code of the asset issued by --secret
+        code: String,
+        /// Only allow the holder to keep existing offers, not to send or receive
+        #[arg(long)]
+        maintain_liabilities: bool,
+        /// Secret key of the issuer (S...)
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+    /// Revoke a holder's authorization (requires the auth revocable flag)
+    Deauthorize {
+        trustor: String,
+        code: String,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+    /// Claw back part of a holder's balance (requires clawback on the trustline)
+    Clawback {
+        /// Account to claw back from (G...)
+        from: String,
+        code: String,
+        amount: Amount,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+    /// Claw back a claimable balance of the asset
+    ClawbackBalance {
+        /// Claimable balance ID (hex)
+        balance_id: String,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+    /// List the holders of an asset and their authorization state
+    Holders {
+        /// The asset (CODE:ISSUER)
+        asset: AssetId,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PoolCommand {
+    /// Print the pool ID of an asset pair
+    Id {
+        /// First asset ("XLM" or CODE:ISSUER)
+        asset_a: AssetId,
+        /// Second asset
+        asset_b: AssetId,
+    },
+    /// Show a pool's reserves, and an account's share of it
+    Info {
+        /// Pool ID (hex)
+        pool_id: String,
+        /// Also show the share held by this account (G...)
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// Trust the pool shares of an asset pair (required before depositing)
+    Trust {
+        asset_a: AssetId,
+        asset_b: AssetId,
+        /// Remove the trustline instead (the share balance must be zero)
+        #[arg(long)]
+        remove: bool,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+    /// Deposit into a pool
+    Deposit {
+        pool_id: String,
+        /// Maximum amount of asset A to deposit
+        #[arg(long)]
+        max_a: Amount,
+        /// Maximum amount of asset B to deposit
+        #[arg(long)]
+        max_b: Amount,
+        /// Allowed pool price movement, in basis points
+        #[arg(long, default_value_t = 100)]
+        slippage_bps: u32,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+    /// Withdraw from a pool by redeeming shares
+    Withdraw {
+        pool_id: String,
+        /// Number of pool shares to redeem
+        #[arg(long)]
+        shares: Amount,
+        /// Allowed shortfall from the current share value, in basis points
+        #[arg(long, default_value_t = 100)]
+        slippage_bps: u32,
+        #[arg(long, env = "STELLAR_SECRET_KEY", hide_env_values = true)]
+        secret: String,
+    },
+}
+
+/// Runs a single subcommand to completion.
+pub async fn run(network: &NetworkProfile, command: Command) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        Command::Generate { fund } => generate(network, fund).await,
+        Command::Account { command: AccountCommand::Data { command } } => run_data(network, command).await,
+        Command::Auth { domain, account, signers, secret } => {
+            let account = match account {
+                Some(account) => account,
+                None => StellarWalletTools::public_key_from_secret(&secret)?,
+            };
+            let client = WebAuthClient::from_domain(&domain, network).await?;
+            let mut secrets = vec![secret.as_str()];
+            secrets.extend(signers.iter().map(String::as_str));
+            let token = client.authenticate(&account, &secrets).await?;
+            println!("Authenticated {} with {}", token.account, domain);
+            if let Some(expires_at) = token.expires_at {
+                println!("Token expires at {} (UNIX time)", expires_at);
+            }
+            println!("{}", token.token);
+            Ok(())
+        }
+        Command::Batch { command: BatchCommand::Pay { file, state, report, dry_run, secret } } => {
+            batch_pay(network, &file, state, report, dry_run, &secret).await
+        }
+        Command::Asset { command } => run_asset(network, command).await,
+        Command::Pool { command } => run_pool(network, command).await,
+        Command::Tx { command } => run_tx(network, command).await,
+        Command::Uri { command } => run_uri(network, command).await,
+        Command::Watch { account, horizon, cursor, cursor_file, exec, all } => {
+            let horizon = horizon.unwrap_or_else(|| network.horizon_url.clone());
+            watch(&account, &horizon, cursor, cursor_file, exec, all).await
+        }
+    }
+}
+
+async fn generate(network: &NetworkProfile, fund: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let key_pair = StellarWalletTools::generate_key_pair()?;
+    println!("Seed Phrase (Mnemonic): \"{}\"", key_pair.mnemonic_phrase);
+    println!("Public Key (G...): \"{}\"", key_pair.public_key);
+    println!("Secret Key (S...): \"{}\"", key_pair.secret_key);
+    show_qr("public key", &key_pair.public_key);
+
+    if fund {
+        let hash = StellarWalletTools::fund_with_friendbot(&key_pair.public_key, network).await?;
+        println!("Account funded on {} by Friendbot (tx {})", network.name, hash);
+    }
+
+    println!("\nNEVER SHARE YOUR SECRET KEY OR SEED PHRASE WITH ANYONE.");
+    Ok(())
+}
+
+async fn run_data(network: &NetworkProfile, command: DataCommand) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        DataCommand::Get { account, name: Some(name) } => {
+            let entry = get_data_entry(&network.horizon_url, &account, &name)
+                .await?
+                .ok_or_else(|| format!("No data entry '{}' on {}", name, account))?;
+            print_data_entry(&entry);
+        }
+        DataCommand::Get { account, name: None } => {
+            let entries = list_data_entries(&network.horizon_url, &account).await?;
+            if entries.is_empty() {
+                println!("No data entries on {}", account);
+            }
+            for entry in &entries {
+                print_data_entry(entry);
+            }
+        }
+        DataCommand::Set { name, value, base64, secret } => {
+            let value = if base64 { decode_data_value(&value)? } else { value.into_bytes() };
+            let xdr = StellarWalletTools::create_manage_data_transaction(&secret, &name, Some(&value), network).await?;
+            print_xdr(&xdr, network);
+        }
+        DataCommand::Rm { name, secret } => {
+            let xdr = StellarWalletTools::create_manage_data_transaction(&secret, &name, None, network).await?;
+            print_xdr(&xdr, network);
+        }
+    }
+    Ok(())
+}
+
+fn print_data_entry(entry: &DataEntry) {
+    match entry.value_str() {
+        Some(text) => println!("{} = \"{}\"", entry.name, text.escape_debug()),
+        None => println!("{} = base64:{}", entry.name, entry.value_base64()),
+    }
+}
+
+async fn batch_pay(
+    network: &NetworkProfile,
+    file: &Path,
+    state: Option<PathBuf>,
+    report: Option<PathBuf>,
+    dry_run: bool,
+    secret: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let with_suffix = |suffix: &str| {
+        let mut path = file.as_os_str().to_owned();
+        path.push(suffix);
+        PathBuf::from(path)
+    };
+    let state = state.unwrap_or_else(|| with_suffix(".state.json"));
+    let report = report.unwrap_or_else(|| with_suffix(".report.csv"));
+
+    let rows = resolve_destinations(read_payment_rows(file)?, &FederationResolver::new()).await?;
+    let mut payout = BatchPayout::prepare(rows, &state)?;
+    println!(
+        "{} payments in {} transactions",
+        payout.rows().len(),
+        payout.transactions().len()
+    );
+    if dry_run {
+        for (index, transaction) in payout.transactions().iter().enumerate() {
+            println!(
+                "  #{}: {} payments, memo {:?}",
+                index + 1,
+                transaction.rows.len(),
+                transaction.memo.as_ref().map(FederationMemo::to_string).unwrap_or_default()
+            );
+        }
+        return Ok(());
+    }
+
+    let outcome = payout.run(secret, network).await;
+    // Write the report even when the run stopped early, so progress is visible.
+    payout.write_report(&report)?;
+    outcome?;
+
+    let results = payout.results();
+    let failed = results.iter().filter(|r| r.status == "failed").count();
+    println!("{} paid, {} failed. Report written to {}", results.len() - failed, failed, report.display());
+    Ok(())
+}
+
+async fn run_asset(network: &NetworkProfile, command: AssetCommand) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        AssetCommand::Authorize { trustor, code, maintain_liabilities, secret } => {
+            let authorization = if maintain_liabilities {
+                TrustlineAuthorization::AuthorizedToMaintainLiabilities
+            } else {
+                TrustlineAuthorization::Authorized
+            };
+            let xdr = StellarWalletTools::create_set_authorization_transaction(
+                &secret, &trustor, &code, authorization, network,
+            )
+            .await?;
+            print_xdr(&xdr, network);
+        }
+        AssetCommand::Deauthorize { trustor, code, secret } => {
+            let xdr = StellarWalletTools::create_set_authorization_transaction(
+                &secret,
+                &trustor,
+                &code,
+                TrustlineAuthorization::Deauthorized,
+                network,
+            )
+            .await?;
+            print_xdr(&xdr, network);
+        }
+        AssetCommand::Clawback { from, code, amount, secret } => {
+            let xdr = StellarWalletTools::create_clawback_transaction(&secret, &from, &code, amount, network).await?;
+            print_xdr(&xdr, network);
+        }
+        AssetCommand::ClawbackBalance { balance_id, secret } => {
+            let xdr = StellarWalletTools::create_clawback_claimable_balance_transaction(&secret, &balance_id, network).await?;
+            print_xdr(&xdr, network);
+        }
+        AssetCommand::Holders { asset } => {
+            let holders = list_asset_holders(&network.horizon_url, &asset).await?;
+            println!("{} holders of {}", holders.len(), asset);
+            for holder in &holders {
+                let state = match holder.authorization {
+                    TrustlineAuthorization::Authorized => "authorized",
+                    TrustlineAuthorization::AuthorizedToMaintainLiabilities => "maintain liabilities",
+                    TrustlineAuthorization::Deauthorized => "not authorized",
+                };
+                let clawback = if holder.clawback_enabled { ", clawback enabled" } else { "" };
+                println!("  {} {} ({}{})", holder.account_id, holder.balance, state, clawback);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_pool(network: &NetworkProfile, command: PoolCommand) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        PoolCommand::Id { asset_a, asset_b } => {
+            println!("{}", compute_pool_id(&asset_a, &asset_b)?);
+        }
+        PoolCommand::Info { pool_id, account } => {
+            let pool = get_pool(&network.horizon_url, &pool_id).await?;
+            println!("Pool {} (fee {} bp)", pool.id, pool.fee_bp);
+            for reserve in &pool.reserves {
+                println!("  Reserve: {} {}", reserve.amount, reserve.asset);
+            }
+            println!("  Total shares: {} ({} trustlines)", pool.total_shares, pool.total_trustlines);
+
+            if let Some(account) = account {
+                let position = get_pool_position(&network.horizon_url, &pool_id, &account).await?;
+                println!("Shares held by {}: {}", account, position.shares);
+                for reserve in &position.underlying {
+                    println!("  Worth: {} {}", reserve.amount, reserve.asset);
+                }
+            }
+        }
+        PoolCommand::Trust { asset_a, asset_b, remove, secret } => {
+            let limit = if remove { Some(Amount::default()) } else { None };
+            let xdr = StellarWalletTools::create_pool_trust_transaction(&secret, &asset_a, &asset_b, limit, network).await?;
+            print_xdr(&xdr, network);
+        }
+        PoolCommand::Deposit { pool_id, max_a, max_b, slippage_bps, secret } => {
+            let xdr = StellarWalletTools::create_pool_deposit_transaction(
+                &secret, &pool_id, max_a, max_b, slippage_bps, network,
+            )
+            .await?;
+            print_xdr(&xdr, network);
+        }
+        PoolCommand::Withdraw { pool_id, shares, slippage_bps, secret } => {
+            let xdr = StellarWalletTools::create_pool_withdraw_transaction(&secret, &pool_id, shares, slippage_bps, network).await?;
+            print_xdr(&xdr, network);
+        }
+    }
+    Ok(())
+}
+
+async fn run_tx(network: &NetworkProfile, command: TxCommand) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        TxCommand::Pay { to, amount, asset, via_path: false, secret, .. } => {
+            // Built against the selected network, with the destination's memo if it needs one.
+            let destination = resolve_destination(&to).await?;
+            let xdr = StellarWalletTools::create_payment_to_destination_transaction(
+                &secret, &destination, amount, &asset, network,
+            )
+            .await?;
+            print_xdr(&xdr, network);
+        }
+        TxCommand::Pay { to, amount, asset, via_path: true, send_asset, slippage_bps, secret } => {
+            let send_asset = send_asset.ok_or("--send-asset is required with --via-path")?;
+            let destination = resolve_destination(&to).await?;
+            let paths = find_strict_receive_paths(
+                &network.horizon_url,
+                &PathAssets::List(vec![send_asset]),
+                &asset,
+                amount,
+            )
+            .await?;
+            // The cheapest path is the one that costs the least to send.
+            let path = paths
+                .into_iter()
+                .min_by_key(|path| path.source_amount)
+                .ok_or("No payment path found")?;
+            println!(
+                "Paying {} {} with at most {} {} (quoted {})",
+                path.destination_amount,
+                path.destination_asset,
+                path.source_amount.plus_bps(slippage_bps),
+                path.source_asset,
+                path.source_amount,
+            );
+            let xdr = StellarWalletTools::create_path_payment_strict_receive_transaction(
+                &secret,
+                &destination.account_id,
+                &path,
+                slippage_bps,
+                destination.memo.as_ref(),
+                network,
+            )
+            .await?;
+            print_xdr(&xdr, network);
+        }
+        TxCommand::Swap { send, amount, receive, slippage_bps, secret } => {
+            let paths = find_strict_send_paths(
+                &network.horizon_url,
+                &send,
+                amount,
+                &PathAssets::List(vec![receive]),
+            )
+            .await?;
+            // The best path is the one that delivers the most.
+            let path = paths
+                .into_iter()
+                .max_by_key(|path| path.destination_amount)
+                .ok_or("No path found for this swap")?;
+            println!(
+                "Swapping {} {} for at least {} {} (quoted {})",
+                path.source_amount,
+                path.source_asset,
+                path.destination_amount.minus_bps(slippage_bps),
+                path.destination_asset,
+                path.destination_amount,
+            );
+            let own_account = StellarWalletTools::public_key_from_secret(&secret)?;
+            let xdr = StellarWalletTools::create_path_payment_strict_send_transaction(
+                &secret, &own_account, &path, slippage_bps, None, network,
+            )
+            .await?;
+            print_xdr(&xdr, network);
+        }
+    }
+    Ok(())
+}
+
+async fn run_uri(network: &NetworkProfile, command: UriCommand) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        UriCommand::Pay { to, amount, asset, memo, message } => {
+            let mut uri = StellarUri::pay(&to, asset, amount, memo.map(FederationMemo::Text)).network(network);
+            if let Some(message) = message {
+                uri = uri.message(&message);
+            }
+            let uri = uri.to_string();
+            println!("{}", uri);
+            show_qr("payment request", &uri);
+        }
+        UriCommand::Run { uri, amount, yes, secret } => {
+            let uri = StellarUri::parse(&uri)?;
+            match uri.verify_origin().await? {
+                Some(domain) => println!("Request from {} (signature verified)", domain),
+                None => println!("Request from an unknown source (unsigned)"),
+            }
+            if let Some(message) = &uri.message {
+                println!("Message: {}", message);
+            }
+            let mut foreign_source = false;
+            match &uri.request {
+                UriRequest::Pay { destination, amount: requested, asset, memo } => {
+                    let amount = requested.or(amount).ok_or("The request leaves the amount open; pass --amount")?;
+                    println!("Pay {} {} to {}", amount, asset, destination);
+                    if let Some(memo) = memo {
+                        println!("  Memo ({}): {}", memo.memo_type(), memo);
+                    }
+                }
+                UriRequest::Tx { pubkey, .. } => {
+                    let preview = uri.preview_transaction()?;
+                    println!("Sign a transaction from {} (sequence {}):", preview.source_account, preview.sequence);
+                    for (i, operation) in preview.operations.iter().enumerate() {
+                        println!("  {}. {}", i + 1, operation);
+                    }
+                    if let Some(pubkey) = pubkey {
+                        println!("  Expected signer: {}", pubkey);
+                    }
+                    // Someone else's transaction: only co-signing a multisig account needs this.
+                    if preview.source_account != StellarWalletTools::public_key_from_secret(&secret)? {
+                        println!("\n!!! WARNING: {} is not your account. Only sign if you co-sign for it !!!", preview.source_account);
+                        foreign_source = true;
+                    }
+                }
+            }
+            if let Some(callback) = &uri.callback {
+                println!("The signed transaction will be sent to {}, not submitted to the network", callback);
+            }
+            // --yes doesn't cover signing for another account.
+            let ask = !yes || foreign_source;
+            if ask && !confirm("Proceed?")? {
+                println!("Cancelled.");
+                return Ok(());
+            }
+            let xdr = StellarWalletTools::sign_uri_request(&uri, &secret, amount, network).await?;
+            match StellarWalletTools::submit_uri_transaction(&uri, &xdr, network).await? {
+                Some(hash) => println!("Submitted: {}", hash),
+                None => println!("Sent to the callback."),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Asks a yes/no question on the terminal; anything but "y"/"yes" is a no.
+fn confirm(question: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    use std::io::Write;
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Resolves a `G...` key or a federation address, reporting what the address maps to.
+async fn resolve_destination(destination: &str) -> Result<ResolvedDestination, Box<dyn std::error::Error>> {
+    let resolved = FederationResolver::new().resolve_destination(destination).await?;
+    if let Some(address) = &resolved.stellar_address {
+        println!("{} resolves to {}", address, resolved.account_id);
+        if let Some(memo) = &resolved.memo {
+            println!("  Required memo ({}): {}", memo.memo_type(), memo);
+        }
+    }
+    Ok(resolved)
+}
+
+fn print_xdr(xdr: &str, network: &NetworkProfile) {
+    println!("\nSigned Transaction XDR (Base64):");
+    println!("{}", xdr);
+    println!("\nSEP-7 request (open with a compatible wallet, or `wallet_app uri run`):");
+    let uri = StellarUri::tx(xdr).network(network).to_string();
+    println!("{}", uri);
+    show_qr("signed transaction", &uri);
+}
+
+/// Renders `data` as a QR code when `--qr` was given (requires the `qr` feature).
+pub fn show_qr(label: &str, data: &str) {
+    #[cfg(feature = "qr")]
+    if let Err(e) = crate::qr::show(label, data) {
+        eprintln!("Could not render the QR code: {}", e);
+    }
+    #[cfg(not(feature = "qr"))]
+    let _ = (label, data);
+}
+
+async fn watch(
+    account: &str,
+    horizon: &str,
+    cursor: Option<String>,
+    cursor_file: Option<PathBuf>,
+    exec: Option<String>,
+    all: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut watcher = PaymentWatcher::new(horizon, account);
+    if let Some(cursor) = cursor {
+        watcher = watcher.cursor(&cursor);
+    }
+    if let Some(path) = cursor_file {
+        watcher = watcher.cursor_file(path);
+    }
+
+    println!("Watching payments for {} (Ctrl+C to stop)...", account);
+    let mut payments = Box::pin(watcher.stream());
+    while let Some(item) = payments.next().await {
+        let payment = match item {
+            Ok(payment) => payment,
+            Err(e) => {
+                // The watcher reconnects by itself; just report the hiccup.
+                eprintln!("Stream error, reconnecting: {}", e);
+                continue;
+            }
+        };
+
+        if !all && !payment.is_incoming(account) {
+            continue;
+        }
+
+        match &exec {
+            Some(hook) => run_hook(hook, &payment).await?,
+            None => print_payment(&payment),
+        }
+    }
+    Ok(())
+}
+
+fn print_payment(payment: &PaymentEvent) {
+    let amount = payment
+        .amount
+        .as_deref()
+        .or(payment.starting_balance.as_deref())
+        .unwrap_or("-");
+    let from = payment.sender().unwrap_or("-");
+    println!(
+        "{} {} {} {} from {} (tx {})",
+        payment.created_at.as_deref().unwrap_or(""),
+        payment.kind,
+        amount,
+        payment.asset(),
+        from,
+        payment.transaction_hash,
+    );
+}
+
+async fn run_hook(hook: &str, payment: &PaymentEvent) -> Result<(), Box<dyn std::error::Error>> {
+    let status = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(hook)
+        .env("STELLAR_PAYMENT_ID", &payment.id)
+        .env("STELLAR_PAYMENT_TYPE", &payment.kind)
+        .env("STELLAR_PAYMENT_TX", &payment.transaction_hash)
+        .env("STELLAR_PAYMENT_FROM", payment.sender().unwrap_or(""))
+        .env("STELLAR_PAYMENT_TO", payment.recipient().unwrap_or(""))
+        .env("STELLAR_PAYMENT_AMOUNT", payment.amount.as_deref().or(payment.starting_balance.as_deref()).unwrap_or(""))
+        .env("STELLAR_PAYMENT_ASSET", payment.asset())
+        .env("STELLAR_PAYMENT_JSON", serde_json::to_string(payment)?)
+        .status()
+        .await?;
+
+    if !status.success() {
+        eprintln!("Hook exited with {} for payment {}", status, payment.id);
+    }
+    Ok(())
+}
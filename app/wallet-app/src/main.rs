@@ -1,10 +1,36 @@
 // Import the StellarWalletTools struct from your utility library
-use stellar_wallet_tools_lib::StellarWalletTools;
+use stellar_wallet_tools_lib::{NetworkProfile, StellarUri, StellarWalletTools};
 use tokio::main; // For async main function
 use std::io::{self, Write}; // For flush
+use clap::Parser; // For parsing subcommands
+
+mod commands; // Non-interactive subcommands (e.g. `watch`)
+#[cfg(feature = "qr")]
+mod qr; // QR code output (`--qr`)
 
 #[main]
 async fn main() {
+    // Run a subcommand directly if one was given, otherwise fall back to the interactive menu.
+    let cli = commands::Cli::parse();
+    let network = match cli.network_profile() {
+        Ok(network) => network,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    #[cfg(feature = "qr")]
+    if let Some(target) = cli.qr.clone() {
+        qr::set_target(target);
+    }
+    if let Some(command) = cli.command {
+        if let Err(e) = commands::run(&network, command).await {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     println!("--- Wallet Pilot Stellar Project CLI ---"); // Updated title
 
     loop {
@@ -29,10 +55,24 @@ async fn main() {
 
                         println!("\nPublic Key (G...):");
                         println!("\"{}\"", key_pair.public_key);
+                        commands::show_qr("public key", &key_pair.public_key);
 
                         println!("\nSecret Key (S...):");
                         println!("\"{}\"", key_pair.secret_key);
 
+                        if network.friendbot_url.is_some() {
+                            print!("\nFund this account with Friendbot on {}? (y/N): ", network.name);
+                            io::stdout().flush().expect("Failed to flush stdout");
+                            let mut answer = String::new();
+                            io::stdin().read_line(&mut answer).expect("Failed to read answer");
+                            if answer.trim().eq_ignore_ascii_case("y") {
+                                match StellarWalletTools::fund_with_friendbot(&key_pair.public_key, &network).await {
+                                    Ok(hash) => println!("Account funded (tx {})", hash),
+                                    Err(e) => eprintln!("Error funding account: {}", e),
+                                }
+                            }
+                        }
+
                         println!("\n!!! IMPORTANT SECURITY WARNING !!!");
                         println!("----------------------------------");
                         println!("NEVER SHARE YOUR SECRET KEY OR SEED PHRASE WITH ANYONE.");
@@ -97,7 +137,11 @@ async fn main() {
                         println!("Signed Transaction XDR (Base64):");
                         println!("{}", xdr);
                         println!("\nUse this XDR to submit the transaction to a Stellar Horizon endpoint.");
-                        println!("Example: https://laboratory.stellar.org/#txbuilder?xdr={}", xdr);
+                        // The menu builds for Testnet, so the request names that network.
+                        println!("Or open this SEP-7 request in a compatible wallet:");
+                        let uri = StellarUri::tx(&xdr).network(&NetworkProfile::testnet()).to_string();
+                        println!("{}", uri);
+                        commands::show_qr("signed transaction", &uri);
                     }
                     Err(e) => {
                         eprintln!("Error creating transaction: {}", e);
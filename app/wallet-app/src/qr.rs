@@ -0,0 +1,136 @@
+// E:\Rust\Projects\wallet\app\wallet_app\src\qr.rs
+// QR code output (`qr` feature): hands keys, SEP-7 requests and signed
+// transactions over to a mobile wallet.
+
+use qrcode::render::{svg, unicode};
+use qrcode::QrCode;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Where QR codes are rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QrTarget {
+    Terminal,
+    Png(PathBuf),
+    Svg(PathBuf),
+}
+
+impl FromStr for QrTarget {
+    type Err = String;
+
+    /// Parses `terminal`, or a file path ending in `.png` or `.svg`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        if lower == "terminal" {
+            Ok(QrTarget::Terminal)
+        } else if lower.ends_with(".png") {
+            Ok(QrTarget::Png(PathBuf::from(s)))
+        } else if lower.ends_with(".svg") {
+            Ok(QrTarget::Svg(PathBuf::from(s)))
+        } else {
+            Err(format!("'{}' is neither \"terminal\" nor a .png/.svg file", s))
+        }
+    }
+}
+
+impl QrTarget {
+    /// The target for one labeled code: files get the label as a suffix
+    /// (`key.svg` becomes `key-public-key.svg`), so codes don't overwrite each other.
+    pub fn for_label(&self, label: &str) -> QrTarget {
+        match self {
+            QrTarget::Terminal => QrTarget::Terminal,
+            QrTarget::Png(path) => QrTarget::Png(labeled_path(path, label)),
+            QrTarget::Svg(path) => QrTarget::Svg(labeled_path(path, label)),
+        }
+    }
+}
+
+fn labeled_path(path: &Path, label: &str) -> PathBuf {
+    let slug: String = label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}-{}.{}", stem, slug, extension))
+}
+
+/// The target chosen with `--qr`, set once at startup.
+static TARGET: OnceLock<QrTarget> = OnceLock::new();
+
+/// Selects where [`show`] renders QR codes.
+pub fn set_target(target: QrTarget) {
+    let _ = TARGET.set(target);
+}
+
+/// Renders `data` to the selected target; does nothing without `--qr`.
+pub fn show(label: &str, data: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(target) = TARGET.get() else {
+        return Ok(());
+    };
+    let target = target.for_label(label);
+    render(data, &target)?;
+    match &target {
+        QrTarget::Terminal => {}
+        QrTarget::Png(path) | QrTarget::Svg(path) => println!("QR code of the {} written to {}", label, path.display()),
+    }
+    Ok(())
+}
+
+/// Encodes `data` as a QR code and renders it to `target`.
+pub fn render(data: &str, target: &QrTarget) -> Result<(), Box<dyn std::error::Error>> {
+    let code = QrCode::new(data.as_bytes())?;
+    match target {
+        // Inverted colors: most terminals have a dark background, and scanners
+        // need dark modules on a light quiet zone.
+        QrTarget::Terminal => {
+            let rendered = code
+                .render::<unicode::Dense1x2>()
+                .dark_color(unicode::Dense1x2::Light)
+                .light_color(unicode::Dense1x2::Dark)
+                .build();
+            println!("{}", rendered);
+        }
+        QrTarget::Png(path) => {
+            code.render::<image::Luma<u8>>().min_dimensions(320, 320).build().save(path)?;
+        }
+        QrTarget::Svg(path) => {
+            let rendered = code.render::<svg::Color>().min_dimensions(320, 320).build();
+            std::fs::write(path, rendered)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr_target_from_str() {
+        assert_eq!("terminal".parse(), Ok(QrTarget::Terminal));
+        assert_eq!("Terminal".parse(), Ok(QrTarget::Terminal));
+        assert_eq!("out/key.PNG".parse(), Ok(QrTarget::Png(PathBuf::from("out/key.PNG"))));
+        assert_eq!("key.svg".parse(), Ok(QrTarget::Svg(PathBuf::from("key.svg"))));
+        assert!("key.jpg".parse::<QrTarget>().is_err());
+        assert!("".parse::<QrTarget>().is_err());
+    }
+
+    #[test]
+    fn test_qr_target_for_label() {
+        let target = QrTarget::Svg(PathBuf::from("out/key.svg"));
+        assert_eq!(target.for_label("public key"), QrTarget::Svg(PathBuf::from("out/key-public-key.svg")));
+        assert_eq!(QrTarget::Terminal.for_label("public key"), QrTarget::Terminal);
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let path = std::env::temp_dir().join(format!("qr-test-{}.svg", std::process::id()));
+        render("web+stellar:pay?destination=GABC", &QrTarget::Svg(path.clone())).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(svg.contains("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
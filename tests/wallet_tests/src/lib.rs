@@ -3,6 +3,14 @@
 use counter_contract::CounterContractClient; // Import the generated client for your contract
 use stellar_wallet_tools::StellarWalletTools; // Import Stellar wallet tools for testing its functionalities
 use soroban_sdk::{Env, testutils::EnvExt, symbol_short};
+use futures_util::StreamExt; // For pulling items out of the payment watcher stream
+use stellar_wallet_tools::{Amount, AssetId, Price};
+
+// Local HTTP stand-in for Horizon and anchor servers.
+#[cfg(test)]
+mod support;
+#[cfg(test)]
+use support::{MockServer, json_response, sse_response, text_response};
 
 // The `#[test]` attribute marks a function as a test.
 // These are integration tests that interact with the contract client.
@@ -97,12 +105,16 @@ async fn test_create_payment_transaction() {
     // It requires a live Testnet Horizon instance and valid, funded keys.
     // For true unit testing, you might mock the Horizon client.
 
-    // Use a test key pair (replace with actual funded testnet keys for a real test)
-    // For demonstration, we'll generate one, but it won't be funded on Testnet.
-    // A real test would require pre-funded accounts.
+    // Generate a fresh source account and have Friendbot create it on Testnet,
+    // so `get_account` can load its sequence number.
     let source_key_pair_result = StellarWalletTools::generate_key_pair();
     assert!(source_key_pair_result.is_ok());
     let source_key_pair = source_key_pair_result.unwrap();
+    let funding_result = StellarWalletTools::fund_with_friendbot(
+        &source_key_pair.public_key,
+        &stellar_wallet_tools::NetworkProfile::testnet(),
+    ).await;
+    assert!(funding_result.is_ok(), "Friendbot funding failed: {:?}", funding_result.err());
 
     let dest_key_pair_result = StellarWalletTools::generate_key_pair();
     assert!(dest_key_pair_result.is_ok());
@@ -126,9 +138,7 @@ async fn test_create_payment_transaction() {
     ).await; // Await the async function
 
     // Assert that the transaction creation was successful
-    // Note: This test will likely fail if the source_secret_key is not funded
-    // on the Testnet, as `get_account` will return an error.
-    // For a robust test, you'd need to mock the Horizon client or ensure funding.
+    // Note: This still requires Testnet (Horizon and Friendbot) to be reachable.
     assert!(transaction_result.is_ok(), "Transaction creation failed: {:?}", transaction_result.err());
 
     let xdr = transaction_result.unwrap();
@@ -136,4 +146,1938 @@ async fn test_create_payment_transaction() {
     // You could add more assertions here, e.g., decoding the XDR and verifying contents.
 }
 
-       
\ No newline at end of file
+// --- Tests for Friendbot funding ---
+
+// A network profile pointing at a local Friendbot stand-in.
+fn local_profile(friendbot_url: Option<String>) -> stellar_wallet_tools::NetworkProfile {
+    stellar_wallet_tools::NetworkProfile {
+        friendbot_url,
+        ..stellar_wallet_tools::NetworkProfile::local()
+    }
+}
+
+#[tokio::test]
+async fn test_fund_with_friendbot() {
+    let server = MockServer::start(|_| json_response(200, r#"{"hash": "f00dfeed"}"#)).await;
+    let key_pair = StellarWalletTools::generate_key_pair().unwrap();
+
+    let hash = StellarWalletTools::fund_with_friendbot(&key_pair.public_key, &local_profile(Some(server.url.clone())))
+        .await
+        .expect("Funding failed");
+    assert_eq!(hash, "f00dfeed");
+
+    // Friendbot is asked to fund exactly the generated account.
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "GET");
+    assert!(requests[0].path.ends_with(&format!("?addr={}", key_pair.public_key)));
+}
+
+#[tokio::test]
+async fn test_fund_with_friendbot_errors() {
+    let key_pair = StellarWalletTools::generate_key_pair().unwrap();
+
+    // Mainnet has no Friendbot.
+    let result = StellarWalletTools::fund_with_friendbot(&key_pair.public_key, &stellar_wallet_tools::NetworkProfile::public()).await;
+    assert!(result.is_err());
+
+    // An already funded account is rejected with Horizon's problem detail.
+    let server = MockServer::start(|_| {
+        json_response(400, r#"{"title": "Bad Request", "detail": "createAccountAlreadyExist (AAAAAAAAAGT/////AAAAAQAAAAAAAAAA/////AAAAAA=)"}"#)
+    })
+    .await;
+    let error = StellarWalletTools::fund_with_friendbot(&key_pair.public_key, &local_profile(Some(server.url.clone())))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("createAccountAlreadyExist"));
+}
+
+// --- Tests for the payment watcher ---
+
+const WATCHED_ACCOUNT: &str = "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7";
+
+// Builds a Horizon `payment` record as sent on the payments stream.
+fn payment_json(paging_token: &str, amount: &str) -> String {
+    serde_json::json!({
+        "id": paging_token,
+        "paging_token": paging_token,
+        "type": "payment",
+        "transaction_hash": format!("tx{}", paging_token),
+        "from": "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H",
+        "to": WATCHED_ACCOUNT,
+        "amount": amount,
+        "asset_type": "native"
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn test_payment_watcher_reconnects_from_last_cursor() {
+    // First connection delivers two payments and closes; the reconnect delivers a third.
+    let server = MockServer::start(|request| {
+        if request.path.contains("cursor=now") {
+            sse_response(&[("100", &payment_json("100", "1.5")), ("101", &payment_json("101", "2.0"))])
+        } else {
+            sse_response(&[("102", &payment_json("102", "3.0"))])
+        }
+    })
+    .await;
+
+    let mut payments = Box::pin(
+        stellar_wallet_tools::PaymentWatcher::new(&server.url, WATCHED_ACCOUNT)
+            .reconnect_delay(std::time::Duration::from_millis(10))
+            .stream(),
+    );
+
+    let mut amounts = Vec::new();
+    while amounts.len() < 3 {
+        let payment = payments.next().await.unwrap().expect("Stream error");
+        assert!(payment.is_incoming(WATCHED_ACCOUNT));
+        amounts.push(payment.amount.unwrap());
+    }
+    assert_eq!(amounts, vec!["1.5", "2.0", "3.0"]);
+
+    // The second connection must resume after the last payment seen.
+    let requests = server.requests();
+    assert!(requests[1].path.contains("cursor=101"), "Unexpected reconnect: {}", requests[1].path);
+    assert_eq!(requests[1].header("Accept"), Some("text/event-stream"));
+}
+
+#[tokio::test]
+async fn test_payment_watcher_persists_cursor() {
+    let server = MockServer::start(|_| sse_response(&[("200", &payment_json("200", "5"))])).await;
+    let cursor_file = std::env::temp_dir().join(format!("watcher-cursor-{}", std::process::id()));
+    std::fs::write(&cursor_file, "199").unwrap();
+
+    let mut payments = Box::pin(
+        stellar_wallet_tools::PaymentWatcher::new(&server.url, WATCHED_ACCOUNT)
+            .cursor_file(&cursor_file)
+            .reconnect_delay(std::time::Duration::from_millis(10))
+            .stream(),
+    );
+
+    // The saved cursor is used for the first request.
+    let first = payments.next().await.unwrap().expect("Stream error");
+    assert_eq!(first.paging_token, "200");
+    assert!(server.requests()[0].path.contains("cursor=199"));
+
+    // The cursor is written once the consumer asks for the next payment.
+    let _ = payments.next().await;
+    assert_eq!(std::fs::read_to_string(&cursor_file).unwrap(), "200");
+    let _ = std::fs::remove_file(&cursor_file);
+}
+
+#[tokio::test]
+async fn test_payment_watcher_account_merge() {
+    let merge = serde_json::json!({
+        "id": "300",
+        "paging_token": "300",
+        "type": "account_merge",
+        "transaction_hash": "tx300",
+        "account": "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H",
+        "into": WATCHED_ACCOUNT
+    })
+    .to_string();
+    let server = MockServer::start(move |_| sse_response(&[("300", &merge)])).await;
+
+    let mut payments = Box::pin(stellar_wallet_tools::PaymentWatcher::new(&server.url, WATCHED_ACCOUNT).stream());
+    let payment = payments.next().await.unwrap().expect("Stream error");
+    assert_eq!(payment.kind, "account_merge");
+    assert_eq!(payment.into.as_deref(), Some(WATCHED_ACCOUNT));
+    assert!(payment.is_incoming(WATCHED_ACCOUNT));
+    assert_eq!(payment.recipient(), Some(WATCHED_ACCOUNT));
+
+    // For the merged account, the same record is outgoing.
+    let merged = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+    assert_eq!(payment.sender(), Some(merged));
+    assert!(!payment.is_incoming(merged));
+}
+
+// --- Tests for amounts and path payments ---
+
+const USDC_ISSUER: &str = "GBBD47IF6LWK7P7MDEVSCWR7DPUWV3NY3DTQEVFL4NAT4AQH3ZLLFLA5";
+
+#[test]
+fn test_amount_parsing_and_formatting() {
+    let amount: Amount = "10.5".parse().unwrap();
+    assert_eq!(amount.stroops(), 105_000_000);
+    assert_eq!(amount.to_string(), "10.5000000");
+    assert_eq!("0.0000001".parse::<Amount>().unwrap().stroops(), 1);
+    assert_eq!(".25".parse::<Amount>().unwrap().to_string(), "0.2500000");
+
+    // More than 7 decimals, signs and garbage are rejected.
+    assert!("1.00000001".parse::<Amount>().is_err());
+    assert!("-1".parse::<Amount>().is_err());
+    assert!("1e5".parse::<Amount>().is_err());
+    assert!("".parse::<Amount>().is_err());
+}
+
+#[test]
+fn test_amount_slippage() {
+    let quote: Amount = "100".parse().unwrap();
+    // 1% below / above the quote.
+    assert_eq!(quote.minus_bps(100).to_string(), "99.0000000");
+    assert_eq!(quote.plus_bps(100).to_string(), "101.0000000");
+    // Rounding always goes in the safe direction.
+    let odd = Amount::from_stroops(3);
+    assert_eq!(odd.minus_bps(5000).stroops(), 1);
+    assert_eq!(odd.plus_bps(5000).stroops(), 5);
+}
+
+#[test]
+fn test_asset_id_parsing() {
+    assert_eq!("XLM".parse::<AssetId>().unwrap(), AssetId::Native);
+    let usdc: AssetId = format!("USDC:{}", USDC_ISSUER).parse().unwrap();
+    assert_eq!(usdc.asset_type(), "credit_alphanum4");
+    assert_eq!(usdc.to_string(), format!("USDC:{}", USDC_ISSUER));
+    assert!("USDC".parse::<AssetId>().is_err());
+    assert!("TOOLONGASSETCODE:GBBD".parse::<AssetId>().is_err());
+}
+
+#[tokio::test]
+async fn test_find_strict_send_paths() {
+    let body = serde_json::json!({
+        "_embedded": { "records": [{
+            "source_asset_type": "native",
+            "source_amount": "100.0000000",
+            "destination_asset_type": "credit_alphanum4",
+            "destination_asset_code": "USDC",
+            "destination_asset_issuer": USDC_ISSUER,
+            "destination_amount": "11.9876543",
+            "path": [{ "asset_type": "credit_alphanum4", "asset_code": "EURC", "asset_issuer": USDC_ISSUER }]
+        }]}
+    })
+    .to_string();
+    let server = MockServer::start(move |_| json_response(200, &body)).await;
+    let usdc: AssetId = format!("USDC:{}", USDC_ISSUER).parse().unwrap();
+
+    let paths = stellar_wallet_tools::path_payment::find_strict_send_paths(
+        &server.url,
+        &AssetId::Native,
+        "100".parse().unwrap(),
+        &stellar_wallet_tools::PathAssets::List(vec![usdc.clone()]),
+    )
+    .await
+    .expect("Path finding failed");
+
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].destination_asset, usdc);
+    assert_eq!(paths[0].destination_amount.to_string(), "11.9876543");
+    assert_eq!(paths[0].path.len(), 1);
+
+    let path = &server.requests()[0].path;
+    assert!(path.starts_with("/paths/strict-send?"));
+    assert!(path.contains("source_asset_type=native"));
+    assert!(path.contains("source_amount=100.0000000"));
+    assert!(path.contains(&format!("destination_assets=USDC%3A{}", USDC_ISSUER)));
+}
+
+// --- Tests for DEX offers ---
+
+#[test]
+fn test_price_from_decimal() {
+    assert_eq!(Price::from_decimal("1.25").unwrap(), Price { n: 5, d: 4 });
+    assert_eq!(Price::from_decimal("0.1").unwrap(), Price { n: 1, d: 10 });
+    assert_eq!(Price::from_decimal("3.14159").unwrap(), Price { n: 314159, d: 100000 });
+    assert_eq!("7/3".parse::<Price>().unwrap(), Price { n: 7, d: 3 });
+
+    // Too many significant digits: the closest 32-bit fraction is used.
+    let approx = Price::from_decimal("0.333333333333333333").unwrap();
+    assert!(approx.d > 0 && approx.n > 0);
+    assert_eq!(approx.to_decimal_string(), "0.3333333");
+
+    // Zero, negative and out-of-range prices are rejected.
+    assert!(Price::from_decimal("0").is_err());
+    assert!(Price::from_decimal("-1").is_err());
+    assert!(Price::from_decimal("0.0000000001").is_err());
+    assert!("1/0".parse::<Price>().is_err());
+}
+
+#[tokio::test]
+async fn test_list_offers_and_order_book() {
+    let offers = serde_json::json!({
+        "_embedded": { "records": [{
+            "id": "164943216",
+            "paging_token": "164943216",
+            "seller": WATCHED_ACCOUNT,
+            "selling": { "asset_type": "native" },
+            "buying": { "asset_type": "credit_alphanum4", "asset_code": "USDC", "asset_issuer": USDC_ISSUER },
+            "amount": "250.0000000",
+            "price_r": { "n": 3, "d": 25 },
+            "price": "0.1200000"
+        }]}
+    })
+    .to_string();
+    let book = serde_json::json!({
+        "bids": [{ "price_r": { "n": 1, "d": 10 }, "price": "0.1000000", "amount": "40.0000000" }],
+        "asks": [{ "price_r": { "n": 3, "d": 25 }, "price": "0.1200000", "amount": "250.0000000" }],
+        "base": { "asset_type": "native" },
+        "counter": { "asset_type": "credit_alphanum4", "asset_code": "USDC", "asset_issuer": USDC_ISSUER }
+    })
+    .to_string();
+    let server = MockServer::start(move |request| {
+        if request.path.starts_with("/order_book") {
+            json_response(200, &book)
+        } else {
+            json_response(200, &offers)
+        }
+    })
+    .await;
+    let usdc: AssetId = format!("USDC:{}", USDC_ISSUER).parse().unwrap();
+
+    let listed = stellar_wallet_tools::offers::list_offers(&server.url, WATCHED_ACCOUNT).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, 164943216);
+    assert_eq!(listed[0].selling, AssetId::Native);
+    assert_eq!(listed[0].buying, usdc);
+    assert_eq!(listed[0].price.to_decimal_string(), "0.1200000");
+    assert!(server.requests()[0].path.contains("limit=200"));
+
+    let order_book = stellar_wallet_tools::offers::order_book(&server.url, &AssetId::Native, &usdc, 20).await.unwrap();
+    assert_eq!(order_book.bids[0].amount.to_string(), "40.0000000");
+    assert_eq!(order_book.asks[0].price_r, Price { n: 3, d: 25 });
+    let request = &server.requests()[1].path;
+    assert!(request.contains("selling_asset_type=native"));
+    assert!(request.contains("buying_asset_code=USDC"));
+}
+
+// --- Tests for liquidity pools ---
+
+#[test]
+fn test_compute_pool_id() {
+    use stellar_wallet_tools::liquidity_pool::compute_pool_id;
+
+    let arst: AssetId = "ARST:GB7TAYRUZGE6TVT7NHP5SMIZRNQA6PLM423EYISAOAP3MKYIQMVYP2JO".parse().unwrap();
+    let usd: AssetId = "USD:GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ".parse().unwrap();
+
+    // Same vector as the reference JavaScript SDK; asset order must not matter.
+    let expected = "dd7b1ab831c273310ddbec6f97870aa83c2fbd78ce22aded37ecbf4f3380fac7";
+    assert_eq!(compute_pool_id(&arst, &usd).unwrap(), expected);
+    assert_eq!(compute_pool_id(&usd, &arst).unwrap(), expected);
+
+    // A pool needs two distinct assets.
+    assert!(compute_pool_id(&usd, &usd).is_err());
+}
+
+#[tokio::test]
+async fn test_pool_position_and_price_bounds() {
+    use stellar_wallet_tools::liquidity_pool::{deposit_price_bounds, get_pool_position};
+
+    let pool_id = "4cd1f6defba237eecbc5fefe259f89ebc4b5edd49116beb5536c4034fc48d63f";
+    let pool = serde_json::json!({
+        "id": pool_id,
+        "fee_bp": 30,
+        "type": "constant_product",
+        "total_trustlines": "12",
+        "total_shares": "1000.0000000",
+        "reserves": [
+            { "asset": "native", "amount": "5000.0000000" },
+            { "asset": format!("USDC:{}", USDC_ISSUER), "amount": "500.0000000" }
+        ]
+    })
+    .to_string();
+    let account = serde_json::json!({
+        "balances": [
+            { "asset_type": "liquidity_pool_shares", "liquidity_pool_id": pool_id, "balance": "100.0000000" },
+            { "asset_type": "native", "balance": "42.0000000" }
+        ]
+    })
+    .to_string();
+    let server = MockServer::start(move |request| {
+        if request.path.starts_with("/liquidity_pools/") {
+            json_response(200, &pool)
+        } else {
+            json_response(200, &account)
+        }
+    })
+    .await;
+
+    let position = get_pool_position(&server.url, pool_id, WATCHED_ACCOUNT).await.unwrap();
+    assert_eq!(position.shares.to_string(), "100.0000000");
+    // 10% of the shares is worth 10% of each reserve.
+    assert_eq!(position.underlying[0].amount.to_string(), "500.0000000");
+    assert_eq!(position.underlying[1].amount.to_string(), "50.0000000");
+
+    // Current price A/B is 10; 1% slippage gives 9.9 .. 10.1.
+    let (min, max) = deposit_price_bounds(&position.pool, 100).unwrap();
+    assert_eq!(min, Price { n: 99, d: 10 });
+    assert_eq!(max, Price { n: 101, d: 10 });
+}
+
+// --- Tests for claimable balances ---
+
+#[test]
+fn test_claim_predicate_evaluation() {
+    use stellar_wallet_tools::ClaimPredicate;
+
+    let created_at = 1_700_000_000;
+    // Claimable between one hour and one day after creation.
+    let window = ClaimPredicate::after_relative_time(3_600).and(ClaimPredicate::BeforeRelativeTime(86_400));
+    assert!(!window.is_satisfied(created_at + 60, created_at));
+    assert!(window.is_satisfied(created_at + 7_200, created_at));
+    assert!(!window.is_satisfied(created_at + 90_000, created_at));
+
+    // Either before an absolute deadline, or never again.
+    let deadline = ClaimPredicate::BeforeAbsoluteTime(created_at + 10).or(ClaimPredicate::Unconditional.negate());
+    assert!(deadline.is_satisfied(created_at, created_at));
+    assert!(!deadline.is_satisfied(created_at + 10, created_at));
+}
+
+#[tokio::test]
+async fn test_list_claimable_balances() {
+    const SECOND_CLAIMANT: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+    let balance_id = "00000000da0d57da7d4850e7fc10d2a9d0ebc731f7afb40574c03395b17d49149b91f5be";
+    let body = serde_json::json!({
+        "_embedded": { "records": [{
+            "id": balance_id,
+            "paging_token": "1-1",
+            "asset": format!("USDC:{}", USDC_ISSUER),
+            "amount": "25.0000000",
+            "sponsor": USDC_ISSUER,
+            "last_modified_time": "2021-07-16T12:30:05Z",
+            "claimants": [
+                { "destination": WATCHED_ACCOUNT, "predicate": { "not": { "abs_before": "2021-07-17T12:30:05Z", "abs_before_epoch": "1626525005" } } },
+                { "destination": USDC_ISSUER, "predicate": { "unconditional": true } },
+                { "destination": SECOND_CLAIMANT, "predicate": { "not": { "rel_before": "3600" } } }
+            ]
+        }]}
+    })
+    .to_string();
+    let server = MockServer::start(move |_| json_response(200, &body)).await;
+
+    let balances = stellar_wallet_tools::claimable_balance::list_claimable_balances(&server.url, WATCHED_ACCOUNT)
+        .await
+        .unwrap();
+    assert!(server.requests()[0].path.contains(&format!("claimant={}", WATCHED_ACCOUNT)));
+    assert!(server.requests()[0].path.contains("limit=200"));
+    assert_eq!(balances.len(), 1);
+    let balance = &balances[0];
+    assert_eq!(balance.id, balance_id);
+    assert_eq!(balance.amount.to_string(), "25.0000000");
+    assert_eq!(balance.claimants.len(), 3);
+
+    // The watched account may only claim after the absolute time; the issuer at any time.
+    assert!(!balance.is_claimable_by(WATCHED_ACCOUNT, 1_626_525_004));
+    assert!(balance.is_claimable_by(WATCHED_ACCOUNT, 1_626_525_005));
+    assert!(balance.is_claimable_by(USDC_ISSUER, 0));
+    // A negated relative time can't be evaluated without the creation time.
+    assert!(!balance.is_claimable_by(SECOND_CLAIMANT, 1_626_525_005));
+    assert!(!balance.is_claimable_by("GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ", 1_626_525_005));
+}
+
+#[tokio::test]
+async fn test_list_claimable_balances_follows_pages() {
+    let record = |token: usize| {
+        serde_json::json!({
+            "id": format!("00000000{:064x}", token),
+            "paging_token": token.to_string(),
+            "asset": "native",
+            "amount": "1.0000000",
+            "claimants": [{ "destination": WATCHED_ACCOUNT, "predicate": { "unconditional": true } }]
+        })
+    };
+    let server = MockServer::start(move |request| {
+        // A full first page, then one more balance after its last paging token.
+        let records: Vec<_> = if request.path.contains("cursor=199") {
+            vec![record(200)]
+        } else {
+            (0..200).map(record).collect()
+        };
+        json_response(200, &serde_json::json!({ "_embedded": { "records": records } }).to_string())
+    })
+    .await;
+
+    let balances = stellar_wallet_tools::claimable_balance::list_claimable_balances(&server.url, WATCHED_ACCOUNT)
+        .await
+        .unwrap();
+    assert_eq!(balances.len(), 201);
+    assert_eq!(server.requests().len(), 2);
+    assert!(server.requests()[1].path.contains("cursor=199"));
+}
+
+#[tokio::test]
+async fn test_claim_looks_up_balance_by_id() {
+    let balance_id = "00000000da0d57da7d4850e7fc10d2a9d0ebc731f7afb40574c03395b17d49149b91f5be";
+    let server = MockServer::start(|_| json_response(404, r#"{"title": "Resource Missing"}"#)).await;
+    let profile = stellar_wallet_tools::NetworkProfile { horizon_url: server.url.clone(), ..local_profile(None) };
+    let key_pair = StellarWalletTools::generate_key_pair().unwrap();
+
+    let error = StellarWalletTools::create_claim_transaction(&key_pair.secret_key, balance_id, &profile)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("not found"));
+    assert_eq!(server.requests()[0].path, format!("/claimable_balances/{}", balance_id));
+}
+
+// --- Tests for sponsored reserves ---
+
+#[test]
+fn test_sponsored_onboarding_operations() {
+    use stellar_wallet_tools::sponsorship::sponsored_onboarding_operations;
+
+    let sponsor = StellarWalletTools::generate_key_pair().unwrap();
+    let new_account = StellarWalletTools::generate_key_pair().unwrap();
+    let usdc: AssetId = format!("USDC:{}", USDC_ISSUER).parse().unwrap();
+
+    // Begin, create account, one trustline (XLM needs none), end.
+    let operations = sponsored_onboarding_operations(
+        &sponsor.public_key,
+        &new_account.public_key,
+        &[AssetId::Native, usdc],
+        Amount::default(),
+    )
+    .unwrap();
+    assert_eq!(operations.len(), 4);
+
+    // The sponsor opens the sandwich and creates the account; the new account
+    // adds its trustline and closes the sandwich.
+    use stellar_sdk::Operation;
+    let steps: Vec<(&str, Option<&str>)> = operations
+        .iter()
+        .map(|operation| match operation {
+            Operation::BeginSponsoringFutureReserves(op) => ("begin", op.source_account()),
+            Operation::CreateAccount(op) => ("create_account", op.source_account()),
+            Operation::ChangeTrust(op) => ("change_trust", op.source_account()),
+            Operation::EndSponsoringFutureReserves(op) => ("end", op.source_account()),
+            _ => ("other", None),
+        })
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            ("begin", Some(sponsor.public_key.as_str())),
+            ("create_account", Some(sponsor.public_key.as_str())),
+            ("change_trust", Some(new_account.public_key.as_str())),
+            ("end", Some(new_account.public_key.as_str())),
+        ]
+    );
+
+    // An account can't sponsor itself, and both accounts must be valid.
+    assert!(sponsored_onboarding_operations(&sponsor.public_key, &sponsor.public_key, &[], Amount::default()).is_err());
+    assert!(sponsored_onboarding_operations(&sponsor.public_key, "GINVALID", &[], Amount::default()).is_err());
+}
+
+// --- Tests for asset issuance ---
+
+fn issuance_request() -> stellar_wallet_tools::IssuanceRequest {
+    stellar_wallet_tools::IssuanceRequest {
+        asset_code: "NODUS".to_string(),
+        initial_supply: "1000000".parse().unwrap(),
+        flags: stellar_wallet_tools::IssuerFlags::default(),
+        lock_issuer: true,
+        home_domain: Some("nodus.example".to_string()),
+        funding: stellar_wallet_tools::issuance::AccountFunding::Friendbot,
+    }
+}
+
+#[test]
+fn test_issuance_request_validation() {
+    use stellar_wallet_tools::IssuerFlags;
+
+    assert!(issuance_request().validate().is_ok());
+
+    // Clawback needs revocable, and a locked issuer can't manage authorization.
+    let mut request = issuance_request();
+    request.lock_issuer = false;
+    request.flags = IssuerFlags { clawback_enabled: true, ..IssuerFlags::default() };
+    assert!(request.validate().is_err());
+    request.flags.auth_revocable = true;
+    assert!(request.validate().is_ok());
+    assert_eq!(request.flags.bits(), 0x2 | 0x8);
+    request.lock_issuer = true;
+    assert!(request.validate().is_err());
+
+    let mut request = issuance_request();
+    request.asset_code = "WAY-TOO-LONG-CODE".to_string();
+    assert!(request.validate().is_err());
+}
+
+#[test]
+fn test_issued_asset_currency_toml() {
+    let request = issuance_request();
+    let issuer = StellarWalletTools::generate_key_pair().unwrap();
+    let distributor = StellarWalletTools::generate_key_pair().unwrap();
+    let asset = stellar_wallet_tools::IssuedAsset {
+        asset: AssetId::credit("NODUS", &issuer.public_key).unwrap(),
+        issuer,
+        distributor,
+        initial_supply: request.initial_supply,
+        flags: request.flags,
+        locked: request.lock_issuer,
+        transaction_hash: "abc".to_string(),
+    };
+
+    let toml = asset.currency_toml(Some("Nodus \"test\" token"), None);
+    assert!(toml.starts_with("[[CURRENCIES]]\n"));
+    assert!(toml.contains("code=\"NODUS\"\n"));
+    assert!(toml.contains(&format!("issuer=\"{}\"\n", asset.issuer.public_key)));
+    assert!(toml.contains("name=\"Nodus \\\"test\\\" token\"\n"));
+    // A locked issuer means a fixed supply.
+    assert!(toml.contains("fixed_number=1000000\n"));
+    assert!(!toml.contains("regulated"));
+}
+
+// --- Tests for authorization and clawback ---
+
+#[test]
+fn test_trustline_authorization_flags() {
+    use stellar_wallet_tools::TrustlineAuthorization;
+
+    assert_eq!(TrustlineAuthorization::Authorized.flags(), (0x1, 0x2));
+    assert_eq!(TrustlineAuthorization::AuthorizedToMaintainLiabilities.flags(), (0x2, 0x1));
+    assert_eq!(TrustlineAuthorization::Deauthorized.flags(), (0, 0x3));
+}
+
+#[tokio::test]
+async fn test_list_asset_holders() {
+    use stellar_wallet_tools::TrustlineAuthorization;
+
+    let usdc: AssetId = format!("USDC:{}", USDC_ISSUER).parse().unwrap();
+    let body = serde_json::json!({
+        "_embedded": { "records": [
+            {
+                "account_id": WATCHED_ACCOUNT,
+                "paging_token": WATCHED_ACCOUNT,
+                "balances": [
+                    { "asset_type": "credit_alphanum4", "asset_code": "USDC", "asset_issuer": USDC_ISSUER,
+                      "balance": "12.5000000", "is_authorized": false,
+                      "is_authorized_to_maintain_liabilities": true, "is_clawback_enabled": true },
+                    { "asset_type": "native", "balance": "100.0000000" }
+                ]
+            },
+            {
+                "account_id": "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H",
+                "paging_token": "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H",
+                "balances": [
+                    { "asset_type": "credit_alphanum4", "asset_code": "USDC", "asset_issuer": USDC_ISSUER,
+                      "balance": "0.0000000", "is_authorized": true,
+                      "is_authorized_to_maintain_liabilities": true }
+                ]
+            }
+        ]}
+    })
+    .to_string();
+    let server = MockServer::start(move |_| json_response(200, &body)).await;
+
+    let holders = stellar_wallet_tools::authorization::list_asset_holders(&server.url, &usdc).await.unwrap();
+    assert!(server.requests()[0].path.contains("asset=USDC"));
+    assert_eq!(holders.len(), 2);
+    assert_eq!(holders[0].account_id, WATCHED_ACCOUNT);
+    assert_eq!(holders[0].balance.to_string(), "12.5000000");
+    assert_eq!(holders[0].authorization, TrustlineAuthorization::AuthorizedToMaintainLiabilities);
+    assert!(holders[0].clawback_enabled);
+    assert_eq!(holders[1].authorization, TrustlineAuthorization::Authorized);
+    assert!(!holders[1].clawback_enabled);
+
+    // Every account holds XLM, so listing its holders is refused.
+    assert!(stellar_wallet_tools::authorization::list_asset_holders(&server.url, &AssetId::Native).await.is_err());
+}
+
+// --- Tests for account data entries ---
+
+#[test]
+fn test_manage_data_validation() {
+    use stellar_wallet_tools::data::{decode_data_value, delete_data_operation, set_data_operation};
+
+    assert!(set_data_operation("config.version", b"2").is_ok());
+    assert!(set_data_operation(&"n".repeat(64), &[0u8; 64]).is_ok());
+    assert!(set_data_operation(&"n".repeat(65), b"1").is_err());
+    assert!(set_data_operation("config.version", &[0u8; 65]).is_err());
+    assert!(set_data_operation("config.version", b"").is_err());
+    assert!(delete_data_operation("").is_err());
+
+    assert_eq!(decode_data_value("AAEC/w==").unwrap(), vec![0, 1, 2, 255]);
+    assert!(decode_data_value("not base64!").is_err());
+}
+
+#[tokio::test]
+async fn test_list_data_entries() {
+    let body = serde_json::json!({
+        "id": WATCHED_ACCOUNT,
+        "account_id": WATCHED_ACCOUNT,
+        "data": { "config.version": "Mg==", "raw": "AAEC/w==" }
+    })
+    .to_string();
+    let server = MockServer::start(move |_| json_response(200, &body)).await;
+
+    let entries = stellar_wallet_tools::data::list_data_entries(&server.url, WATCHED_ACCOUNT).await.unwrap();
+    assert_eq!(server.requests()[0].path, format!("/accounts/{}", WATCHED_ACCOUNT));
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].name, "config.version");
+    assert_eq!(entries[0].value_str(), Some("2"));
+    assert_eq!(entries[1].value, vec![0, 1, 2, 255]);
+    assert_eq!(entries[1].value_base64(), "AAEC/w==");
+
+    let missing = stellar_wallet_tools::data::get_data_entry(&server.url, WATCHED_ACCOUNT, "absent").await.unwrap();
+    assert!(missing.is_none());
+}
+
+// --- Tests for sequence number management ---
+
+#[test]
+fn test_sequence_allocator_reserves_consecutive_numbers() {
+    use stellar_wallet_tools::SequenceAllocator;
+
+    let allocator = SequenceAllocator::new(WATCHED_ACCOUNT, 100);
+    assert_eq!(allocator.next(), 101);
+    let batch = allocator.reserve(3);
+    assert_eq!((batch.first, batch.last, batch.len()), (102, 104, 3));
+    assert_eq!(batch.iter().collect::<Vec<_>>(), vec![102, 103, 104]);
+    assert_eq!(allocator.current(), 104);
+
+    // Clones handed to parallel jobs share the counter and never collide.
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let allocator = allocator.clone();
+            std::thread::spawn(move || (0..25).map(|_| allocator.next()).collect::<Vec<_>>())
+        })
+        .collect();
+    let mut numbers: Vec<i64> = handles.into_iter().flat_map(|h| h.join().unwrap()).collect();
+    numbers.sort();
+    assert_eq!(numbers, (105..205).collect::<Vec<_>>());
+
+    assert!(stellar_wallet_tools::sequence::bump_sequence_operation(-1).is_err());
+}
+
+#[tokio::test]
+async fn test_sequence_allocator_load_and_resync() {
+    let sequence = std::sync::Arc::new(std::sync::atomic::AtomicI64::new(5_000));
+    let server_sequence = sequence.clone();
+    let server = MockServer::start(move |_| {
+        let body = serde_json::json!({
+            "account_id": WATCHED_ACCOUNT,
+            "sequence": server_sequence.load(std::sync::atomic::Ordering::SeqCst).to_string(),
+        });
+        json_response(200, &body.to_string())
+    })
+    .await;
+
+    let allocator = stellar_wallet_tools::SequenceAllocator::load(&server.url, WATCHED_ACCOUNT).await.unwrap();
+    assert_eq!(allocator.next(), 5_001);
+
+    // Another wallet moved the account forward; resyncing skips past it.
+    sequence.store(6_000, std::sync::atomic::Ordering::SeqCst);
+    assert_eq!(allocator.resync(&server.url).await.unwrap(), 6_000);
+    assert_eq!(allocator.next(), 6_001);
+
+    // Resyncing never goes back to numbers already handed out.
+    sequence.store(5_500, std::sync::atomic::Ordering::SeqCst);
+    assert_eq!(allocator.resync(&server.url).await.unwrap(), 6_001);
+}
+
+// --- Tests for pre-signed escrows ---
+
+fn escrow_terms() -> stellar_wallet_tools::EscrowTerms {
+    stellar_wallet_tools::EscrowTerms {
+        beneficiary: WATCHED_ACCOUNT.to_string(),
+        recovery_account: USDC_ISSUER.to_string(),
+        unlock_time: 1_800_000_000,
+        recovery_time: 1_800_086_400,
+    }
+}
+
+#[test]
+fn test_escrow_terms_validation() {
+    assert!(escrow_terms().validate().is_ok());
+
+    let mut terms = escrow_terms();
+    terms.recovery_time = terms.unlock_time;
+    assert!(terms.validate().is_err());
+
+    let mut terms = escrow_terms();
+    terms.beneficiary = "GINVALID".to_string();
+    assert!(terms.validate().is_err());
+}
+
+#[test]
+fn test_escrow_bundle_save_and_load() {
+    use stellar_wallet_tools::{EscrowBundle, PreSignedTransaction};
+
+    let hash = [7u8; 32];
+    let pre_signed = |valid_from| PreSignedTransaction {
+        xdr: "AAAA".to_string(),
+        hash: hash.iter().map(|b| format!("{:02x}", b)).collect(),
+        signer_key: stellar_wallet_tools::strkey::encode_pre_auth_tx(&hash),
+        sequence: 42,
+        valid_from,
+    };
+    let terms = escrow_terms();
+    let bundle = EscrowBundle {
+        escrow_account: WATCHED_ACCOUNT.to_string(),
+        network_passphrase: stellar_wallet_tools::network::TESTNET_PASSPHRASE.to_string(),
+        setup_xdr: "AAAA".to_string(),
+        release: pre_signed(terms.unlock_time),
+        recovery: pre_signed(terms.recovery_time),
+        terms,
+    };
+    assert!(bundle.release.signer_key.starts_with('T'));
+
+    let path = std::env::temp_dir().join(format!("escrow-bundle-{}.json", std::process::id()));
+    bundle.save(&path).unwrap();
+    let loaded = EscrowBundle::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, bundle);
+}
+
+#[test]
+fn test_build_escrow_bundle() {
+    use std::str::FromStr;
+    use stellar_sdk::{Keypair, Network, Operation, Transaction};
+
+    let key_pair = StellarWalletTools::generate_key_pair().unwrap();
+    let escrow_keypair = Keypair::from_secret(&key_pair.secret_key).unwrap();
+    let network = stellar_wallet_tools::NetworkProfile::testnet();
+    let terms = escrow_terms();
+    let bundle = stellar_wallet_tools::escrow::build_escrow_bundle(&escrow_keypair, 1_000, &terms, &network).unwrap();
+    let decode = |xdr: &str| Transaction::from_xdr_base64(xdr, Network::from_str(&network.network_passphrase).unwrap()).unwrap();
+
+    // The setup goes first; release and recovery share the next sequence number.
+    let setup = decode(&bundle.setup_xdr);
+    assert_eq!(setup.sequence_number(), 1_001);
+    for (pre_signed, valid_from) in [(&bundle.release, terms.unlock_time), (&bundle.recovery, terms.recovery_time)] {
+        let transaction = decode(&pre_signed.xdr);
+        assert_eq!(transaction.sequence_number(), 1_002);
+        assert_eq!(pre_signed.sequence, 1_002);
+        assert_eq!(transaction.time_bounds(), Some((valid_from, 0)));
+        assert_eq!(pre_signed.valid_from, valid_from);
+
+        // The pre-auth signer is the hash of exactly this transaction.
+        let hash = transaction.hash();
+        let computed = stellar_wallet_tools::escrow::transaction_hash(&pre_signed.xdr, &network.network_passphrase).unwrap();
+        assert_eq!(computed, hash);
+        assert_eq!(pre_signed.hash, hash.iter().map(|b| format!("{:02x}", b)).collect::<String>());
+        assert_eq!(pre_signed.signer_key, stellar_wallet_tools::strkey::encode_pre_auth_tx(&hash));
+    }
+
+    // The setup adds both signers and disables the master key.
+    let set_options: Vec<_> = setup
+        .operations()
+        .iter()
+        .filter_map(|operation| match operation {
+            Operation::SetOptions(op) => Some(op),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(set_options.len(), 3);
+    let signer_keys: Vec<String> = set_options.iter().filter_map(|op| op.signer().map(|s| s.key().to_string())).collect();
+    assert_eq!(signer_keys, vec![bundle.release.signer_key.clone(), bundle.recovery.signer_key.clone()]);
+    assert_eq!(set_options[2].master_weight(), Some(0));
+}
+
+// --- Tests for channel accounts ---
+
+#[test]
+fn test_channel_pool_round_robin() {
+    use stellar_wallet_tools::ChannelPool;
+
+    let main = StellarWalletTools::generate_key_pair().unwrap();
+    let channel_a = StellarWalletTools::generate_key_pair().unwrap();
+    let channel_b = StellarWalletTools::generate_key_pair().unwrap();
+    let pool = ChannelPool::new(
+        &main.public_key,
+        vec![(channel_a.secret_key.clone(), 10), (channel_b.secret_key.clone(), 20)],
+    )
+    .unwrap();
+
+    let picked: Vec<String> = (0..4).map(|_| pool.next_channel().public_key.clone()).collect();
+    assert_eq!(
+        picked,
+        vec![
+            channel_a.public_key.clone(),
+            channel_b.public_key.clone(),
+            channel_a.public_key.clone(),
+            channel_b.public_key.clone()
+        ]
+    );
+    assert_eq!(pool.channels()[0].current_sequence(), 10);
+    assert_eq!(pool.channels()[0].secret_key(), channel_a.secret_key);
+
+    // Debug output never shows the secret keys.
+    let debug = format!("{:?}", pool);
+    assert!(debug.contains(&channel_a.public_key));
+    assert!(!debug.contains(&channel_a.secret_key) && !debug.contains(&channel_b.secret_key));
+
+    // Empty pools and the main account as its own channel are rejected.
+    assert!(ChannelPool::new(&main.public_key, vec![]).is_err());
+    assert!(ChannelPool::new(&main.public_key, vec![(main.secret_key.clone(), 1)]).is_err());
+}
+
+// --- Tests for batch payouts ---
+
+fn temp_file(name: &str, contents: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_read_payment_rows() {
+    use stellar_wallet_tools::batch::read_payment_rows;
+
+    let csv = format!(
+        "destination,amount,asset,memo\n{},10.5,,salary\n{}, 3 ,USDC:{},\n",
+        WATCHED_ACCOUNT, USDC_ISSUER, USDC_ISSUER
+    );
+    let path = temp_file("payroll.csv", &csv);
+    let rows = read_payment_rows(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].asset, AssetId::Native);
+    assert_eq!(rows[0].memo, Some(stellar_wallet_tools::FederationMemo::Text("salary".to_string())));
+    assert_eq!(rows[1].amount.to_string(), "3.0000000");
+    assert_eq!(rows[1].memo, None);
+
+    let json = serde_json::json!([{ "destination": WATCHED_ACCOUNT, "amount": "1", "asset": "XLM" }]).to_string();
+    let path = temp_file("payroll.json", &json);
+    assert_eq!(read_payment_rows(&path).unwrap().len(), 1);
+    std::fs::remove_file(&path).unwrap();
+
+    // Every invalid row is reported, not just the first.
+    let csv = format!(
+        "destination,amount,asset,memo\nGINVALID,1,,\n{},-1,,\n{},1,,this memo is far longer than allowed\n",
+        WATCHED_ACCOUNT, WATCHED_ACCOUNT
+    );
+    let path = temp_file("invalid.csv", &csv);
+    let error = read_payment_rows(&path).unwrap_err().to_string();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.contains("row 1") && error.contains("row 2") && error.contains("row 3"));
+}
+
+#[test]
+fn test_plan_batch_transactions() {
+    use stellar_wallet_tools::batch::{plan_transactions, BatchPayout};
+    use stellar_wallet_tools::PaymentRow;
+
+    let row = |memo: Option<&str>| PaymentRow {
+        destination: WATCHED_ACCOUNT.to_string(),
+        amount: "1".parse().unwrap(),
+        asset: AssetId::Native,
+        memo: memo.map(|memo| stellar_wallet_tools::FederationMemo::Text(memo.to_string())),
+    };
+    let mut rows: Vec<PaymentRow> = (0..250).map(|_| row(None)).collect();
+    rows.insert(10, row(Some("invoice-1")));
+    rows.push(row(Some("invoice-1")));
+
+    // 250 memo-less payments need three transactions; the memo rows share a fourth.
+    let plan = plan_transactions(&rows);
+    assert_eq!(plan.iter().map(|t| t.rows.len()).collect::<Vec<_>>(), vec![100, 100, 50, 2]);
+    assert_eq!(plan[3].memo, Some(stellar_wallet_tools::FederationMemo::Text("invoice-1".to_string())));
+    assert_eq!(plan[3].rows, vec![10, 251]);
+
+    // A fresh payout reports every row as pending.
+    let state_path = std::env::temp_dir().join(format!("{}-batch.state.json", std::process::id()));
+    let payout = BatchPayout::prepare(rows, &state_path).unwrap();
+    assert!(!payout.is_complete());
+    assert!(payout.results().iter().all(|r| r.status == "pending" && r.transaction_hash.is_none()));
+
+    // A state file from another batch is refused.
+    std::fs::write(&state_path, r#"{"fingerprint":"other","transactions":[]}"#).unwrap();
+    assert!(BatchPayout::prepare(vec![row(None)], &state_path).is_err());
+    std::fs::remove_file(&state_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_batch_payout_run_resumes_and_rebuilds() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use stellar_wallet_tools::batch::{BatchPayout, BatchStatus};
+    use stellar_wallet_tools::PaymentRow;
+
+    let source = StellarWalletTools::generate_key_pair().unwrap();
+    let account = serde_json::json!({ "id": source.public_key, "account_id": source.public_key, "sequence": "100" }).to_string();
+    let submissions = std::sync::Arc::new(AtomicUsize::new(0));
+    let counter = submissions.clone();
+    let bad_seq = r#"{"title": "Transaction Failed", "extras": {"result_codes": {"transaction": "tx_bad_seq"}}}"#;
+    let server = MockServer::start(move |request| {
+        if request.method == "POST" {
+            match counter.fetch_add(1, Ordering::SeqCst) {
+                // The connection drops while the first envelope is in flight.
+                0 => String::new(),
+                // The second transaction's first two envelopes hit bad_seq.
+                1 | 2 => json_response(400, bad_seq),
+                _ => json_response(200, r#"{"hash": "rebuilt"}"#),
+            }
+        } else if request.path.starts_with("/transactions/") {
+            json_response(200, r#"{"successful": true}"#)
+        } else {
+            json_response(200, &account)
+        }
+    })
+    .await;
+    let profile = stellar_wallet_tools::NetworkProfile { horizon_url: server.url.clone(), ..local_profile(None) };
+
+    let row = |memo: &str| PaymentRow {
+        destination: WATCHED_ACCOUNT.to_string(),
+        amount: "1".parse().unwrap(),
+        asset: AssetId::Native,
+        memo: Some(stellar_wallet_tools::FederationMemo::Text(memo.to_string())),
+    };
+    let rows = vec![row("first"), row("second")];
+    let state_path = std::env::temp_dir().join(format!("{}-batch-run.state.json", std::process::id()));
+    let _ = std::fs::remove_file(&state_path);
+
+    // The network error stops the run and leaves the envelope in flight.
+    let mut payout = BatchPayout::prepare(rows.clone(), &state_path).unwrap();
+    assert!(payout.run(&source.secret_key, &profile).await.is_err());
+    let in_flight_hash = match &payout.transactions()[0].status {
+        BatchStatus::InFlight { hash, .. } => hash.clone(),
+        other => panic!("Unexpected status {:?}", other),
+    };
+
+    // On resume the envelope in flight is found in a ledger before anything is
+    // submitted; the second transaction is rebuilt after each bad_seq instead of
+    // being marked failed.
+    let mut payout = BatchPayout::prepare(rows, &state_path).unwrap();
+    payout.run(&source.secret_key, &profile).await.unwrap();
+    std::fs::remove_file(&state_path).unwrap();
+    assert_eq!(payout.transactions()[0].status, BatchStatus::Succeeded { hash: in_flight_hash.clone() });
+    assert_eq!(payout.transactions()[1].status, BatchStatus::Succeeded { hash: "rebuilt".to_string() });
+    assert!(payout.is_complete());
+
+    let requests = server.requests();
+    assert!(requests.iter().any(|r| r.path == format!("/transactions/{}", in_flight_hash)));
+    // Only the envelope in flight is looked up and it isn't resubmitted; the second
+    // transaction was built three times.
+    assert_eq!(requests.iter().filter(|r| r.method == "GET" && r.path.starts_with("/transactions/")).count(), 1);
+    assert_eq!(requests.iter().filter(|r| r.method == "POST").count(), 4);
+    assert_eq!(requests.iter().filter(|r| r.path.starts_with("/accounts/")).count(), 4);
+}
+
+#[tokio::test]
+async fn test_batch_payout_keeps_unknown_outcomes_in_flight() {
+    use stellar_wallet_tools::batch::{BatchPayout, BatchStatus};
+    use stellar_wallet_tools::PaymentRow;
+
+    let source = StellarWalletTools::generate_key_pair().unwrap();
+    let account = serde_json::json!({ "id": source.public_key, "account_id": source.public_key, "sequence": "100" }).to_string();
+    // Submissions time out, and the lookups of the envelope fail too.
+    let server = MockServer::start(move |request| {
+        if request.method == "POST" {
+            json_response(504, r#"{"title": "Timeout"}"#)
+        } else if request.path.starts_with("/transactions/") {
+            json_response(503, r#"{"title": "Service Unavailable"}"#)
+        } else {
+            json_response(200, &account)
+        }
+    })
+    .await;
+    let profile = stellar_wallet_tools::NetworkProfile { horizon_url: server.url.clone(), ..local_profile(None) };
+    let rows = vec![PaymentRow {
+        destination: WATCHED_ACCOUNT.to_string(),
+        amount: "1".parse().unwrap(),
+        asset: AssetId::Native,
+        memo: None,
+    }];
+    let state_path = std::env::temp_dir().join(format!("{}-batch-unknown.state.json", std::process::id()));
+    let _ = std::fs::remove_file(&state_path);
+
+    // A 504 doesn't mean the transaction failed: it may still be applied.
+    let mut payout = BatchPayout::prepare(rows.clone(), &state_path).unwrap();
+    assert!(payout.run(&source.secret_key, &profile).await.is_err());
+    let hash = match &payout.transactions()[0].status {
+        BatchStatus::InFlight { hash, .. } => hash.clone(),
+        other => panic!("Unexpected status {:?}", other),
+    };
+
+    // On resume the failed lookup stops the run before the envelope is resubmitted or rebuilt.
+    let mut payout = BatchPayout::prepare(rows, &state_path).unwrap();
+    assert!(payout.run(&source.secret_key, &profile).await.is_err());
+    std::fs::remove_file(&state_path).unwrap();
+    assert!(matches!(&payout.transactions()[0].status, BatchStatus::InFlight { hash: h, .. } if *h == hash));
+    assert_eq!(payout.results()[0].status, "pending");
+
+    let requests = server.requests();
+    assert_eq!(requests.iter().filter(|r| r.method == "POST").count(), 1);
+    assert!(requests.iter().any(|r| r.path == format!("/transactions/{}", hash)));
+}
+
+#[tokio::test]
+async fn test_batch_rows_resolve_federation_addresses() {
+    use stellar_wallet_tools::batch::{read_payment_rows, resolve_destinations, BatchPayout};
+    use stellar_wallet_tools::{FederationMemo, FederationResolver};
+
+    let server = federation_server().await;
+    let domain = server.url.trim_start_matches("http://").to_string();
+    let csv = format!(
+        "destination,amount,asset,memo\nalice*{0},1,,\nalice*{0},2,,\n{1},3,,\n",
+        domain, USDC_ISSUER
+    );
+    let path = temp_file("federated.csv", &csv);
+    let rows = read_payment_rows(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // Unresolved addresses can't be paid.
+    let state_path = std::env::temp_dir().join(format!("{}-federated.state.json", std::process::id()));
+    assert!(BatchPayout::prepare(rows.clone(), &state_path).is_err());
+
+    // Both rows for alice get her account and ID memo, from a single lookup.
+    let resolver = FederationResolver::new().insecure_http();
+    let resolved = resolve_destinations(rows.clone(), &resolver).await.unwrap();
+    assert_eq!(resolved[0].destination, WATCHED_ACCOUNT);
+    assert_eq!(resolved[1].memo, Some(FederationMemo::Id(42)));
+    assert_eq!(resolved[2].destination, USDC_ISSUER);
+    assert_eq!(resolved[2].memo, None);
+    assert_eq!(server.requests().iter().filter(|r| r.path.starts_with("/federation")).count(), 1);
+
+    // A row memo with the required value is kept, as the required ID memo.
+    let mut matching = rows.clone();
+    matching[0].memo = Some(FederationMemo::Text("42".to_string()));
+    let resolved = resolve_destinations(matching, &resolver).await.unwrap();
+    assert_eq!(resolved[0].memo, Some(FederationMemo::Id(42)));
+
+    // A row memo that contradicts the required one is refused.
+    let mut conflicting = rows;
+    conflicting[0].memo = Some(FederationMemo::Text("salary".to_string()));
+    let error = resolve_destinations(conflicting, &resolver).await.unwrap_err().to_string();
+    assert!(error.contains("row 1"));
+}
+
+// --- Tests for federation ---
+
+/// A domain serving stellar.toml and a federation endpoint that knows `alice`.
+async fn federation_server() -> MockServer {
+    MockServer::start(|request| {
+        if request.path == "/.well-known/stellar.toml" {
+            let host = request.header("host").unwrap_or_default();
+            let toml = format!("# test domain\nFEDERATION_SERVER=\"http://{}/federation\"\n", host);
+            return text_response(200, "text/plain", &toml);
+        }
+        if request.path.starts_with("/federation") && request.path.contains("q=alice") {
+            let body = serde_json::json!({
+                "stellar_address": "alice*example",
+                "account_id": WATCHED_ACCOUNT,
+                "memo_type": "id",
+                "memo": "42"
+            });
+            return json_response(200, &body.to_string());
+        }
+        json_response(404, r#"{"detail":"not found"}"#)
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_federation_resolution_and_cache() {
+    use stellar_wallet_tools::{FederationMemo, FederationResolver};
+
+    let server = federation_server().await;
+    let domain = server.url.trim_start_matches("http://").to_string();
+    let resolver = FederationResolver::new().insecure_http();
+
+    let address = format!("alice*{}", domain);
+    let resolved = resolver.resolve_destination(&address).await.unwrap();
+    assert_eq!(resolved.account_id, WATCHED_ACCOUNT);
+    assert_eq!(resolved.memo, Some(FederationMemo::Id(42)));
+    assert_eq!(resolved.stellar_address.as_deref(), Some(address.as_str()));
+    let federation_request = &server.requests()[1];
+    assert!(federation_request.path.contains("type=name"));
+
+    // The second lookup is served from the cache.
+    resolver.resolve(&address).await.unwrap();
+    assert_eq!(server.requests().len(), 2);
+
+    // Names are case-sensitive, so they are not folded into the same cache entry.
+    assert!(resolver.resolve(&format!("ALICE*{}", domain)).await.is_err());
+
+    // Unknown names fail; plain account IDs pass through without any request.
+    assert!(resolver.resolve(&format!("bob*{}", domain)).await.is_err());
+    let plain = resolver.resolve_destination(USDC_ISSUER).await.unwrap();
+    assert_eq!(plain.account_id, USDC_ISSUER);
+    assert!(plain.memo.is_none());
+    assert!(resolver.resolve_destination("not-an-account").await.is_err());
+}
+
+#[test]
+fn test_split_federation_address() {
+    use stellar_wallet_tools::federation::split_federation_address;
+
+    assert_eq!(split_federation_address("bob*stellar.org").unwrap(), ("bob", "stellar.org"));
+    // Names may contain '*' themselves; the domain follows the last one.
+    assert_eq!(split_federation_address("a*b*example.com").unwrap(), ("a*b", "example.com"));
+    assert!(split_federation_address("*example.com").is_err());
+    assert!(split_federation_address("bob*").is_err());
+}
+
+// --- Tests for stellar.toml ---
+
+fn sample_stellar_toml() -> String {
+    format!(
+        r#"
+VERSION="2.0.0"
+NETWORK_PASSPHRASE="Test SDF Network ; September 2015"
+FEDERATION_SERVER="https://example.com/federation"
+TRANSFER_SERVER_SEP0024="https://example.com/sep24"
+WEB_AUTH_ENDPOINT="http://example.com/auth"
+SIGNING_KEY="{signing_key}"
+ACCOUNTS=["{issuer}"]
+
+[DOCUMENTATION]
+ORG_NAME="Example Anchor"
+
+[[CURRENCIES]]
+code="USDC"
+issuer="{issuer}"
+display_decimals=2
+is_asset_anchored=true
+anchor_asset_type="fiat"
+
+[[CURRENCIES]]
+code="BAD"
+issuer="GNOTAKEY"
+
+[[VALIDATORS]]
+ALIAS="example-1"
+PUBLIC_KEY="{signing_key}"
+HOST="core.example.com:11625"
+"#,
+        signing_key = WATCHED_ACCOUNT,
+        issuer = USDC_ISSUER
+    )
+}
+
+#[test]
+fn test_parse_stellar_toml() {
+    use stellar_wallet_tools::StellarToml;
+
+    let toml = StellarToml::parse(&sample_stellar_toml()).unwrap();
+    assert_eq!(toml.network_passphrase.as_deref(), Some(stellar_wallet_tools::network::TESTNET_PASSPHRASE));
+    assert_eq!(toml.federation_server.as_deref(), Some("https://example.com/federation"));
+    assert_eq!(toml.signing_key.as_deref(), Some(WATCHED_ACCOUNT));
+    assert_eq!(toml.documentation.unwrap().org_name.as_deref(), Some("Example Anchor"));
+    assert_eq!(toml.currencies.len(), 2);
+    let usdc = toml.currency("USDC", USDC_ISSUER).unwrap();
+    assert_eq!(usdc.display_decimals, Some(2));
+    assert!(usdc.is_asset_anchored);
+    assert_eq!(toml.validators[0].alias.as_deref(), Some("example-1"));
+
+    // The plain-http auth endpoint and the bad issuer are reported.
+    let warnings = toml.warnings();
+    assert_eq!(warnings.len(), 2, "{:?}", warnings);
+    assert!(warnings[0].contains("WEB_AUTH_ENDPOINT"));
+    assert!(warnings[1].contains("BAD"));
+
+    assert!(StellarToml::parse("VERSION = [unclosed").is_err());
+}
+
+#[tokio::test]
+async fn test_fetch_stellar_toml() {
+    use stellar_wallet_tools::StellarTomlFetcher;
+
+    let toml = sample_stellar_toml();
+    let server = MockServer::start(move |_| text_response(200, "text/plain", &toml)).await;
+    let domain = server.url.trim_start_matches("http://").to_string();
+
+    let fetched = StellarTomlFetcher::new().insecure_http().fetch(&domain).await.unwrap();
+    assert_eq!(fetched.currencies.len(), 2);
+    assert_eq!(server.requests()[0].path, "/.well-known/stellar.toml");
+
+    // Files above the size limit are refused, and only domain names are accepted.
+    assert!(StellarTomlFetcher::new().insecure_http().max_size(64).fetch(&domain).await.is_err());
+    assert!(StellarTomlFetcher::new().url("example.com/evil").is_err());
+}
+
+// --- Tests for SEP-10 web authentication ---
+
+/// An unsigned JWT with the given claims (the client never verifies signatures).
+fn fake_jwt(claims: serde_json::Value) -> String {
+    use base64::Engine;
+    let encode = |value: &serde_json::Value| {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(value.to_string())
+    };
+    format!("{}.{}.signature", encode(&serde_json::json!({ "alg": "none" })), encode(&claims))
+}
+
+#[test]
+fn test_web_auth_token_claims() {
+    use stellar_wallet_tools::WebAuthToken;
+
+    let jwt = fake_jwt(serde_json::json!({ "sub": WATCHED_ACCOUNT, "exp": 1_700_000_600, "iat": 1_700_000_000 }));
+    let token = WebAuthToken::from_jwt(&jwt).unwrap();
+    assert_eq!(token.account, WATCHED_ACCOUNT);
+    assert_eq!(token.expires_at, Some(1_700_000_600));
+    assert!(!token.is_expired(1_700_000_599));
+    assert!(token.is_expired(1_700_000_600));
+    assert!(WebAuthToken::from_jwt("not-a-jwt").is_err());
+    // A token without a subject doesn't say which account it authenticates.
+    assert!(WebAuthToken::from_jwt(&fake_jwt(serde_json::json!({ "exp": 1_700_000_600 }))).is_err());
+}
+
+#[test]
+fn test_web_auth_client_from_stellar_toml() {
+    use stellar_wallet_tools::{StellarToml, WebAuthClient};
+
+    let network = stellar_wallet_tools::NetworkProfile::testnet();
+    let toml = StellarToml::parse(&sample_stellar_toml()).unwrap();
+    let client = WebAuthClient::from_stellar_toml("example.com", &toml, &network).unwrap();
+    assert_eq!(client.endpoint, "http://example.com/auth");
+    assert_eq!(client.server_signing_key, WATCHED_ACCOUNT);
+
+    // The anchor must publish a signing key and run on the same network.
+    let mut missing_key = toml.clone();
+    missing_key.signing_key = None;
+    assert!(WebAuthClient::from_stellar_toml("example.com", &missing_key, &network).is_err());
+    assert!(
+        WebAuthClient::from_stellar_toml("example.com", &toml, &stellar_wallet_tools::NetworkProfile::public()).is_err()
+    );
+}
+
+/// A Horizon stand-in knowing one multisig account: master and co-signer weigh 1, medium threshold 2.
+async fn multisig_horizon(account: String, co_signer: String) -> MockServer {
+    MockServer::start(move |request| {
+        if request.path != format!("/accounts/{}", account) {
+            return json_response(404, r#"{"title":"Resource Missing"}"#);
+        }
+        let body = serde_json::json!({
+            "account_id": account,
+            "signers": [
+                { "key": account, "weight": 1, "type": "ed25519_public_key" },
+                { "key": co_signer, "weight": 1, "type": "ed25519_public_key" }
+            ],
+            "thresholds": { "low_threshold": 1, "med_threshold": 2, "high_threshold": 2 }
+        });
+        json_response(200, &body.to_string())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_web_auth_challenge_roundtrip() {
+    use stellar_wallet_tools::{WebAuthClient, WebAuthServer};
+
+    let server_key = StellarWalletTools::generate_key_pair().unwrap();
+    let client = StellarWalletTools::generate_key_pair().unwrap();
+    let co_signer = StellarWalletTools::generate_key_pair().unwrap();
+    let horizon = multisig_horizon(client.public_key.clone(), co_signer.public_key.clone()).await;
+    let network = stellar_wallet_tools::NetworkProfile { horizon_url: horizon.url.clone(), ..local_profile(None) };
+
+    let server = WebAuthServer::new(&server_key.secret_key, &["example.com"], "https://auth.example.com/sep10", &network)
+        .unwrap();
+    let wallet = WebAuthClient {
+        home_domain: "example.com".to_string(),
+        endpoint: "https://auth.example.com/sep10".to_string(),
+        server_signing_key: server_key.public_key.clone(),
+        network_passphrase: network.network_passphrase.clone(),
+    };
+    let now = 1_700_000_000;
+
+    let challenge = server.build_challenge(&client.public_key, None, None, now).unwrap();
+    wallet.validate_challenge(&challenge, &client.public_key, now).unwrap();
+    // The challenge expires, and isn't valid for another account or home domain.
+    assert!(wallet.validate_challenge(&challenge, &client.public_key, now + 3_600).is_err());
+    assert!(wallet.validate_challenge(&challenge, &co_signer.public_key, now).is_err());
+    let other_domain = WebAuthClient { home_domain: "evil.com".to_string(), ..wallet.clone() };
+    assert!(other_domain.validate_challenge(&challenge, &client.public_key, now).is_err());
+
+    let sign = |xdr: &str, secret: &str| {
+        StellarWalletTools::sign_transaction(xdr, secret, &network.network_passphrase).unwrap()
+    };
+
+    // One signature doesn't reach the medium threshold of 2.
+    let signed_once = sign(&challenge, &client.secret_key);
+    assert!(server.verify_challenge(&signed_once, now).await.is_err());
+
+    let signed = sign(&signed_once, &co_signer.secret_key);
+    let authenticated = server.verify_challenge(&signed, now + 60).await.unwrap();
+    assert_eq!(authenticated.account, client.public_key);
+    assert_eq!(authenticated.home_domain, "example.com");
+    assert_eq!(authenticated.signers.len(), 2);
+
+    // Signatures from keys that aren't signers of the account are rejected.
+    let stranger = StellarWalletTools::generate_key_pair().unwrap();
+    let over_signed = sign(&signed, &stranger.secret_key);
+    assert!(server.verify_challenge(&over_signed, now).await.is_err());
+}
+
+#[test]
+fn test_web_auth_rejects_invalid_challenges() {
+    use base64::Engine;
+    use std::str::FromStr;
+    use stellar_sdk::{Account, Asset, Keypair, ManageDataOperation, Network, Operation, PaymentOperation, TransactionBuilder};
+    use stellar_wallet_tools::WebAuthClient;
+
+    let server_key = StellarWalletTools::generate_key_pair().unwrap();
+    let server_keypair = Keypair::from_secret(&server_key.secret_key).unwrap();
+    let client = StellarWalletTools::generate_key_pair().unwrap();
+    let network = local_profile(None);
+    let wallet = WebAuthClient {
+        home_domain: "example.com".to_string(),
+        endpoint: "https://auth.example.com/sep10".to_string(),
+        server_signing_key: server_key.public_key.clone(),
+        network_passphrase: network.network_passphrase.clone(),
+    };
+    let now = 1_700_000_000;
+
+    // Challenges are built by hand: `WebAuthServer` never breaks these rules.
+    let nonce = base64::engine::general_purpose::STANDARD.encode([7u8; 48]);
+    let auth = |source: &str, nonce: &str| -> Operation {
+        ManageDataOperation::new("example.com auth".to_string(), Some(nonce.as_bytes().to_vec()))
+            .with_source_account(source.to_string())
+            .into()
+    };
+    let data = |name: &str, value: &str, source: &str| -> Operation {
+        ManageDataOperation::new(name.to_string(), Some(value.as_bytes().to_vec()))
+            .with_source_account(source.to_string())
+            .into()
+    };
+    let web_auth_domain = |domain: &str| data("web_auth_domain", domain, &server_key.public_key);
+    let build = |sequence: i64, operations: Vec<Operation>, signed: bool| {
+        let source = Account::new(server_key.public_key.clone(), sequence - 1).unwrap();
+        let mut builder = TransactionBuilder::new(source, Network::from_str(&network.network_passphrase).unwrap())
+            .time_bounds(now, now + 900);
+        for operation in operations {
+            builder = builder.add_operation(operation);
+        }
+        let transaction = builder.build();
+        if signed { transaction.sign(&server_keypair).unwrap() } else { transaction }.to_xdr_base64()
+    };
+    let rejects = |challenge: String, reason: &str| {
+        let error = wallet.validate_challenge(&challenge, &client.public_key, now).unwrap_err();
+        assert!(error.to_string().contains(reason), "expected '{}', got '{}'", reason, error);
+    };
+
+    // The well-formed challenge passes, so each rejection below is down to one rule.
+    wallet
+        .validate_challenge(&build(0, vec![auth(&client.public_key, &nonce), web_auth_domain("auth.example.com")], true), &client.public_key, now)
+        .unwrap();
+
+    rejects(build(1, vec![auth(&client.public_key, &nonce), web_auth_domain("auth.example.com")], true), "sequence number must be 0");
+    let payment: Operation =
+        PaymentOperation::new(server_key.public_key.clone(), Asset::native(), "1".to_string()).into();
+    rejects(build(0, vec![auth(&client.public_key, &nonce), payment], true), "only manage_data operations");
+    rejects(build(0, vec![auth(&server_key.public_key, &nonce), web_auth_domain("auth.example.com")], true), "first operation's source");
+    rejects(build(0, vec![auth(&client.public_key, &nonce), web_auth_domain("evil.com")], true), "web_auth_domain doesn't match");
+    rejects(build(0, vec![auth(&client.public_key, "too-short"), web_auth_domain("auth.example.com")], true), "nonce");
+    rejects(build(0, vec![auth(&client.public_key, &nonce), web_auth_domain("auth.example.com")], false), "not signed by the server");
+    // The wallet never asks for client domain verification.
+    let client_domain = data("client_domain", "wallet.example.com", &client.public_key);
+    rejects(build(0, vec![auth(&client.public_key, &nonce), client_domain], true), "client_domain");
+}
+
+#[tokio::test]
+async fn test_web_auth_unfunded_account() {
+    use stellar_wallet_tools::WebAuthServer;
+
+    let server_key = StellarWalletTools::generate_key_pair().unwrap();
+    let client = StellarWalletTools::generate_key_pair().unwrap();
+    let horizon = MockServer::start(|_| json_response(404, r#"{"title":"Resource Missing"}"#)).await;
+    let network = stellar_wallet_tools::NetworkProfile { horizon_url: horizon.url.clone(), ..local_profile(None) };
+    let server = WebAuthServer::new(&server_key.secret_key, &["example.com"], "https://example.com/auth", &network)
+        .unwrap();
+
+    let now = 1_700_000_000;
+    let challenge = server.build_challenge(&client.public_key, Some("example.com"), Some(7), now).unwrap();
+    assert!(server.build_challenge(&client.public_key, Some("other.com"), None, now).is_err());
+
+    // Without the client's signature, the account isn't authenticated.
+    assert!(server.verify_challenge(&challenge, now).await.is_err());
+
+    let signed = StellarWalletTools::sign_transaction(&challenge, &client.secret_key, &network.network_passphrase).unwrap();
+    let authenticated = server.verify_challenge(&signed, now).await.unwrap();
+    assert_eq!(authenticated.account, client.public_key);
+    assert_eq!(authenticated.memo, Some(7));
+}
+
+// --- Tests for SEP-24 interactive transfers ---
+
+#[tokio::test]
+async fn test_interactive_withdrawal_flow() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use stellar_wallet_tools::{FederationMemo, InteractiveClient, InteractiveRequest, TransferStatus, WebAuthToken};
+
+    let jwt = fake_jwt(serde_json::json!({ "sub": WATCHED_ACCOUNT }));
+    let expected_auth = format!("Bearer {}", jwt);
+    let polls = AtomicUsize::new(0);
+    let anchor = MockServer::start(move |request| {
+        if request.path == "/info" {
+            return json_response(200, r#"{"deposit":{"USDC":{"enabled":true,"min_amount":1}},
+                "withdraw":{"USDC":{"enabled":true,"max_amount":"1000","fee_fixed":"0.5"}}}"#);
+        }
+        if request.header("authorization") != Some(expected_auth.as_str()) {
+            return json_response(403, r#"{"type":"authentication_required"}"#);
+        }
+        if request.path == "/transactions/withdraw/interactive" {
+            return json_response(200, r#"{"type":"interactive_customer_info_needed","url":"https://anchor.example/w/82","id":"82"}"#);
+        }
+        let status = if polls.fetch_add(1, Ordering::SeqCst) == 0 { "incomplete" } else { "pending_user_transfer_start" };
+        json_response(200, &format!(
+            r#"{{"transaction":{{"id":"82","kind":"withdrawal","status":"{}","amount_in":"25.5",
+                "withdraw_anchor_account":"{}","withdraw_memo":"1234","withdraw_memo_type":"id"}}}}"#,
+            status, USDC_ISSUER
+        ))
+    })
+    .await;
+
+    let client = InteractiveClient::new(&format!("{}/", anchor.url)).poll_interval(std::time::Duration::from_millis(10));
+    let info = client.info().await.unwrap();
+    assert_eq!(info.deposit["USDC"].min_amount, Some("1".parse().unwrap()));
+    assert_eq!(info.withdraw["USDC"].max_amount, Some("1000".parse().unwrap()));
+
+    // Everything except /info needs the SEP-10 token.
+    let asset = AssetId::credit("USDC", USDC_ISSUER).unwrap();
+    let request = InteractiveRequest::for_asset(&asset).amount("25.5".parse().unwrap());
+    let error = client.withdraw(&request).await.unwrap_err();
+    assert_eq!(error.downcast_ref::<stellar_wallet_tools::AnchorError>().unwrap().status, 403);
+
+    let token = WebAuthToken::from_jwt(&jwt).unwrap();
+    let client = client.with_token(token);
+    let started = client.withdraw(&request).await.unwrap();
+    assert_eq!(started.url, "https://anchor.example/w/82");
+    let body: serde_json::Value = serde_json::from_str(&anchor.requests().last().unwrap().body).unwrap();
+    assert_eq!(body["asset_issuer"], USDC_ISSUER);
+    assert_eq!(body["amount"], "25.5000000");
+
+    let transaction = client
+        .wait_for(&started.id, &[TransferStatus::PendingUserTransferStart], std::time::Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(transaction.status, TransferStatus::PendingUserTransferStart);
+    let (destination, amount) = transaction.withdrawal_payment().unwrap();
+    assert_eq!(destination.account_id, USDC_ISSUER);
+    assert_eq!(destination.memo, Some(FederationMemo::Id(1234)));
+    assert_eq!(amount, "25.5".parse().unwrap());
+}
+
+// --- Tests for SEP-6 programmatic transfers ---
+
+#[tokio::test]
+async fn test_transfer_deposit_and_withdraw() {
+    use stellar_wallet_tools::{DepositRequest, TransferClient, TransferOutcome, TransferStatus, WithdrawRequest};
+
+    let anchor = MockServer::start(|request| {
+        let path = request.path.as_str();
+        if path == "/info" {
+            json_response(200, r#"{"deposit":{"USDC":{"enabled":true,"fee_fixed":0.25}},
+                "withdraw":{"USDC":{"enabled":true,"types":{"bank_account":{"fields":{"dest":{"description":"IBAN"}}}}}},
+                "transactions":{"enabled":true,"authentication_required":true}}"#)
+        } else if path.starts_with("/deposit") && !path.contains("email_address=") {
+            json_response(403, r#"{"type":"non_interactive_customer_info_needed","fields":["first_name","email_address"]}"#)
+        } else if path.starts_with("/deposit") {
+            json_response(200, r#"{"id":"d-1","eta":3600,"instructions":{"organization.bank_account_number":
+                {"value":"DE89370400440532013000","description":"IBAN"}}}"#)
+        } else if path.starts_with("/withdraw") && path.contains("amount=") {
+            json_response(403, r#"{"type":"customer_info_status","status":"pending","eta":60}"#)
+        } else if path.starts_with("/withdraw") {
+            json_response(200, &format!(r#"{{"id":"w-1","account_id":"{}","memo_type":"text","memo":"w-1"}}"#, USDC_ISSUER))
+        } else if path.starts_with("/transactions?") {
+            json_response(200, r#"{"transactions":[{"id":"d-1","kind":"deposit","status":"pending_user_transfer_start"},
+                {"id":"w-0","kind":"withdrawal","status":"completed","amount_in":"10"}]}"#)
+        } else {
+            json_response(404, r#"{"error":"not found"}"#)
+        }
+    })
+    .await;
+    let client = TransferClient::new(&anchor.url);
+
+    let info = client.info().await.unwrap();
+    assert_eq!(info.deposit["USDC"].fee_fixed, Some("0.25".parse().unwrap()));
+    assert!(info.withdraw["USDC"].types["bank_account"].fields.contains_key("dest"));
+    assert!(info.transactions.unwrap().authentication_required);
+
+    let mut deposit = DepositRequest { asset_code: "USDC".to_string(), account: WATCHED_ACCOUNT.to_string(), ..Default::default() };
+    match client.deposit(&deposit).await.unwrap() {
+        TransferOutcome::CustomerInfoNeeded { fields } => assert_eq!(fields, vec!["first_name", "email_address"]),
+        other => panic!("unexpected outcome {:?}", other),
+    }
+    deposit.email_address = Some("alice@example.com".to_string());
+    match client.deposit(&deposit).await.unwrap() {
+        TransferOutcome::Ready(instructions) => {
+            assert_eq!(instructions.id.as_deref(), Some("d-1"));
+            assert_eq!(instructions.instructions["organization.bank_account_number"].value, "DE89370400440532013000");
+        }
+        other => panic!("unexpected outcome {:?}", other),
+    }
+
+    let mut withdraw = WithdrawRequest {
+        asset_code: "USDC".to_string(),
+        kind: "bank_account".to_string(),
+        amount: Some("10".parse().unwrap()),
+        ..Default::default()
+    };
+    match client.withdraw(&withdraw).await.unwrap() {
+        TransferOutcome::CustomerInfoStatus { status, eta, .. } => {
+            assert_eq!(status, "pending");
+            assert_eq!(eta, Some(60));
+        }
+        other => panic!("unexpected outcome {:?}", other),
+    }
+    withdraw.amount = None;
+    let TransferOutcome::Ready(instructions) = client.withdraw(&withdraw).await.unwrap() else {
+        panic!("expected withdrawal instructions");
+    };
+    let destination = instructions.destination().unwrap();
+    assert_eq!(destination.account_id, USDC_ISSUER);
+    assert_eq!(destination.memo, Some(stellar_wallet_tools::FederationMemo::Text("w-1".to_string())));
+
+    let transactions = client.transactions("USDC", None, Some(5)).await.unwrap();
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[0].status, TransferStatus::PendingUserTransferStart);
+    assert_eq!(transactions[1].amount_in, Some("10".parse().unwrap()));
+    assert!(anchor.requests().last().unwrap().path.contains("limit=5"));
+
+    // Other errors still come back as errors.
+    assert!(client.transaction("missing").await.is_err());
+}
+
+// --- Tests for SEP-12 customer information ---
+
+#[tokio::test]
+async fn test_kyc_customer_lifecycle() {
+    use stellar_wallet_tools::{CustomerClient, CustomerQuery, CustomerStatus, CustomerUpdate, FieldType};
+
+    let anchor = MockServer::start(|request| match request.method.as_str() {
+        "GET" => json_response(200, r#"{"status":"NEEDS_INFO","fields":{
+            "first_name":{"type":"string","description":"First name"},
+            "photo_id_front":{"type":"binary","description":"Front of the ID"},
+            "id_type":{"type":"string","choices":["passport","drivers_license"]},
+            "mobile_number":{"type":"string","optional":true}},
+            "provided_fields":{"last_name":{"type":"string","status":"REJECTED","error":"Doesn't match the ID"}}}"#),
+        "PUT" => json_response(202, r#"{"id":"c-42"}"#),
+        "DELETE" => text_response(200, "text/plain", ""),
+        _ => json_response(405, r#"{"error":"method not allowed"}"#),
+    })
+    .await;
+    let client = CustomerClient::new(&anchor.url);
+
+    let query = CustomerQuery { account: Some(WATCHED_ACCOUNT.to_string()), ..Default::default() };
+    let customer = client.get_customer(&query).await.unwrap();
+    assert_eq!(customer.status, CustomerStatus::NeedsInfo);
+    assert_eq!(customer.fields["photo_id_front"].kind, FieldType::Binary);
+    assert_eq!(customer.missing_fields(), vec!["first_name", "id_type", "photo_id_front"]);
+    assert_eq!(customer.rejected_fields(), vec!["last_name"]);
+    assert!(anchor.requests()[0].path.contains(&format!("account={}", WATCHED_ACCOUNT)));
+
+    let bad = CustomerUpdate::new(query.clone()).text("id_type", "library_card").text("photo_id_front", "not a file");
+    assert_eq!(bad.check(&customer).len(), 2);
+
+    let update = CustomerUpdate::new(query)
+        .binary("photo_id_front", "id.png", Some("image/png"), b"PNGDATA".to_vec())
+        .text("first_name", "Alice")
+        .text("id_type", "passport");
+    assert!(update.check(&customer).is_empty());
+    assert_eq!(client.put_customer(&update).await.unwrap(), "c-42");
+
+    // Text parts (identity first) are sent before the file.
+    let body = anchor.requests()[1].body.clone();
+    let account_at = body.find("name=\"account\"").unwrap();
+    let first_name_at = body.find("name=\"first_name\"").unwrap();
+    let file_at = body.find("name=\"photo_id_front\"; filename=\"id.png\"").unwrap();
+    assert!(account_at < first_name_at && first_name_at < file_at);
+    assert!(body.contains("PNGDATA"));
+
+    client.delete_customer(WATCHED_ACCOUNT, None, None).await.unwrap();
+    let delete = &anchor.requests()[2];
+    assert_eq!(delete.method, "DELETE");
+    assert_eq!(delete.path, format!("/customer/{}", WATCHED_ACCOUNT));
+}
+
+// --- Tests for SEP-31 cross-border payments ---
+
+#[tokio::test]
+async fn test_direct_payment_sender_flow() {
+    use stellar_wallet_tools::{DirectPaymentClient, DirectPaymentOutcome, DirectPaymentRequest, FederationMemo, TransferStatus};
+
+    let anchor = MockServer::start(|request| {
+        if request.path == "/info" {
+            return json_response(200, r#"{"receive":{
+                "USDC":{"enabled":true,"quotes_supported":true,"min_amount":"1","max_amount":"10000",
+                    "sep12":{"sender":{"types":{"sep31-sender":{"description":"KYC'd business"}}},
+                             "receiver":{"types":{"sep31-receiver":{"description":"bank account holder"}}}}},
+                "EURC":{"enabled":true,"quotes_required":true}}}"#);
+        }
+        if request.method == "POST" {
+            if request.body.contains("\"receiver_id\":\"r-unknown\"") {
+                return json_response(400, r#"{"error":"customer_info_needed","type":"sep31-receiver"}"#);
+            }
+            return json_response(201, r#"{"id":"t-1"}"#);
+        }
+        json_response(200, &format!(
+            r#"{{"transaction":{{"id":"t-1","status":"pending_sender","amount_in":"100","amount_in_asset":"stellar:USDC:{}",
+                "stellar_account_id":"{}","stellar_memo_type":"hash","stellar_memo":"YWJjZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXoxMjM0NTY="}}}}"#,
+            USDC_ISSUER, USDC_ISSUER
+        ))
+    })
+    .await;
+    let client = DirectPaymentClient::new(&anchor.url);
+
+    let info = client.info().await.unwrap();
+    assert!(info.receive["USDC"].sep12.receiver.types.contains_key("sep31-receiver"));
+
+    let mut request = DirectPaymentRequest {
+        amount: "100".parse().unwrap(),
+        asset_code: "USDC".to_string(),
+        asset_issuer: Some(USDC_ISSUER.to_string()),
+        sender_id: "s-1".to_string(),
+        receiver_id: "r-unknown".to_string(),
+        ..Default::default()
+    };
+    match client.create_transaction(&request, Some(&info)).await.unwrap() {
+        DirectPaymentOutcome::CustomerInfoNeeded { customer_type } => assert_eq!(customer_type.as_deref(), Some("sep31-receiver")),
+        other => panic!("unexpected outcome {:?}", other),
+    }
+
+    // Requests breaking the anchor's limits are refused before anything is sent.
+    let sent = anchor.requests().len();
+    let too_large = DirectPaymentRequest { amount: "20000".parse().unwrap(), ..request.clone() };
+    assert!(client.create_transaction(&too_large, Some(&info)).await.is_err());
+    let without_quote = DirectPaymentRequest { asset_code: "EURC".to_string(), ..request.clone() };
+    assert!(client.create_transaction(&without_quote, Some(&info)).await.is_err());
+    assert_eq!(anchor.requests().len(), sent);
+
+    request.receiver_id = "r-1".to_string();
+    let DirectPaymentOutcome::Created { id } = client.create_transaction(&request, Some(&info)).await.unwrap() else {
+        panic!("expected a created transaction");
+    };
+    let transaction = client
+        .wait_for(&id, &[TransferStatus::PendingSender], std::time::Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(anchor.requests().last().unwrap().path, "/transactions/t-1");
+    let (destination, amount) = transaction.payment().unwrap();
+    assert_eq!(destination.account_id, USDC_ISSUER);
+    assert!(matches!(destination.memo, Some(FederationMemo::Hash(_))));
+    assert_eq!(amount, "100".parse().unwrap());
+}
+
+#[tokio::test]
+async fn test_send_direct_payment() {
+    use std::str::FromStr;
+    use stellar_sdk::{Memo, Network, Transaction};
+    use stellar_wallet_tools::DirectPaymentTransaction;
+
+    let source = StellarWalletTools::generate_key_pair().unwrap();
+    let account = serde_json::json!({ "id": source.public_key, "account_id": source.public_key, "sequence": "100" }).to_string();
+    let horizon = MockServer::start(move |request| {
+        if request.method == "POST" {
+            json_response(200, r#"{"hash": "sent"}"#)
+        } else {
+            json_response(200, &account)
+        }
+    })
+    .await;
+    let network = stellar_wallet_tools::NetworkProfile { horizon_url: horizon.url.clone(), ..local_profile(None) };
+    let anchor_transaction = |status: &str| -> DirectPaymentTransaction {
+        serde_json::from_value(serde_json::json!({
+            "id": "t-1", "status": status, "amount_in": "100",
+            "stellar_account_id": WATCHED_ACCOUNT, "stellar_memo_type": "hash",
+            "stellar_memo": "YWJjZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXoxMjM0NTY="
+        }))
+        .unwrap()
+    };
+
+    // Nothing is sent for a transaction that isn't waiting for the sender.
+    let usdc = AssetId::credit("USDC", USDC_ISSUER).unwrap();
+    let pending_receiver = anchor_transaction("pending_receiver");
+    assert!(StellarWalletTools::send_direct_payment(&source.secret_key, &pending_receiver, &usdc, &network).await.is_err());
+    assert!(horizon.requests().is_empty());
+
+    let pending_sender = anchor_transaction("pending_sender");
+    let hash = StellarWalletTools::send_direct_payment(&source.secret_key, &pending_sender, &usdc, &network).await.unwrap();
+    assert_eq!(hash, "sent");
+
+    // One payment from the source account, carrying the anchor's hash memo.
+    let submitted = horizon.requests().into_iter().find(|r| r.method == "POST").unwrap();
+    assert_eq!(submitted.path, "/transactions");
+    let xdr = submitted.body.trim_start_matches("tx=").replace("%2B", "+").replace("%2F", "/").replace("%3D", "=");
+    let transaction = Transaction::from_xdr_base64(&xdr, Network::from_str(&network.network_passphrase).unwrap()).unwrap();
+    assert_eq!(transaction.source_account().to_string(), source.public_key);
+    assert_eq!(transaction.sequence_number(), 101);
+    assert_eq!(transaction.operations().len(), 1);
+    assert!(matches!(transaction.memo(), Memo::Hash(_)));
+}
+
+// --- Tests for RFC 3339 timestamps ---
+
+#[test]
+fn test_rfc3339_timestamps() {
+    use stellar_wallet_tools::time::{format_rfc3339, parse_rfc3339};
+
+    assert_eq!(parse_rfc3339("2023-11-14T22:13:20Z"), Some(1_700_000_000));
+    assert_eq!(format_rfc3339(1_700_000_000), "2023-11-14T22:13:20Z");
+    // Offsets are ahead of (+) or behind (-) UTC; fractions of a second are dropped.
+    assert_eq!(parse_rfc3339("2023-11-14T22:13:20+00:00"), Some(1_700_000_000));
+    assert_eq!(parse_rfc3339("2023-11-14T16:43:20.250-05:30"), Some(1_700_000_000));
+    assert_eq!(parse_rfc3339("2023-11-15T00:13:20+02:00"), Some(1_700_000_000));
+    assert_eq!(parse_rfc3339("2023-11-14T22:13:20"), None);
+    assert_eq!(parse_rfc3339("2023-11-14T22:13:20+0200"), None);
+}
+
+// --- Tests for SEP-38 quotes ---
+
+#[tokio::test]
+async fn test_quotes_and_path_comparison() {
+    use std::cmp::Ordering;
+    use stellar_wallet_tools::{PaymentPath, QuoteAsset, QuoteClient, QuoteRequest};
+
+    let usdc = format!("stellar:USDC:{}", USDC_ISSUER);
+    let quote_body = format!(
+        r#"{{"id":"q-1","expires_at":"2023-11-14T22:23:20Z","total_price":"0.1010101","price":"0.1",
+            "sell_asset":"{}","sell_amount":"100","buy_asset":"stellar:native","buy_amount":"990",
+            "fee":{{"total":"1","asset":"{}","details":[{{"name":"Service fee","amount":"1"}}]}}}}"#,
+        usdc, usdc
+    );
+    let info_body = format!(
+        r#"{{"assets":[{{"asset":"{}"}},{{"asset":"iso4217:BRL","country_codes":["BR"],
+            "buy_delivery_methods":[{{"name":"PIX","description":"Instant transfer"}}]}}]}}"#,
+        usdc
+    );
+    let anchor = MockServer::start(move |request| {
+        let path = request.path.as_str();
+        if path == "/info" {
+            json_response(200, &info_body)
+        } else if path.starts_with("/prices?") {
+            json_response(200, r#"{"buy_assets":[{"asset":"iso4217:BRL","price":"0.18","decimals":2}]}"#)
+        } else if path.starts_with("/price?") {
+            json_response(200, r#"{"total_price":"0.2","price":"0.18","sell_amount":"100","buy_amount":"500",
+                "fee":{"total":"10","asset":"iso4217:BRL"}}"#)
+        } else {
+            json_response(if request.method == "POST" { 201 } else { 200 }, &quote_body)
+        }
+    })
+    .await;
+    let client = QuoteClient::new(&anchor.url);
+
+    let assets = client.info().await.unwrap();
+    assert_eq!(assets[0].asset.stellar(), Some(&AssetId::credit("USDC", USDC_ISSUER).unwrap()));
+    assert_eq!(assets[1].asset, QuoteAsset::OffChain("iso4217:BRL".to_string()));
+    assert_eq!(assets[1].buy_delivery_methods[0].name, "PIX");
+
+    let sell: QuoteAsset = usdc.parse().unwrap();
+    let prices = client.prices(&sell, "100".parse().unwrap()).await.unwrap();
+    assert_eq!(prices[0].decimals, Some(2));
+
+    let brl: QuoteAsset = "iso4217:BRL".parse().unwrap();
+    let estimate = client
+        .price(&QuoteRequest::sell(sell.clone(), "100".parse().unwrap(), brl, "sep31"))
+        .await
+        .unwrap();
+    assert_eq!(estimate.buy_amount, "500".parse().unwrap());
+    assert!(anchor.requests().last().unwrap().path.contains("context=sep31"));
+
+    let mut request = QuoteRequest::sell(sell.clone(), "100".parse().unwrap(), "stellar:native".parse().unwrap(), "sep6");
+    request.expire_after = Some(1_700_000_000);
+    let quote = client.quote(&request).await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&anchor.requests().last().unwrap().body).unwrap();
+    assert_eq!(body["expire_after"], "2023-11-14T22:13:20Z");
+    assert_eq!(body["sell_asset"], usdc.as_str());
+    assert_eq!(quote.expires_at, 1_700_000_600);
+    assert_eq!(quote.seconds_left(1_700_000_000), 600);
+    assert!(quote.is_expired(1_700_000_600));
+    assert_eq!(quote.fee.details[0].amount, Some("1".parse().unwrap()));
+    assert_eq!(client.get_quote("q-1").await.unwrap().id, "q-1");
+
+    // Both amounts, or neither, is refused locally.
+    request.buy_amount = Some("1".parse().unwrap());
+    assert!(client.quote(&request).await.is_err());
+
+    // 100 USDC -> 990 XLM beats a path giving 980 XLM, loses to one giving 1000.
+    let path = |received: &str| PaymentPath {
+        source_asset: AssetId::credit("USDC", USDC_ISSUER).unwrap(),
+        source_amount: "100".parse().unwrap(),
+        destination_asset: AssetId::Native,
+        destination_amount: received.parse().unwrap(),
+        path: vec![],
+    };
+    assert_eq!(quote.compare_with_path(&path("980")), Some(Ordering::Greater));
+    assert_eq!(quote.compare_with_path(&path("1000")), Some(Ordering::Less));
+    let other_pair = PaymentPath { destination_asset: AssetId::credit("USDC", USDC_ISSUER).unwrap(), ..path("990") };
+    assert_eq!(quote.compare_with_path(&other_pair), None);
+}
+
+// --- Tests for SEP-7 URIs ---
+
+#[test]
+fn test_stellar_uri_pay_roundtrip() {
+    use stellar_wallet_tools::{FederationMemo, StellarUri, UriRequest};
+
+    let asset = AssetId::credit("USDC", USDC_ISSUER).unwrap();
+    let uri = StellarUri::pay(WATCHED_ACCOUNT, asset.clone(), Some("12.5".parse().unwrap()), Some(FederationMemo::Id(7)))
+        .network(&local_profile(None))
+        .message("Invoice #42 & fees");
+    let text = uri.to_string();
+    assert!(text.starts_with(&format!("web+stellar:pay?destination={}&amount=12.5000000", WATCHED_ACCOUNT)));
+    assert!(text.contains("msg=Invoice%20%2342%20%26%20fees"));
+
+    let parsed = StellarUri::parse(&text).unwrap();
+    assert_eq!(parsed.message.as_deref(), Some("Invoice #42 & fees"));
+    assert_eq!(parsed.network_passphrase(), local_profile(None).network_passphrase);
+    assert_eq!(
+        parsed.request,
+        UriRequest::Pay {
+            destination: WATCHED_ACCOUNT.to_string(),
+            amount: Some("12.5".parse().unwrap()),
+            asset,
+            memo: Some(FederationMemo::Id(7)),
+        }
+    );
+    assert_eq!(parsed.to_string(), text);
+
+    // The public network is implied when no passphrase is given.
+    let public = StellarUri::parse(&format!("web+stellar:pay?destination={}", WATCHED_ACCOUNT)).unwrap();
+    assert_eq!(public.network_passphrase(), stellar_wallet_tools::NetworkProfile::public().network_passphrase);
+
+    assert!(StellarUri::parse("https://example.com/pay").is_err());
+    assert!(StellarUri::parse("web+stellar:pay?amount=1").is_err());
+    assert!(StellarUri::parse("web+stellar:tx?xdr=AAAA&callback=https%3A%2F%2Fexample.com").is_err());
+    assert!(StellarUri::parse(&format!("web+stellar:pay?destination={}&origin_domain=example.com", WATCHED_ACCOUNT)).is_err());
+    // Return memos would otherwise be sent, and re-serialized, as hash memos.
+    let memo_return = format!("web+stellar:pay?destination={}&memo=YWJj&memo_type=MEMO_RETURN", WATCHED_ACCOUNT);
+    assert!(StellarUri::parse(&memo_return).is_err());
+}
+
+#[test]
+fn test_stellar_uri_transaction_preview() {
+    use std::str::FromStr;
+    use stellar_sdk::{Account, Asset, Network, PaymentOperation, TransactionBuilder};
+    use stellar_wallet_tools::StellarUri;
+
+    let source = StellarWalletTools::generate_key_pair().unwrap();
+    let network = local_profile(None);
+    let transaction = TransactionBuilder::new(
+        Account::new(source.public_key.clone(), 41).unwrap(),
+        Network::from_str(&network.network_passphrase).unwrap(),
+    )
+    .add_operation(PaymentOperation::new(WATCHED_ACCOUNT.to_string(), Asset::native(), "5".to_string()).into())
+    .build();
+
+    let uri = StellarUri::parse(&StellarUri::tx(&transaction.to_xdr_base64()).network(&network).to_string()).unwrap();
+    let preview = uri.preview_transaction().unwrap();
+    assert_eq!(preview.source_account, source.public_key);
+    assert_eq!(preview.sequence, 42);
+    assert_eq!(preview.operations.len(), 1);
+
+    // Payment requests have no envelope to show.
+    assert!(StellarUri::pay(WATCHED_ACCOUNT, AssetId::Native, None, None).preview_transaction().is_err());
+}
+
+#[tokio::test]
+async fn test_stellar_uri_origin_signature() {
+    use stellar_wallet_tools::{StellarTomlFetcher, StellarUri};
+
+    let signing = StellarWalletTools::generate_key_pair().unwrap();
+    let signing_key = signing.public_key.clone();
+    let server = MockServer::start(move |_| {
+        text_response(200, "text/plain", &format!("URI_REQUEST_SIGNING_KEY=\"{}\"\n", signing_key))
+    })
+    .await;
+    let domain = server.url.trim_start_matches("http://").to_string();
+    let fetcher = StellarTomlFetcher::new().insecure_http();
+
+    let signed = StellarUri::tx("AAAAAgAAAAB+base64/xdr==")
+        .callback("https://example.com/sign")
+        .sign(&domain, &signing.secret_key)
+        .unwrap();
+    let text = signed.to_string();
+    assert!(text.contains("xdr=AAAAAgAAAAB%2Bbase64%2Fxdr%3D%3D"));
+    assert!(text.contains("&signature="));
+
+    let parsed = StellarUri::parse(&text).unwrap();
+    assert_eq!(parsed.callback.as_deref(), Some("https://example.com/sign"));
+    assert_eq!(parsed.verify_origin_with(&fetcher).await.unwrap(), Some(domain.clone()));
+
+    // Any change to the signed part breaks the signature.
+    let tampered = StellarUri::parse(&text.replace("example.com%2Fsign", "evil.com%2Fsign")).unwrap();
+    assert!(tampered.verify_origin_with(&fetcher).await.is_err());
+    let other = StellarWalletTools::generate_key_pair().unwrap();
+    assert!(parsed.verify_signature(&other.public_key).is_err());
+
+    // Unsigned requests have no origin to verify.
+    assert_eq!(StellarUri::tx("AAAA").verify_origin_with(&fetcher).await.unwrap(), None);
+}
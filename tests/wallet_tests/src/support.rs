@@ -0,0 +1,147 @@
+// E:\Rust\Projects\wallet\tests\wallet_tests\src\support.rs
+// A tiny local HTTP stand-in used by the tests instead of a live Horizon or anchor server.
+
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A request received by the [`MockServer`].
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    /// Path including the query string, e.g. `/accounts/G.../payments?cursor=now`.
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockRequest {
+    /// Returns the value of the header `name` (case-insensitive), if present.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Serves one canned response per connection and records every request it sees.
+pub struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    /// Starts the server on a random local port. `handler` receives each request
+    /// and returns the complete raw HTTP response to send back.
+    pub async fn start<F>(handler: F) -> MockServer
+    where
+        F: Fn(&MockRequest) -> String + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Failed to bind mock server");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler = Arc::new(handler);
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let recorded = recorded.clone();
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    if let Some(request) = read_request(&mut socket).await {
+                        let response = handler(&request);
+                        recorded.lock().unwrap().push(request);
+                        let _ = socket.write_all(response.as_bytes()).await;
+                        let _ = socket.shutdown().await;
+                    }
+                });
+            }
+        });
+
+        MockServer { url, requests }
+    }
+
+    /// All requests received so far, in arrival order.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn read_request(socket: &mut tokio::net::TcpStream) -> Option<MockRequest> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+
+    // Read until the end of the headers.
+    let header_end = loop {
+        let n = socket.read(&mut buf).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        data.extend_from_slice(&buf[..n]);
+        if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&data[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+
+    // Read the body, if the client announced one.
+    let content_length = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    while data.len() < header_end + content_length {
+        let n = socket.read(&mut buf).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..n]);
+    }
+    let body = String::from_utf8_lossy(&data[header_end..]).to_string();
+
+    Some(MockRequest { method, path, headers, body })
+}
+
+/// Builds a complete HTTP response with the given status and JSON body.
+pub fn json_response(status: u16, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// Builds a complete HTTP response with the given content type and body.
+pub fn text_response(status: u16, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {} OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// Builds a server-sent events response that delivers `events` and then closes,
+/// the way Horizon ends a stream. Each event is `(id, data)`.
+pub fn sse_response(events: &[(&str, &str)]) -> String {
+    let mut body = String::from("retry: 10\ndata: \"hello\"\n\n");
+    for (id, data) in events {
+        body.push_str(&format!("id: {}\ndata: {}\n\n", id, data));
+    }
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n{}",
+        body
+    )
+}
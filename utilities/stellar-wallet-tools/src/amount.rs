@@ -0,0 +1,115 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\amount.rs
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Number of stroops in one unit of an asset (Stellar amounts have 7 decimals).
+pub const STROOPS_PER_UNIT: i64 = 10_000_000;
+
+/// An asset amount, stored as an integer number of stroops to avoid floating point errors.
+///
+/// Parses from and formats to the decimal strings used by Horizon (e.g. `"10.5000000"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Amount(i64);
+
+/// Error returned when a decimal amount string can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAmountError(String);
+
+impl fmt::Display for ParseAmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid amount '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseAmountError {}
+
+impl Amount {
+    /// The largest amount representable on the ledger.
+    pub const MAX: Amount = Amount(i64::MAX);
+
+    /// Creates an amount from a number of stroops.
+    pub fn from_stroops(stroops: i64) -> Self {
+        Amount(stroops)
+    }
+
+    /// Returns the amount in stroops.
+    pub fn stroops(self) -> i64 {
+        self.0
+    }
+
+    /// Adds two amounts, returning `None` on overflow.
+    pub fn checked_add(self, other: Amount) -> Option<Amount> {
+        self.0.checked_add(other.0).map(Amount)
+    }
+
+    /// Subtracts `other`, returning `None` on overflow.
+    pub fn checked_sub(self, other: Amount) -> Option<Amount> {
+        self.0.checked_sub(other.0).map(Amount)
+    }
+
+    /// Lowers the amount by `bps` basis points (1/100 of a percent), rounding down.
+    /// Used to derive a minimum amount to receive from a quote.
+    pub fn minus_bps(self, bps: u32) -> Amount {
+        let scaled = self.0 as i128 * (10_000 - bps.min(10_000) as i128) / 10_000;
+        Amount(scaled as i64)
+    }
+
+    /// Raises the amount by `bps` basis points, rounding up and saturating at [`Amount::MAX`].
+    /// Used to derive a maximum amount to send from a quote.
+    pub fn plus_bps(self, bps: u32) -> Amount {
+        let scaled = (self.0 as i128 * (10_000 + bps as i128) + 9_999) / 10_000;
+        Amount(scaled.min(i64::MAX as i128) as i64)
+    }
+}
+
+impl FromStr for Amount {
+    type Err = ParseAmountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseAmountError(s.to_string());
+        let trimmed = s.trim();
+        let (whole, fraction) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+
+        // Only plain, non-negative decimals with at most 7 fractional digits are valid.
+        if (whole.is_empty() && fraction.is_empty())
+            || fraction.len() > 7
+            || !whole.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(error());
+        }
+
+        let whole: i64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| error())? };
+        let fraction: i64 = format!("{:0<7}", fraction).parse().map_err(|_| error())?;
+
+        whole
+            .checked_mul(STROOPS_PER_UNIT)
+            .and_then(|stroops| stroops.checked_add(fraction))
+            .map(Amount)
+            .ok_or_else(error)
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let unit = STROOPS_PER_UNIT as u64;
+        write!(f, "{}{}.{:07}", sign, abs / unit, abs % unit)
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,274 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\anchor.rs
+// Shared plumbing for anchor APIs (SEP-6, 12, 24, 31, 38): authenticated
+// requests, error documents and the anchors' loose amount encoding.
+
+use crate::amount::Amount;
+use crate::federation::{FederationMemo, ResolvedDestination};
+use crate::transfer::TransferField;
+use crate::web_auth::WebAuthToken;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// An error answer from an anchor: the HTTP status and the JSON body, whose
+/// `error` field usually explains the problem.
+#[derive(Debug, Clone)]
+pub struct AnchorError {
+    pub status: u16,
+    pub message: String,
+    /// The full response body, for answers that carry more than a message
+    /// (e.g. SEP-6 "customer info needed").
+    pub body: serde_json::Value,
+}
+
+impl fmt::Display for AnchorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Anchor request failed ({}): {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for AnchorError {}
+
+/// Adds the SEP-10 token, if any, to a request.
+pub(crate) fn authorize(request: reqwest::RequestBuilder, token: Option<&WebAuthToken>) -> reqwest::RequestBuilder {
+    match token {
+        Some(token) => request.bearer_auth(&token.token),
+        None => request,
+    }
+}
+
+/// Sends a request and decodes the JSON answer; non-success answers become an [`AnchorError`].
+pub(crate) async fn send_json<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T, Box<dyn std::error::Error>> {
+    let response = request.send().await?;
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(Box::new(AnchorError {
+            status: status.as_u16(),
+            message: body["error"].as_str().unwrap_or("unknown error").to_string(),
+            body,
+        }));
+    }
+    Ok(serde_json::from_value(body)?)
+}
+
+/// Joins an endpoint path to a server base URL, tolerating a trailing slash.
+pub(crate) fn endpoint(base_url: &str, path: &str) -> String {
+    format!("{}/{}", base_url.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+/// Deserializes an optional amount that anchors send either as a string or a number.
+pub(crate) fn optional_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Amount>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Loose {
+        Text(String),
+        Number(serde_json::Number),
+    }
+    let text = match Option::<Loose>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(Loose::Text(text)) if text.is_empty() => return Ok(None),
+        Some(Loose::Text(text)) => text,
+        Some(Loose::Number(number)) => number.to_string(),
+    };
+    text.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+/// Polls an anchor transaction with `fetch` until its status is one of `statuses`
+/// or terminal, sleeping `poll_interval` between requests.
+pub(crate) async fn wait_for<T, Fut>(
+    id: &str,
+    statuses: &[TransferStatus],
+    poll_interval: Duration,
+    timeout: Duration,
+    status_of: impl Fn(&T) -> &TransferStatus,
+    fetch: impl Fn() -> Fut,
+) -> Result<T, Box<dyn std::error::Error>>
+where
+    Fut: Future<Output = Result<T, Box<dyn std::error::Error>>>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        let transaction = fetch().await?;
+        let status = status_of(&transaction);
+        if status.is_terminal() || statuses.contains(status) {
+            return Ok(transaction);
+        }
+        if Instant::now() + poll_interval > deadline {
+            return Err(format!("Timed out waiting for transaction {} (status '{}')", id, status).into());
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// Status of an anchor transaction (SEP-6, SEP-24 and SEP-31 share most values).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TransferStatus {
+    Incomplete,
+    PendingUserTransferStart,
+    PendingUserTransferComplete,
+    PendingExternal,
+    PendingAnchor,
+    PendingStellar,
+    PendingTrust,
+    PendingUser,
+    PendingSender,
+    PendingReceiver,
+    PendingCustomerInfoUpdate,
+    PendingTransactionInfoUpdate,
+    Completed,
+    Refunded,
+    Expired,
+    NoMarket,
+    TooSmall,
+    TooLarge,
+    Error,
+    /// A status this library doesn't know about.
+    Other(String),
+}
+
+impl TransferStatus {
+    /// The status as written by anchors, e.g. `pending_user_transfer_start`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            TransferStatus::Incomplete => "incomplete",
+            TransferStatus::PendingUserTransferStart => "pending_user_transfer_start",
+            TransferStatus::PendingUserTransferComplete => "pending_user_transfer_complete",
+            TransferStatus::PendingExternal => "pending_external",
+            TransferStatus::PendingAnchor => "pending_anchor",
+            TransferStatus::PendingStellar => "pending_stellar",
+            TransferStatus::PendingTrust => "pending_trust",
+            TransferStatus::PendingUser => "pending_user",
+            TransferStatus::PendingSender => "pending_sender",
+            TransferStatus::PendingReceiver => "pending_receiver",
+            TransferStatus::PendingCustomerInfoUpdate => "pending_customer_info_update",
+            TransferStatus::PendingTransactionInfoUpdate => "pending_transaction_info_update",
+            TransferStatus::Completed => "completed",
+            TransferStatus::Refunded => "refunded",
+            TransferStatus::Expired => "expired",
+            TransferStatus::NoMarket => "no_market",
+            TransferStatus::TooSmall => "too_small",
+            TransferStatus::TooLarge => "too_large",
+            TransferStatus::Error => "error",
+            TransferStatus::Other(status) => status,
+        }
+    }
+
+    /// Whether the transaction is finished and its status won't change anymore.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TransferStatus::Completed
+                | TransferStatus::Refunded
+                | TransferStatus::Expired
+                | TransferStatus::NoMarket
+                | TransferStatus::TooSmall
+                | TransferStatus::TooLarge
+                | TransferStatus::Error
+        )
+    }
+}
+
+impl From<&str> for TransferStatus {
+    fn from(status: &str) -> Self {
+        match status {
+            "incomplete" => TransferStatus::Incomplete,
+            "pending_user_transfer_start" => TransferStatus::PendingUserTransferStart,
+            "pending_user_transfer_complete" => TransferStatus::PendingUserTransferComplete,
+            "pending_external" => TransferStatus::PendingExternal,
+            "pending_anchor" => TransferStatus::PendingAnchor,
+            "pending_stellar" => TransferStatus::PendingStellar,
+            "pending_trust" => TransferStatus::PendingTrust,
+            "pending_user" => TransferStatus::PendingUser,
+            "pending_sender" => TransferStatus::PendingSender,
+            "pending_receiver" => TransferStatus::PendingReceiver,
+            "pending_customer_info_update" => TransferStatus::PendingCustomerInfoUpdate,
+            "pending_transaction_info_update" => TransferStatus::PendingTransactionInfoUpdate,
+            "completed" => TransferStatus::Completed,
+            "refunded" => TransferStatus::Refunded,
+            "expired" => TransferStatus::Expired,
+            "no_market" => TransferStatus::NoMarket,
+            "too_small" => TransferStatus::TooSmall,
+            "too_large" => TransferStatus::TooLarge,
+            "error" => TransferStatus::Error,
+            other => TransferStatus::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for TransferStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TransferStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(TransferStatus::from(String::deserialize(deserializer)?.as_str()))
+    }
+}
+
+/// A deposit or withdrawal, as returned by the `/transaction` and `/transactions`
+/// endpoints of SEP-6 and SEP-24 anchors.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnchorTransaction {
+    pub id: String,
+    /// `deposit`, `withdrawal`, `deposit-exchange` or `withdrawal-exchange`.
+    pub kind: String,
+    pub status: TransferStatus,
+    #[serde(default)]
+    pub status_eta: Option<i64>,
+    #[serde(default)]
+    pub more_info_url: Option<String>,
+    #[serde(default, deserialize_with = "optional_amount")]
+    pub amount_in: Option<Amount>,
+    #[serde(default, deserialize_with = "optional_amount")]
+    pub amount_out: Option<Amount>,
+    #[serde(default, deserialize_with = "optional_amount")]
+    pub amount_fee: Option<Amount>,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+    #[serde(default)]
+    pub stellar_transaction_id: Option<String>,
+    #[serde(default)]
+    pub external_transaction_id: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// SEP-6, set with `pending_transaction_info_update`: what to fix.
+    #[serde(default)]
+    pub required_info_message: Option<String>,
+    #[serde(default)]
+    pub required_info_updates: Option<HashMap<String, TransferField>>,
+    /// Withdrawals: the account to send the funds to.
+    #[serde(default)]
+    pub withdraw_anchor_account: Option<String>,
+    #[serde(default)]
+    pub withdraw_memo: Option<String>,
+    #[serde(default)]
+    pub withdraw_memo_type: Option<String>,
+}
+
+impl AnchorTransaction {
+    /// Where and how much to pay for a withdrawal waiting for the user's transfer
+    /// (`pending_user_transfer_start`).
+    pub fn withdrawal_payment(&self) -> Result<(ResolvedDestination, Amount), Box<dyn std::error::Error>> {
+        if !self.kind.starts_with("withdrawal") {
+            return Err(format!("Transaction {} is a {}, not a withdrawal", self.id, self.kind).into());
+        }
+        if self.status != TransferStatus::PendingUserTransferStart {
+            return Err(format!("Withdrawal {} isn't waiting for a payment (status '{}')", self.id, self.status).into());
+        }
+        let account_id = self
+            .withdraw_anchor_account
+            .clone()
+            .ok_or_else(|| format!("Withdrawal {} has no withdraw_anchor_account", self.id))?;
+        let amount = self.amount_in.ok_or_else(|| format!("Withdrawal {} has no amount_in", self.id))?;
+        let memo = FederationMemo::parse(self.withdraw_memo_type.as_deref(), self.withdraw_memo.as_deref())?;
+        Ok((ResolvedDestination { account_id, memo, stellar_address: None }, amount))
+    }
+}
@@ -0,0 +1,128 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\asset.rs
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use stellar_sdk::{Asset, Keypair, StellarSdkError};
+
+/// Identifies an asset the way users and Horizon write it: `native` (or `XLM`)
+/// for lumens, `CODE:ISSUER` for everything else.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AssetId {
+    Native,
+    Credit { code: String, issuer: String },
+}
+
+impl AssetId {
+    /// Creates a credit asset, validating the code length (1-12 characters) and the issuer key.
+    pub fn credit(code: &str, issuer: &str) -> Result<Self, StellarSdkError> {
+        if code.is_empty() || code.len() > 12 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(StellarSdkError::InvalidAsset(format!("Invalid asset code '{}'", code)));
+        }
+        Keypair::from_public_key(issuer)?;
+        Ok(AssetId::Credit {
+            code: code.to_string(),
+            issuer: issuer.to_string(),
+        })
+    }
+
+    /// Builds an asset from the `asset_type`/`asset_code`/`asset_issuer` triple used in Horizon records.
+    ///
+    /// Returns `None` for records that aren't a plain asset, such as
+    /// `liquidity_pool_shares` balances, which have no code or issuer.
+    pub fn from_horizon(asset_type: &str, code: Option<&str>, issuer: Option<&str>) -> Option<Self> {
+        match (asset_type, code, issuer) {
+            ("native", _, _) => Some(AssetId::Native),
+            (_, Some(code), Some(issuer)) => Some(AssetId::Credit {
+                code: code.to_string(),
+                issuer: issuer.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Horizon's `asset_type` value for this asset.
+    pub fn asset_type(&self) -> &'static str {
+        match self {
+            AssetId::Native => "native",
+            AssetId::Credit { code, .. } if code.len() <= 4 => "credit_alphanum4",
+            AssetId::Credit { .. } => "credit_alphanum12",
+        }
+    }
+
+    /// Query parameters describing this asset, e.g. `source_asset_type`, `source_asset_code`
+    /// and `source_asset_issuer` for `prefix = "source_"`.
+    pub fn horizon_params(&self, prefix: &str) -> Vec<(String, String)> {
+        let mut params = vec![(format!("{}asset_type", prefix), self.asset_type().to_string())];
+        if let AssetId::Credit { code, issuer } = self {
+            params.push((format!("{}asset_code", prefix), code.clone()));
+            params.push((format!("{}asset_issuer", prefix), issuer.clone()));
+        }
+        params
+    }
+
+    /// Converts this identifier into the SDK's `Asset`.
+    pub fn to_sdk_asset(&self) -> Result<Asset, StellarSdkError> {
+        match self {
+            AssetId::Native => Ok(Asset::native()),
+            AssetId::Credit { code, issuer } => Asset::new(code.clone(), issuer.clone()),
+        }
+    }
+}
+
+impl FromStr for AssetId {
+    type Err = StellarSdkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("native") || s.eq_ignore_ascii_case("XLM") {
+            return Ok(AssetId::Native);
+        }
+        match s.split_once(':') {
+            Some((code, issuer)) => AssetId::credit(code, issuer),
+            None => Err(StellarSdkError::InvalidAsset(format!(
+                "Asset '{}' must be 'native' or CODE:ISSUER",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for AssetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetId::Native => write!(f, "native"),
+            AssetId::Credit { code, issuer } => write!(f, "{}:{}", code, issuer),
+        }
+    }
+}
+
+impl Serialize for AssetId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for AssetId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(|e: StellarSdkError| serde::de::Error::custom(e.to_string()))
+    }
+}
+
+/// The `asset_type`/`asset_code`/`asset_issuer` object Horizon embeds in its records.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct HorizonAsset {
+    pub asset_type: String,
+    pub asset_code: Option<String>,
+    pub asset_issuer: Option<String>,
+}
+
+impl TryFrom<HorizonAsset> for AssetId {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(asset: HorizonAsset) -> Result<Self, Self::Error> {
+        AssetId::from_horizon(&asset.asset_type, asset.asset_code.as_deref(), asset.asset_issuer.as_deref())
+            .ok_or_else(|| format!("Unsupported asset type '{}'", asset.asset_type).into())
+    }
+}
@@ -0,0 +1,206 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\authorization.rs
+
+use crate::amount::Amount;
+use crate::asset::AssetId;
+use crate::network::NetworkProfile;
+use crate::{horizon, tx, StellarWalletTools};
+use serde::Deserialize;
+use stellar_sdk::{
+    ClawbackClaimableBalanceOperation, ClawbackOperation, Keypair, SetTrustLineFlagsOperation,
+    StellarSdkError,
+};
+
+/// `AUTHORIZED_FLAG`: the holder may send, receive and trade the asset.
+pub const TRUSTLINE_AUTHORIZED_FLAG: u32 = 0x1;
+/// `AUTHORIZED_TO_MAINTAIN_LIABILITIES_FLAG`: existing offers stay, nothing new is allowed.
+pub const TRUSTLINE_AUTHORIZED_TO_MAINTAIN_LIABILITIES_FLAG: u32 = 0x2;
+/// `TRUSTLINE_CLAWBACK_ENABLED_FLAG`: the issuer may claw back this holder's balance.
+pub const TRUSTLINE_CLAWBACK_ENABLED_FLAG: u32 = 0x4;
+
+/// Authorization state of a trustline to a regulated asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustlineAuthorization {
+    Authorized,
+    AuthorizedToMaintainLiabilities,
+    Deauthorized,
+}
+
+impl TrustlineAuthorization {
+    /// The `(set_flags, clear_flags)` pair that moves a trustline into this state.
+    pub fn flags(self) -> (u32, u32) {
+        match self {
+            TrustlineAuthorization::Authorized => {
+                (TRUSTLINE_AUTHORIZED_FLAG, TRUSTLINE_AUTHORIZED_TO_MAINTAIN_LIABILITIES_FLAG)
+            }
+            TrustlineAuthorization::AuthorizedToMaintainLiabilities => {
+                (TRUSTLINE_AUTHORIZED_TO_MAINTAIN_LIABILITIES_FLAG, TRUSTLINE_AUTHORIZED_FLAG)
+            }
+            TrustlineAuthorization::Deauthorized => (
+                0,
+                TRUSTLINE_AUTHORIZED_FLAG | TRUSTLINE_AUTHORIZED_TO_MAINTAIN_LIABILITIES_FLAG,
+            ),
+        }
+    }
+}
+
+/// A holder of an asset, as listed by Horizon.
+#[derive(Debug, Clone)]
+pub struct AssetHolder {
+    pub account_id: String,
+    pub balance: Amount,
+    pub authorization: TrustlineAuthorization,
+    pub clawback_enabled: bool,
+}
+
+#[derive(Deserialize)]
+struct HolderAccountRecord {
+    account_id: String,
+    paging_token: String,
+    balances: Vec<HolderBalanceRecord>,
+}
+
+#[derive(Deserialize)]
+struct HolderBalanceRecord {
+    asset_code: Option<String>,
+    asset_issuer: Option<String>,
+    balance: Amount,
+    #[serde(default)]
+    is_authorized: bool,
+    #[serde(default)]
+    is_authorized_to_maintain_liabilities: bool,
+    #[serde(default)]
+    is_clawback_enabled: bool,
+}
+
+/// Lists every account holding a trustline to `asset`, with its authorization state.
+///
+/// Follows Horizon's pagination until all holders have been read.
+pub async fn list_asset_holders(
+    horizon_url: &str,
+    asset: &AssetId,
+) -> Result<Vec<AssetHolder>, Box<dyn std::error::Error>> {
+    let (code, issuer) = match asset {
+        AssetId::Credit { code, issuer } => (code.as_str(), issuer.as_str()),
+        AssetId::Native => return Err("Every account holds XLM; pass an issued asset".into()),
+    };
+
+    const PAGE_SIZE: usize = 200;
+    let mut holders = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let query = vec![
+            ("asset".to_string(), asset.to_string()),
+            ("limit".to_string(), PAGE_SIZE.to_string()),
+            ("cursor".to_string(), cursor.clone()),
+        ];
+        let records: Vec<HolderAccountRecord> =
+            horizon::get_records(&format!("{}/accounts", horizon_url), &query).await?;
+        let page_len = records.len();
+
+        for record in records {
+            cursor = record.paging_token.clone();
+            let trustline = record
+                .balances
+                .into_iter()
+                .find(|b| b.asset_code.as_deref() == Some(code) && b.asset_issuer.as_deref() == Some(issuer));
+            if let Some(trustline) = trustline {
+                let authorization = if trustline.is_authorized {
+                    TrustlineAuthorization::Authorized
+                } else if trustline.is_authorized_to_maintain_liabilities {
+                    TrustlineAuthorization::AuthorizedToMaintainLiabilities
+                } else {
+                    TrustlineAuthorization::Deauthorized
+                };
+                holders.push(AssetHolder {
+                    account_id: record.account_id,
+                    balance: trustline.balance,
+                    authorization,
+                    clawback_enabled: trustline.is_clawback_enabled,
+                });
+            }
+        }
+
+        if page_len < PAGE_SIZE {
+            return Ok(holders);
+        }
+    }
+}
+
+/// Builds a `SetTrustLineFlags` operation that moves `trustor`'s trustline to `authorization`.
+pub fn set_trustline_flags_operation(
+    trustor: &str,
+    asset: &AssetId,
+    authorization: TrustlineAuthorization,
+) -> Result<SetTrustLineFlagsOperation, StellarSdkError> {
+    Keypair::from_public_key(trustor)?;
+    let (set_flags, clear_flags) = authorization.flags();
+    Ok(SetTrustLineFlagsOperation::new(trustor.to_string(), asset.to_sdk_asset()?, set_flags, clear_flags))
+}
+
+/// Builds a `Clawback` operation burning `amount` of `asset` from `from`.
+pub fn clawback_operation(asset: &AssetId, from: &str, amount: Amount) -> Result<ClawbackOperation, StellarSdkError> {
+    Keypair::from_public_key(from)?;
+    Ok(ClawbackOperation::new(asset.to_sdk_asset()?, from.to_string(), amount.to_string()))
+}
+
+/// Builds a `ClawbackClaimableBalance` operation for the balance with the given hex ID.
+pub fn clawback_claimable_balance_operation(balance_id: &str) -> ClawbackClaimableBalanceOperation {
+    ClawbackClaimableBalanceOperation::new(balance_id.to_string())
+}
+
+/// The issuer's asset with code `asset_code`.
+fn issued_asset(issuer_keypair: &Keypair, asset_code: &str) -> Result<AssetId, StellarSdkError> {
+    AssetId::credit(asset_code, &issuer_keypair.public_key().to_string())
+}
+
+impl StellarWalletTools {
+    /// Creates a signed transaction, from the issuer, that changes a holder's authorization.
+    ///
+    /// # Arguments
+    /// * `issuer_secret_key` - The secret key of the asset issuer.
+    /// * `trustor` - The holder whose trustline is changed (G...).
+    /// * `asset_code` - The code of the issuer's asset.
+    /// * `authorization` - The new authorization state.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_set_authorization_transaction(
+        issuer_secret_key: &str,
+        trustor: &str,
+        asset_code: &str,
+        authorization: TrustlineAuthorization,
+        network: &NetworkProfile,
+    ) -> Result<String, StellarSdkError> {
+        let issuer_keypair = Keypair::from_secret(issuer_secret_key)?;
+        let asset = issued_asset(&issuer_keypair, asset_code)?;
+        let operation = set_trustline_flags_operation(trustor, &asset, authorization)?;
+        tx::build_signed_transaction(network, &issuer_keypair, vec![operation.into()]).await
+    }
+
+    /// Creates a signed transaction, from the issuer, that claws back `amount` from a holder.
+    /// The holder's trustline must have clawback enabled.
+    pub async fn create_clawback_transaction(
+        issuer_secret_key: &str,
+        from: &str,
+        asset_code: &str,
+        amount: Amount,
+        network: &NetworkProfile,
+    ) -> Result<String, StellarSdkError> {
+        let issuer_keypair = Keypair::from_secret(issuer_secret_key)?;
+        let asset = issued_asset(&issuer_keypair, asset_code)?;
+        let operation = clawback_operation(&asset, from, amount)?;
+        tx::build_signed_transaction(network, &issuer_keypair, vec![operation.into()]).await
+    }
+
+    /// Creates a signed transaction, from the issuer, that claws back a claimable balance.
+    pub async fn create_clawback_claimable_balance_transaction(
+        issuer_secret_key: &str,
+        balance_id: &str,
+        network: &NetworkProfile,
+    ) -> Result<String, StellarSdkError> {
+        let issuer_keypair = Keypair::from_secret(issuer_secret_key)?;
+        let operation = clawback_claimable_balance_operation(balance_id);
+        tx::build_signed_transaction(network, &issuer_keypair, vec![operation.into()]).await
+    }
+}
@@ -0,0 +1,470 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\batch.rs
+// Batch payouts: many payments packed into as few transactions as possible,
+// with progress saved to disk so an interrupted run can be resumed safely.
+
+use crate::amount::Amount;
+use crate::asset::AssetId;
+use crate::federation::{self, FederationMemo, FederationResolver};
+use crate::horizon::{self, SubmissionError};
+use crate::network::NetworkProfile;
+use crate::{strkey, tx};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use stellar_sdk::{Keypair, Operation, PaymentOperation};
+
+/// Maximum number of operations in one transaction.
+pub const MAX_OPERATIONS_PER_TRANSACTION: usize = 100;
+/// Maximum length, in bytes, of a text memo.
+pub const MAX_MEMO_TEXT_BYTES: usize = 28;
+/// How often a freshly built transaction is rebuilt after a `tx_bad_seq`
+/// rejection before it is left pending for the next run.
+const MAX_BAD_SEQ_REBUILDS: usize = 3;
+
+/// One validated payment of a batch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PaymentRow {
+    /// Account ID (G...), or a federation address until [`resolve_destinations`] has run.
+    pub destination: String,
+    pub amount: Amount,
+    pub asset: AssetId,
+    pub memo: Option<FederationMemo>,
+}
+
+/// A row as written in the input file, before validation.
+#[derive(Debug, Deserialize)]
+struct RawRow {
+    destination: String,
+    amount: String,
+    #[serde(default)]
+    asset: Option<String>,
+    #[serde(default)]
+    memo: Option<String>,
+}
+
+impl RawRow {
+    fn validate(self) -> Result<PaymentRow, String> {
+        let destination = self.destination.trim().to_string();
+        if federation::is_federation_address(&destination) {
+            federation::split_federation_address(&destination).map_err(|e| e.to_string())?;
+        } else {
+            strkey::decode_account_id(&destination)?;
+        }
+        let amount: Amount = self.amount.trim().parse().map_err(|e| format!("{}", e))?;
+        if amount.stroops() <= 0 {
+            return Err("amount must be positive".to_string());
+        }
+        let asset = match self.asset.as_deref().map(str::trim) {
+            None | Some("") => AssetId::Native,
+            Some(asset) => asset.parse().map_err(|e| format!("{}", e))?,
+        };
+        let memo = self.memo.filter(|memo| !memo.is_empty());
+        if let Some(memo) = &memo {
+            if memo.len() > MAX_MEMO_TEXT_BYTES {
+                return Err(format!("memo is longer than {} bytes", MAX_MEMO_TEXT_BYTES));
+            }
+        }
+        Ok(PaymentRow { destination, amount, asset, memo: memo.map(FederationMemo::Text) })
+    }
+}
+
+/// Reads and validates the payments of a batch file.
+///
+/// Files ending in `.json` hold an array of objects; anything else is read as
+/// CSV with a `destination,amount,asset,memo` header. `asset` defaults to XLM
+/// and `memo` is optional. Amounts are decimal strings (e.g. `"12.5"`).
+///
+/// Destinations are account IDs or federation addresses; the latter must be
+/// resolved with [`resolve_destinations`] before paying.
+///
+/// Every row is checked before anything is returned, and the error lists all
+/// invalid rows with their 1-based row number.
+pub fn read_payment_rows(path: &Path) -> Result<Vec<PaymentRow>, Box<dyn std::error::Error>> {
+    let raw_rows: Vec<RawRow> = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+        serde_json::from_str(&std::fs::read_to_string(path)?)?
+    } else {
+        let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_path(path)?;
+        reader.deserialize().collect::<Result<_, _>>()?
+    };
+    validate_rows(raw_rows)
+}
+
+fn validate_rows(raw_rows: Vec<RawRow>) -> Result<Vec<PaymentRow>, Box<dyn std::error::Error>> {
+    if raw_rows.is_empty() {
+        return Err("The batch file has no payments".into());
+    }
+    let mut rows = Vec::with_capacity(raw_rows.len());
+    let mut errors = Vec::new();
+    for (index, raw_row) in raw_rows.into_iter().enumerate() {
+        match raw_row.validate() {
+            Ok(row) => rows.push(row),
+            Err(e) => errors.push(format!("row {}: {}", index + 1, e)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(format!("Invalid payment rows:\n  {}", errors.join("\n  ")).into());
+    }
+    Ok(rows)
+}
+
+/// Replaces federation addresses with the account IDs they resolve to, adding
+/// the memo the recipient requires.
+///
+/// Addresses are resolved through `resolver`, so rows paying the same address
+/// cost a single lookup. A row whose own memo differs from the required one is
+/// an error (the required memo replaces a matching one, keeping its type); the error lists all failing rows with their 1-based row number.
+pub async fn resolve_destinations(
+    rows: Vec<PaymentRow>,
+    resolver: &FederationResolver,
+) -> Result<Vec<PaymentRow>, Box<dyn std::error::Error>> {
+    let mut resolved_rows = Vec::with_capacity(rows.len());
+    let mut errors = Vec::new();
+    for (index, mut row) in rows.into_iter().enumerate() {
+        if !federation::is_federation_address(&row.destination) {
+            resolved_rows.push(row);
+            continue;
+        }
+        let resolved = match resolver.resolve(&row.destination).await {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                errors.push(format!("row {}: {}", index + 1, e));
+                continue;
+            }
+        };
+        row.memo = match (row.memo, resolved.memo) {
+            // Row memos are read as text, so an ID or hash memo matches by its text form.
+            (Some(own), Some(required)) if own.to_string() != required.to_string() => {
+                errors.push(format!("row {}: {} requires the memo '{}'", index + 1, row.destination, required));
+                continue;
+            }
+            (own, required) => required.or(own),
+        };
+        row.destination = resolved.account_id;
+        resolved_rows.push(row);
+    }
+    if !errors.is_empty() {
+        return Err(format!("Unresolved destinations:\n  {}", errors.join("\n  ")).into());
+    }
+    Ok(resolved_rows)
+}
+
+/// Groups rows into transactions.
+///
+/// A memo belongs to a whole transaction, so rows are grouped by memo (in order
+/// of first appearance) and each group is split into chunks of at most 100
+/// payments. Rows without a memo share transactions.
+pub fn plan_transactions(rows: &[PaymentRow]) -> Vec<BatchTransaction> {
+    let mut groups: Vec<(Option<FederationMemo>, Vec<usize>)> = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        match groups.iter_mut().find(|(memo, _)| *memo == row.memo) {
+            Some((_, indexes)) => indexes.push(index),
+            None => groups.push((row.memo.clone(), vec![index])),
+        }
+    }
+    groups
+        .into_iter()
+        .flat_map(|(memo, indexes)| {
+            indexes
+                .chunks(MAX_OPERATIONS_PER_TRANSACTION)
+                .map(|chunk| BatchTransaction { rows: chunk.to_vec(), memo: memo.clone(), status: BatchStatus::Pending })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Progress of one transaction of the batch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BatchStatus {
+    Pending,
+    /// Signed and submitted, outcome unknown. On resume the envelope is looked up,
+    /// then resubmitted as is if it isn't in a ledger, so the payments can't be sent twice.
+    InFlight { hash: String, xdr: String },
+    Succeeded { hash: String },
+    Failed {
+        hash: Option<String>,
+        transaction_code: Option<String>,
+        /// One result code per payment, when the network returned them.
+        operation_codes: Vec<String>,
+    },
+}
+
+/// A transaction of the batch: the rows it pays (0-based) and their shared memo.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchTransaction {
+    pub rows: Vec<usize>,
+    pub memo: Option<FederationMemo>,
+    pub status: BatchStatus,
+}
+
+/// The progress file of a batch run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BatchState {
+    /// Hash of the rows, so a state file is never applied to a different batch.
+    fingerprint: String,
+    transactions: Vec<BatchTransaction>,
+}
+
+/// Outcome of one row, as written to the results report.
+#[derive(Debug, Clone, Serialize)]
+pub struct RowResult {
+    /// 1-based row number in the input file.
+    pub row: usize,
+    pub destination: String,
+    pub amount: Amount,
+    pub asset: AssetId,
+    pub memo: Option<String>,
+    /// `success`, `failed` or `pending`.
+    pub status: String,
+    pub transaction_hash: Option<String>,
+    /// The operation result code, or the transaction result code when the
+    /// payment itself didn't fail.
+    pub result_code: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TransactionRecord {
+    successful: bool,
+}
+
+/// A resumable batch payout.
+pub struct BatchPayout {
+    rows: Vec<PaymentRow>,
+    state: BatchState,
+    state_path: PathBuf,
+}
+
+impl BatchPayout {
+    /// Prepares a payout of `rows`, resuming from `state_path` if it holds the
+    /// progress of the same rows, or planning a fresh run otherwise.
+    pub fn prepare(rows: Vec<PaymentRow>, state_path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if let Some(row) = rows.iter().find(|row| federation::is_federation_address(&row.destination)) {
+            return Err(format!("{} must be resolved first (see resolve_destinations)", row.destination).into());
+        }
+        let fingerprint = fingerprint(&rows)?;
+        let state = match std::fs::read_to_string(state_path) {
+            Ok(contents) => {
+                let state: BatchState = serde_json::from_str(&contents)?;
+                if state.fingerprint != fingerprint {
+                    return Err(format!(
+                        "{} belongs to a different batch; remove it to start over",
+                        state_path.display()
+                    )
+                    .into());
+                }
+                state
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                BatchState { fingerprint, transactions: plan_transactions(&rows) }
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(BatchPayout { rows, state, state_path: state_path.to_path_buf() })
+    }
+
+    pub fn rows(&self) -> &[PaymentRow] {
+        &self.rows
+    }
+
+    pub fn transactions(&self) -> &[BatchTransaction] {
+        &self.state.transactions
+    }
+
+    /// Whether every transaction has either succeeded or failed.
+    pub fn is_complete(&self) -> bool {
+        self.state
+            .transactions
+            .iter()
+            .all(|t| matches!(t.status, BatchStatus::Succeeded { .. } | BatchStatus::Failed { .. }))
+    }
+
+    /// Signs and submits every transaction that hasn't completed yet, saving the
+    /// progress after each step. Failed transactions are recorded and skipped;
+    /// only errors that prevent further progress (e.g. Horizon being unreachable)
+    /// stop the run, which can then be resumed.
+    ///
+    /// An envelope left in flight by an earlier run is looked up first, and only
+    /// resubmitted if it isn't in a ledger. Whenever the outcome of a submission is
+    /// unknown (network errors, 5xx answers such as `504 Timeout`, lookups that
+    /// fail), the envelope stays in flight and the run stops.
+    ///
+    /// A transaction rejected with `tx_bad_seq` is rebuilt with a fresh sequence
+    /// number, unless it was a resubmission that Horizon finds in a ledger.
+    pub async fn run(&mut self, secret_key: &str, network: &NetworkProfile) -> Result<(), Box<dyn std::error::Error>> {
+        let keypair = Keypair::from_secret(secret_key)?;
+        let source_id = keypair.public_key().to_string();
+
+        for index in 0..self.state.transactions.len() {
+            let mut rebuilds = 0;
+            loop {
+                let (hash, xdr, resubmitted) = match &self.state.transactions[index].status {
+                    BatchStatus::Succeeded { .. } | BatchStatus::Failed { .. } => break,
+                    BatchStatus::InFlight { hash, xdr } => {
+                        let (hash, xdr) = (hash.clone(), xdr.clone());
+                        if let Some(status) = applied_status(&network.horizon_url, &hash).await? {
+                            self.set_status(index, status)?;
+                            break;
+                        }
+                        (hash, xdr, true)
+                    }
+                    BatchStatus::Pending => {
+                        let batch = &self.state.transactions[index];
+                        let operations = batch
+                            .rows
+                            .iter()
+                            .map(|&row| payment_operation(&self.rows[row]))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        let memo = batch.memo.as_ref().map(FederationMemo::to_sdk_memo).transpose()?;
+                        let transaction = tx::build_transaction_with_memo(network, &source_id, memo, operations).await?;
+                        let xdr = transaction.sign(&keypair)?.to_xdr_base64();
+                        let hash = to_hex(&tx::transaction_hash(&xdr, &network.network_passphrase)?);
+                        // Record the envelope before submitting it, so a crash can't lead to paying twice.
+                        self.set_status(index, BatchStatus::InFlight { hash: hash.clone(), xdr: xdr.clone() })?;
+                        (hash, xdr, false)
+                    }
+                };
+
+                let status = match horizon::submit_transaction(&network.horizon_url, &xdr).await {
+                    Ok(hash) => BatchStatus::Succeeded { hash },
+                    Err(e) => {
+                        let rejection = match e.downcast::<SubmissionError>() {
+                            Ok(rejection) => rejection,
+                            // Network errors: the envelope may still be applied; keep it in flight.
+                            Err(e) => return Err(e),
+                        };
+                        if rejection.status >= 500 || rejection.transaction_code.is_none() {
+                            // Timeouts and other answers without a verdict: same as a network error.
+                            return Err(rejection);
+                        }
+                        if rejection.transaction_code.as_deref() != Some("tx_bad_seq") {
+                            failed(Some(hash), &rejection)
+                        } else {
+                            // A resubmitted envelope may have made it into a ledger since it was looked up.
+                            let applied = if resubmitted {
+                                applied_status(&network.horizon_url, &hash).await?
+                            } else {
+                                None
+                            };
+                            match applied {
+                                Some(status) => status,
+                                // Not in a ledger, and its sequence number is used up: it can
+                                // never be applied, so rebuild it with a fresh sequence number.
+                                None => {
+                                    self.set_status(index, BatchStatus::Pending)?;
+                                    rebuilds += 1;
+                                    if rebuilds < MAX_BAD_SEQ_REBUILDS {
+                                        continue;
+                                    }
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                };
+                self.set_status(index, status)?;
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// The outcome of every row, in input order.
+    pub fn results(&self) -> Vec<RowResult> {
+        let mut results: Vec<RowResult> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| RowResult {
+                row: index + 1,
+                destination: row.destination.clone(),
+                amount: row.amount,
+                asset: row.asset.clone(),
+                memo: row.memo.as_ref().map(FederationMemo::to_string),
+                status: "pending".to_string(),
+                transaction_hash: None,
+                result_code: None,
+            })
+            .collect();
+
+        for batch in &self.state.transactions {
+            for (position, &row) in batch.rows.iter().enumerate() {
+                let result = &mut results[row];
+                match &batch.status {
+                    BatchStatus::Pending | BatchStatus::InFlight { .. } => {}
+                    BatchStatus::Succeeded { hash } => {
+                        result.status = "success".to_string();
+                        result.transaction_hash = Some(hash.clone());
+                    }
+                    BatchStatus::Failed { hash, transaction_code, operation_codes } => {
+                        result.status = "failed".to_string();
+                        result.transaction_hash = hash.clone();
+                        // Prefer the payment's own code unless it succeeded inside a failed transaction.
+                        result.result_code = operation_codes
+                            .get(position)
+                            .filter(|code| code.as_str() != "op_success")
+                            .or(transaction_code.as_ref())
+                            .cloned();
+                    }
+                }
+            }
+        }
+        results
+    }
+
+    /// Writes the results report as CSV.
+    pub fn write_report(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let mut writer = csv::Writer::from_path(path)?;
+        for result in self.results() {
+            writer.serialize(result)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn set_status(&mut self, index: usize, status: BatchStatus) -> Result<(), Box<dyn std::error::Error>> {
+        self.state.transactions[index].status = status;
+        // Write to a temporary file first so a crash never leaves a truncated state file.
+        let temp_path = self.state_path.with_extension("tmp");
+        std::fs::write(&temp_path, serde_json::to_string_pretty(&self.state)?)?;
+        std::fs::rename(&temp_path, &self.state_path)?;
+        Ok(())
+    }
+}
+
+fn payment_operation(row: &PaymentRow) -> Result<Operation, Box<dyn std::error::Error>> {
+    Ok(PaymentOperation::new(row.destination.clone(), row.asset.to_sdk_asset()?, row.amount.to_string()).into())
+}
+
+fn failed(hash: Option<String>, rejection: &SubmissionError) -> BatchStatus {
+    BatchStatus::Failed {
+        hash,
+        transaction_code: rejection.transaction_code.clone(),
+        operation_codes: rejection.operation_codes.clone(),
+    }
+}
+
+/// Looks a transaction up on Horizon: its final status if it is in a ledger, `None`
+/// if Horizon answers 404. Any other failure is an error, since the transaction
+/// may still be applied.
+async fn applied_status(horizon_url: &str, hash: &str) -> Result<Option<BatchStatus>, Box<dyn std::error::Error>> {
+    let record = horizon::get_json_optional::<TransactionRecord>(&format!("{}/transactions/{}", horizon_url, hash), &[]).await?;
+    Ok(record.map(|record| {
+        if record.successful {
+            BatchStatus::Succeeded { hash: hash.to_string() }
+        } else {
+            BatchStatus::Failed {
+                hash: Some(hash.to_string()),
+                transaction_code: Some("tx_failed".to_string()),
+                operation_codes: Vec::new(),
+            }
+        }
+    }))
+}
+
+fn fingerprint(rows: &[PaymentRow]) -> Result<String, serde_json::Error> {
+    Ok(to_hex(&Sha256::digest(serde_json::to_vec(rows)?)))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
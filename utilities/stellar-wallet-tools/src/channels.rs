@@ -0,0 +1,228 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\channels.rs
+// Channel accounts: extra source accounts that let one main account submit many
+// transactions per ledger.
+
+use crate::amount::Amount;
+use crate::asset::AssetId;
+use crate::network::NetworkProfile;
+use crate::sequence::SequenceAllocator;
+use crate::{horizon, tx, StellarWalletTools};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use stellar_sdk::{AccountMergeOperation, CreateAccountOperation, Keypair, Operation, PaymentOperation};
+
+/// Every transaction carries at most 20 signatures; releasing channels also
+/// needs the main account's, which leaves room for 19 channels per transaction.
+const MAX_CHANNELS_PER_RELEASE: usize = 19;
+
+/// One channel account with its locally tracked sequence number.
+#[derive(Clone)]
+pub struct Channel {
+    pub public_key: String,
+    secret_key: String,
+    sequence: SequenceAllocator,
+}
+
+impl Channel {
+    pub fn secret_key(&self) -> &str {
+        &self.secret_key
+    }
+
+    /// The last sequence number used by this channel.
+    pub fn current_sequence(&self) -> i64 {
+        self.sequence.current()
+    }
+}
+
+// Written by hand so the secret key never ends up in logs.
+impl fmt::Debug for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Channel")
+            .field("public_key", &self.public_key)
+            .field("secret_key", &"<redacted>")
+            .field("sequence", &self.sequence.current())
+            .finish()
+    }
+}
+
+/// A pool of channel accounts serving one main account.
+///
+/// Channels are only used as *transaction* sources, so they pay the fee and
+/// provide the sequence number; every operation keeps the main account as its
+/// source, which moves the funds. Each transaction is therefore signed by both
+/// the channel and the main account.
+///
+/// Channels are handed out round-robin. Their sequence numbers are tracked
+/// locally, so transactions on different channels can be submitted in parallel.
+pub struct ChannelPool {
+    main_account: String,
+    channels: Vec<Channel>,
+    next: AtomicUsize,
+}
+
+impl fmt::Debug for ChannelPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelPool")
+            .field("main_account", &self.main_account)
+            .field("channels", &self.channels)
+            .field("next", &self.next.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl ChannelPool {
+    /// Creates a pool from existing channel accounts, given as `(secret_key, current_sequence)`.
+    pub fn new(main_account: &str, channels: Vec<(String, i64)>) -> Result<Self, Box<dyn std::error::Error>> {
+        if channels.is_empty() {
+            return Err("A channel pool needs at least one channel account".into());
+        }
+        let mut pool_channels = Vec::with_capacity(channels.len());
+        for (secret_key, sequence) in channels {
+            let public_key = Keypair::from_secret(&secret_key)?.public_key().to_string();
+            if public_key == main_account {
+                return Err("The main account can't be its own channel".into());
+            }
+            pool_channels.push(Channel {
+                sequence: SequenceAllocator::new(&public_key, sequence),
+                public_key,
+                secret_key,
+            });
+        }
+        Ok(ChannelPool { main_account: main_account.to_string(), channels: pool_channels, next: AtomicUsize::new(0) })
+    }
+
+    /// Loads existing channel accounts and their sequence numbers from Horizon.
+    pub async fn load(
+        main_account: &str,
+        channel_secret_keys: &[String],
+        network: &NetworkProfile,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut channels = Vec::with_capacity(channel_secret_keys.len());
+        for secret_key in channel_secret_keys {
+            let public_key = Keypair::from_secret(secret_key)?.public_key().to_string();
+            let allocator = SequenceAllocator::load(&network.horizon_url, &public_key).await?;
+            channels.push((secret_key.clone(), allocator.current()));
+        }
+        Self::new(main_account, channels)
+    }
+
+    pub fn main_account(&self) -> &str {
+        &self.main_account
+    }
+
+    pub fn channels(&self) -> &[Channel] {
+        &self.channels
+    }
+
+    /// The next channel, round-robin.
+    pub fn next_channel(&self) -> &Channel {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.channels.len();
+        &self.channels[index]
+    }
+
+    /// Builds a transaction on the next channel and signs it with the channel and `main_keypair`.
+    ///
+    /// The operations must use the main account as their source
+    /// (`with_source_account`), otherwise they would act on the channel.
+    ///
+    /// # Returns
+    /// A `Result` containing the channel used and the base64-encoded transaction XDR.
+    pub fn build_transaction(
+        &self,
+        network: &NetworkProfile,
+        main_keypair: &Keypair,
+        operations: Vec<Operation>,
+    ) -> Result<(&Channel, String), Box<dyn std::error::Error>> {
+        if main_keypair.public_key().to_string() != self.main_account {
+            return Err("The key pair doesn't belong to the pool's main account".into());
+        }
+        let channel = self.next_channel();
+        let channel_keypair = Keypair::from_secret(&channel.secret_key)?;
+        let transaction = channel.sequence.build_transaction(network, operations)?;
+        let signed_transaction = transaction.sign(&channel_keypair)?.sign(main_keypair)?;
+        Ok((channel, signed_transaction.to_xdr_base64()))
+    }
+
+    /// Builds a payment from the main account, sent through the next channel.
+    pub fn build_payment_transaction(
+        &self,
+        network: &NetworkProfile,
+        main_keypair: &Keypair,
+        destination: &str,
+        amount: Amount,
+        asset: &AssetId,
+    ) -> Result<(&Channel, String), Box<dyn std::error::Error>> {
+        let payment = PaymentOperation::new(destination.to_string(), asset.to_sdk_asset()?, amount.to_string())
+            .with_source_account(self.main_account.clone());
+        self.build_transaction(network, main_keypair, vec![payment.into()])
+    }
+
+    /// Re-reads a channel's sequence number, e.g. after a `tx_bad_seq` rejection.
+    pub async fn resync(&self, channel: &Channel, network: &NetworkProfile) -> Result<i64, Box<dyn std::error::Error>> {
+        channel.sequence.resync(&network.horizon_url).await
+    }
+
+    /// Merges every channel back into the main account, returning their XLM.
+    ///
+    /// Channels are merged 19 at a time, so each transaction stays within the
+    /// 20 signature limit.
+    ///
+    /// # Returns
+    /// A `Result` containing the hashes of the submitted transactions, in order.
+    pub async fn release(self, main_secret_key: &str, network: &NetworkProfile) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let main_keypair = Keypair::from_secret(main_secret_key)?;
+        let mut hashes = Vec::new();
+        for chunk in self.channels.chunks(MAX_CHANNELS_PER_RELEASE) {
+            let operations = chunk
+                .iter()
+                .map(|channel| {
+                    AccountMergeOperation::new(self.main_account.clone())
+                        .with_source_account(channel.public_key.clone())
+                        .into()
+                })
+                .collect();
+            let mut transaction =
+                tx::build_transaction(network, &self.main_account, operations).await?.sign(&main_keypair)?;
+            for channel in chunk {
+                transaction = transaction.sign(&Keypair::from_secret(&channel.secret_key)?)?;
+            }
+            hashes.push(horizon::submit_transaction(&network.horizon_url, &transaction.to_xdr_base64()).await?);
+        }
+        Ok(hashes)
+    }
+}
+
+impl StellarWalletTools {
+    /// Creates `count` channel accounts funded by the main account, in one transaction.
+    ///
+    /// # Arguments
+    /// * `main_secret_key` - The secret key of the main account (funds the channels).
+    /// * `count` - Number of channels to create (1 to 100).
+    /// * `starting_balance` - XLM given to each channel to cover its reserve and fees.
+    /// * `network` - The network profile to create the channels on.
+    ///
+    /// # Returns
+    /// A `Result` containing the `ChannelPool`. Persist the channels' secret keys
+    /// (see [`Channel::secret_key`]) to reuse or release them later.
+    pub async fn create_channel_pool(
+        main_secret_key: &str,
+        count: usize,
+        starting_balance: Amount,
+        network: &NetworkProfile,
+    ) -> Result<ChannelPool, Box<dyn std::error::Error>> {
+        if count == 0 || count > 100 {
+            return Err("A channel pool has 1 to 100 channels".into());
+        }
+        let main_keypair = Keypair::from_secret(main_secret_key)?;
+        let channel_keys = (0..count).map(|_| Self::generate_key_pair()).collect::<Result<Vec<_>, _>>()?;
+        let operations = channel_keys
+            .iter()
+            .map(|key| CreateAccountOperation::new(key.public_key.clone(), starting_balance.to_string()).into())
+            .collect();
+        let xdr = tx::build_signed_transaction(network, &main_keypair, operations).await?;
+        horizon::submit_transaction(&network.horizon_url, &xdr).await?;
+
+        let secret_keys: Vec<String> = channel_keys.into_iter().map(|key| key.secret_key).collect();
+        ChannelPool::load(&main_keypair.public_key().to_string(), &secret_keys, network).await
+    }
+}
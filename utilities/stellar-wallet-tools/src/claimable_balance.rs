@@ -0,0 +1,333 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\claimable_balance.rs
+
+use crate::amount::Amount;
+use crate::asset::AssetId;
+use crate::network::NetworkProfile;
+use crate::time::{parse_rfc3339, unix_now};
+use crate::{horizon, tx, StellarWalletTools};
+use serde::Deserialize;
+use serde_json::Value;
+use stellar_sdk::{
+    ClaimClaimableBalanceOperation, CreateClaimableBalanceOperation, Keypair, StellarSdkError,
+};
+
+/// Condition under which a claimant may claim a balance.
+///
+/// "After" conditions are expressed, as on the ledger, by negating a "before" condition;
+/// use [`ClaimPredicate::after_absolute_time`] and [`ClaimPredicate::after_relative_time`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimPredicate {
+    Unconditional,
+    And(Box<ClaimPredicate>, Box<ClaimPredicate>),
+    Or(Box<ClaimPredicate>, Box<ClaimPredicate>),
+    Not(Box<ClaimPredicate>),
+    /// Claimable before this UNIX timestamp (seconds).
+    BeforeAbsoluteTime(i64),
+    /// Claimable for this many seconds after the balance is created.
+    BeforeRelativeTime(i64),
+}
+
+impl ClaimPredicate {
+    /// Both predicates must hold.
+    pub fn and(self, other: ClaimPredicate) -> Self {
+        ClaimPredicate::And(Box::new(self), Box::new(other))
+    }
+
+    /// Either predicate must hold.
+    pub fn or(self, other: ClaimPredicate) -> Self {
+        ClaimPredicate::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Negates a predicate.
+    pub fn negate(self) -> Self {
+        ClaimPredicate::Not(Box::new(self))
+    }
+
+    /// Claimable from this UNIX timestamp (seconds) onwards.
+    pub fn after_absolute_time(timestamp: i64) -> Self {
+        ClaimPredicate::BeforeAbsoluteTime(timestamp).negate()
+    }
+
+    /// Claimable once this many seconds have passed since the balance was created.
+    pub fn after_relative_time(seconds: i64) -> Self {
+        ClaimPredicate::BeforeRelativeTime(seconds).negate()
+    }
+
+    /// Evaluates the predicate at `now` for a balance created at `created_at` (both UNIX seconds).
+    pub fn is_satisfied(&self, now: i64, created_at: i64) -> bool {
+        match self {
+            ClaimPredicate::Unconditional => true,
+            ClaimPredicate::And(a, b) => a.is_satisfied(now, created_at) && b.is_satisfied(now, created_at),
+            ClaimPredicate::Or(a, b) => a.is_satisfied(now, created_at) || b.is_satisfied(now, created_at),
+            ClaimPredicate::Not(p) => !p.is_satisfied(now, created_at),
+            ClaimPredicate::BeforeAbsoluteTime(t) => now < *t,
+            ClaimPredicate::BeforeRelativeTime(s) => now < created_at.saturating_add(*s),
+        }
+    }
+
+    /// Evaluates the predicate at `now` (UNIX seconds) using absolute times only.
+    ///
+    /// Horizon reports relative conditions already converted to `abs_before`, so
+    /// predicates read from it never need a creation time. A relative condition
+    /// here can't be evaluated: the result is `None` unless the other side of an
+    /// `and`/`or` settles it.
+    fn is_satisfied_at(&self, now: i64) -> Option<bool> {
+        match self {
+            ClaimPredicate::Unconditional => Some(true),
+            ClaimPredicate::And(a, b) => match (a.is_satisfied_at(now), b.is_satisfied_at(now)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            ClaimPredicate::Or(a, b) => match (a.is_satisfied_at(now), b.is_satisfied_at(now)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            ClaimPredicate::Not(p) => p.is_satisfied_at(now).map(|satisfied| !satisfied),
+            ClaimPredicate::BeforeAbsoluteTime(t) => Some(now < *t),
+            ClaimPredicate::BeforeRelativeTime(_) => None,
+        }
+    }
+
+    /// Parses the JSON form Horizon uses for predicates, e.g.
+    /// `{"not": {"abs_before": "2025-01-01T00:00:00Z", "abs_before_epoch": "1735689600"}}`.
+    pub fn from_horizon_json(value: &Value) -> Result<Self, Box<dyn std::error::Error>> {
+        let invalid = || format!("Unsupported claim predicate: {}", value);
+        let pair = |list: &Value| -> Result<(Self, Self), Box<dyn std::error::Error>> {
+            match list.as_array().map(|l| l.as_slice()) {
+                Some([a, b]) => Ok((Self::from_horizon_json(a)?, Self::from_horizon_json(b)?)),
+                _ => Err(invalid().into()),
+            }
+        };
+        let seconds = |v: &Value| -> Result<i64, Box<dyn std::error::Error>> {
+            match v {
+                Value::String(s) => Ok(s.parse()?),
+                Value::Number(n) => n.as_i64().ok_or_else(|| invalid().into()),
+                _ => Err(invalid().into()),
+            }
+        };
+
+        if value.get("unconditional").is_some() {
+            Ok(ClaimPredicate::Unconditional)
+        } else if let Some(list) = value.get("and") {
+            let (a, b) = pair(list)?;
+            Ok(a.and(b))
+        } else if let Some(list) = value.get("or") {
+            let (a, b) = pair(list)?;
+            Ok(a.or(b))
+        } else if let Some(inner) = value.get("not") {
+            Ok(Self::from_horizon_json(inner)?.negate())
+        } else if let Some(epoch) = value.get("abs_before_epoch") {
+            Ok(ClaimPredicate::BeforeAbsoluteTime(seconds(epoch)?))
+        } else if let Some(time) = value.get("abs_before").and_then(Value::as_str) {
+            // Older Horizon versions only return the RFC 3339 form.
+            Ok(ClaimPredicate::BeforeAbsoluteTime(parse_rfc3339(time).ok_or_else(invalid)?))
+        } else if let Some(rel) = value.get("rel_before") {
+            Ok(ClaimPredicate::BeforeRelativeTime(seconds(rel)?))
+        } else {
+            Err(invalid().into())
+        }
+    }
+
+    fn to_sdk_predicate(&self) -> stellar_sdk::ClaimPredicate {
+        match self {
+            ClaimPredicate::Unconditional => stellar_sdk::ClaimPredicate::Unconditional,
+            ClaimPredicate::And(a, b) => stellar_sdk::ClaimPredicate::And(vec![a.to_sdk_predicate(), b.to_sdk_predicate()]),
+            ClaimPredicate::Or(a, b) => stellar_sdk::ClaimPredicate::Or(vec![a.to_sdk_predicate(), b.to_sdk_predicate()]),
+            ClaimPredicate::Not(p) => stellar_sdk::ClaimPredicate::Not(Some(Box::new(p.to_sdk_predicate()))),
+            ClaimPredicate::BeforeAbsoluteTime(t) => stellar_sdk::ClaimPredicate::BeforeAbsoluteTime(*t),
+            ClaimPredicate::BeforeRelativeTime(s) => stellar_sdk::ClaimPredicate::BeforeRelativeTime(*s),
+        }
+    }
+}
+
+/// An account allowed to claim a balance, and the condition it has to meet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claimant {
+    pub destination: String,
+    pub predicate: ClaimPredicate,
+}
+
+impl Claimant {
+    /// A claimant that can claim at any time.
+    pub fn unconditional(destination: &str) -> Self {
+        Claimant {
+            destination: destination.to_string(),
+            predicate: ClaimPredicate::Unconditional,
+        }
+    }
+}
+
+/// A claimable balance as listed by Horizon.
+#[derive(Debug, Clone)]
+pub struct ClaimableBalance {
+    /// Balance ID (hex, including the 8-character type prefix).
+    pub id: String,
+    pub asset: AssetId,
+    pub amount: Amount,
+    pub sponsor: Option<String>,
+    pub claimants: Vec<Claimant>,
+}
+
+impl ClaimableBalance {
+    /// Returns `true` if `account_id` is a claimant whose predicate holds at `now` (UNIX seconds).
+    ///
+    /// Only the absolute times (`abs_before`/`abs_before_epoch`) Horizon reports are evaluated;
+    /// a predicate that depends on a relative time doesn't count as satisfied.
+    pub fn is_claimable_by(&self, account_id: &str, now: i64) -> bool {
+        self.claimants
+            .iter()
+            .any(|c| c.destination == account_id && c.predicate.is_satisfied_at(now) == Some(true))
+    }
+}
+
+#[derive(Deserialize)]
+struct ClaimantRecord {
+    destination: String,
+    predicate: Value,
+}
+
+#[derive(Deserialize)]
+struct ClaimableBalanceRecord {
+    id: String,
+    paging_token: String,
+    asset: AssetId,
+    amount: Amount,
+    sponsor: Option<String>,
+    claimants: Vec<ClaimantRecord>,
+}
+
+impl TryFrom<ClaimableBalanceRecord> for ClaimableBalance {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(record: ClaimableBalanceRecord) -> Result<Self, Self::Error> {
+        let claimants = record
+            .claimants
+            .into_iter()
+            .map(|c| {
+                Ok(Claimant {
+                    destination: c.destination,
+                    predicate: ClaimPredicate::from_horizon_json(&c.predicate)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Self::Error>>()?;
+
+        Ok(ClaimableBalance {
+            id: record.id,
+            asset: record.asset,
+            amount: record.amount,
+            sponsor: record.sponsor,
+            claimants,
+        })
+    }
+}
+
+/// Lists the claimable balances that `claimant` is one of the claimants of.
+///
+/// Follows Horizon's pagination until all balances have been read.
+pub async fn list_claimable_balances(
+    horizon_url: &str,
+    claimant: &str,
+) -> Result<Vec<ClaimableBalance>, Box<dyn std::error::Error>> {
+    const PAGE_SIZE: usize = 200;
+    let mut balances = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let query = vec![
+            ("claimant".to_string(), claimant.to_string()),
+            ("limit".to_string(), PAGE_SIZE.to_string()),
+            ("cursor".to_string(), cursor.clone()),
+        ];
+        let records: Vec<ClaimableBalanceRecord> =
+            horizon::get_records(&format!("{}/claimable_balances", horizon_url), &query).await?;
+        let page_len = records.len();
+
+        for record in records {
+            cursor = record.paging_token.clone();
+            balances.push(ClaimableBalance::try_from(record)?);
+        }
+
+        if page_len < PAGE_SIZE {
+            return Ok(balances);
+        }
+    }
+}
+
+/// Builds a `CreateClaimableBalance` operation. Between 1 and 10 claimants are allowed.
+pub fn create_claimable_balance_operation(
+    asset: &AssetId,
+    amount: Amount,
+    claimants: &[Claimant],
+) -> Result<CreateClaimableBalanceOperation, Box<dyn std::error::Error>> {
+    if claimants.is_empty() || claimants.len() > 10 {
+        return Err("A claimable balance needs between 1 and 10 claimants".into());
+    }
+    let claimants = claimants
+        .iter()
+        .map(|c| {
+            Keypair::from_public_key(&c.destination)?;
+            Ok(stellar_sdk::Claimant::new(c.destination.clone(), c.predicate.to_sdk_predicate()))
+        })
+        .collect::<Result<Vec<_>, StellarSdkError>>()?;
+
+    Ok(CreateClaimableBalanceOperation::new(asset.to_sdk_asset()?, amount.to_string(), claimants))
+}
+
+/// Builds a `ClaimClaimableBalance` operation for the balance with the given hex ID.
+pub fn claim_claimable_balance_operation(balance_id: &str) -> ClaimClaimableBalanceOperation {
+    ClaimClaimableBalanceOperation::new(balance_id.to_string())
+}
+
+impl StellarWalletTools {
+    /// Creates a signed transaction that locks `amount` of `asset` in a new claimable balance.
+    ///
+    /// # Arguments
+    /// * `source_secret_key` - The secret key of the account funding the balance.
+    /// * `asset` - The asset to send; the claimants don't need a trustline yet.
+    /// * `amount` - The amount to lock.
+    /// * `claimants` - Who may claim the balance, and when.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_claimable_balance_transaction(
+        source_secret_key: &str,
+        asset: &AssetId,
+        amount: Amount,
+        claimants: &[Claimant],
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let operation = create_claimable_balance_operation(asset, amount, claimants)?;
+        Ok(tx::build_signed_transaction(network, &source_keypair, vec![operation.into()]).await?)
+    }
+
+    /// Creates a signed transaction that claims a claimable balance.
+    ///
+    /// The balance is loaded first so that a claim which would fail on-chain
+    /// (wrong account, predicate not met) is reported without paying a fee.
+    pub async fn create_claim_transaction(
+        source_secret_key: &str,
+        balance_id: &str,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let account_id = source_keypair.public_key().to_string();
+
+        let record: ClaimableBalanceRecord =
+            horizon::get_json_optional(&format!("{}/claimable_balances/{}", network.horizon_url, balance_id), &[])
+                .await?
+                .ok_or_else(|| format!("Claimable balance {} not found", balance_id))?;
+        let balance = ClaimableBalance::try_from(record)?;
+        if !balance.claimants.iter().any(|c| c.destination == account_id) {
+            return Err(format!("{} is not a claimant of balance {}", account_id, balance_id).into());
+        }
+        if !balance.is_claimable_by(&account_id, unix_now()) {
+            return Err(format!("Balance {} can't be claimed by {} right now", balance_id, account_id).into());
+        }
+
+        let operation = claim_claimable_balance_operation(balance_id);
+        Ok(tx::build_signed_transaction(network, &source_keypair, vec![operation.into()]).await?)
+    }
+}
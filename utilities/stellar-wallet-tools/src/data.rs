@@ -0,0 +1,124 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\data.rs
+
+use crate::network::NetworkProfile;
+use crate::{horizon, tx, StellarWalletTools};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use stellar_sdk::{Keypair, ManageDataOperation};
+
+/// Maximum length, in bytes, of both the name and the value of a data entry.
+pub const MAX_DATA_ENTRY_BYTES: usize = 64;
+
+/// A name/value pair stored on an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataEntry {
+    pub name: String,
+    pub value: Vec<u8>,
+}
+
+impl DataEntry {
+    /// The value as text, if it is valid UTF-8.
+    pub fn value_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.value).ok()
+    }
+
+    /// The value base64-encoded, as Horizon and XDR tooling show it.
+    pub fn value_base64(&self) -> String {
+        BASE64.encode(&self.value)
+    }
+}
+
+#[derive(Deserialize)]
+struct AccountData {
+    /// Entry names mapped to base64-encoded values.
+    #[serde(default)]
+    data: BTreeMap<String, String>,
+}
+
+/// Checks a data entry name: 1 to 64 bytes.
+pub fn validate_data_name(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if name.is_empty() || name.len() > MAX_DATA_ENTRY_BYTES {
+        return Err(format!("Data entry names must be 1 to {} bytes long", MAX_DATA_ENTRY_BYTES).into());
+    }
+    Ok(())
+}
+
+/// Decodes a base64 value given on the command line or by Horizon, checking its length.
+pub fn decode_data_value(value_base64: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let value = BASE64.decode(value_base64.trim())?;
+    if value.len() > MAX_DATA_ENTRY_BYTES {
+        return Err(format!("Data entry values can be at most {} bytes", MAX_DATA_ENTRY_BYTES).into());
+    }
+    Ok(value)
+}
+
+/// Lists the data entries of an account, sorted by name, with their values decoded.
+pub async fn list_data_entries(
+    horizon_url: &str,
+    account_id: &str,
+) -> Result<Vec<DataEntry>, Box<dyn std::error::Error>> {
+    let account: AccountData = horizon::get_json(&format!("{}/accounts/{}", horizon_url, account_id), &[]).await?;
+    let mut entries = Vec::with_capacity(account.data.len());
+    for (name, value) in account.data {
+        entries.push(DataEntry { value: BASE64.decode(value)?, name });
+    }
+    Ok(entries)
+}
+
+/// Reads a single data entry of an account, or `None` if it isn't set.
+pub async fn get_data_entry(
+    horizon_url: &str,
+    account_id: &str,
+    name: &str,
+) -> Result<Option<DataEntry>, Box<dyn std::error::Error>> {
+    let entries = list_data_entries(horizon_url, account_id).await?;
+    Ok(entries.into_iter().find(|entry| entry.name == name))
+}
+
+/// Builds a `ManageData` operation that creates or replaces the entry `name`.
+pub fn set_data_operation(name: &str, value: &[u8]) -> Result<ManageDataOperation, Box<dyn std::error::Error>> {
+    validate_data_name(name)?;
+    if value.is_empty() {
+        // An empty value is how the protocol deletes an entry.
+        return Err("Data entry values can't be empty; use a delete operation to remove the entry".into());
+    }
+    if value.len() > MAX_DATA_ENTRY_BYTES {
+        return Err(format!("Data entry values can be at most {} bytes", MAX_DATA_ENTRY_BYTES).into());
+    }
+    Ok(ManageDataOperation::new(name.to_string(), Some(value.to_vec())))
+}
+
+/// Builds a `ManageData` operation that deletes the entry `name`.
+pub fn delete_data_operation(name: &str) -> Result<ManageDataOperation, Box<dyn std::error::Error>> {
+    validate_data_name(name)?;
+    Ok(ManageDataOperation::new(name.to_string(), None))
+}
+
+impl StellarWalletTools {
+    /// Creates a signed transaction that sets (or, with `None`, deletes) a data entry
+    /// on the signer's account.
+    ///
+    /// # Arguments
+    /// * `secret_key` - The secret key of the account owning the entry.
+    /// * `name` - The entry name (1 to 64 bytes).
+    /// * `value` - The raw value (1 to 64 bytes), or `None` to delete the entry.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_manage_data_transaction(
+        secret_key: &str,
+        name: &str,
+        value: Option<&[u8]>,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let keypair = Keypair::from_secret(secret_key)?;
+        let operation = match value {
+            Some(value) => set_data_operation(name, value)?,
+            None => delete_data_operation(name)?,
+        };
+        Ok(tx::build_signed_transaction(network, &keypair, vec![operation.into()]).await?)
+    }
+}
@@ -0,0 +1,319 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\direct_payment.rs
+// Cross-border payments sent through a receiving anchor (SEP-0031).
+
+use crate::amount::Amount;
+use crate::anchor::{self, AnchorError, TransferStatus};
+use crate::asset::AssetId;
+use crate::federation::{FederationMemo, ResolvedDestination};
+use crate::network::NetworkProfile;
+use crate::stellar_toml::StellarToml;
+use crate::web_auth::WebAuthToken;
+use crate::{horizon, StellarWalletTools};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often [`DirectPaymentClient::wait_for`] polls by default.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A kind of SEP-12 customer the anchor knows, e.g. `sep31-large-sender`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CustomerType {
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// The SEP-12 customer types accepted for one role (sender or receiver).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CustomerTypes {
+    #[serde(default)]
+    pub types: HashMap<String, CustomerType>,
+}
+
+/// Customer information the anchor requires, registered through SEP-12.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CustomerRequirements {
+    #[serde(default)]
+    pub sender: CustomerTypes,
+    #[serde(default)]
+    pub receiver: CustomerTypes,
+}
+
+/// Limits, fees and requirements for one asset the anchor receives.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReceiveAssetInfo {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Whether a SEP-38 quote may be attached.
+    #[serde(default)]
+    pub quotes_supported: bool,
+    /// Whether a SEP-38 quote must be attached.
+    #[serde(default)]
+    pub quotes_required: bool,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub min_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub max_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub fee_fixed: Option<Amount>,
+    #[serde(default)]
+    pub fee_percent: Option<f64>,
+    #[serde(default)]
+    pub sep12: CustomerRequirements,
+    /// Off-chain funding methods, e.g. `SEPA`, for `funding_method`.
+    #[serde(default)]
+    pub funding_methods: Vec<String>,
+}
+
+/// What an anchor receives, as answered by `/info`. Keys are asset codes.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DirectPaymentInfo {
+    #[serde(default)]
+    pub receive: HashMap<String, ReceiveAssetInfo>,
+}
+
+/// Parameters of a `POST /transactions` request.
+///
+/// `sender_id` and `receiver_id` are SEP-12 customer IDs, registered beforehand
+/// with the types listed in [`ReceiveAssetInfo::sep12`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DirectPaymentRequest {
+    pub amount: Amount,
+    pub asset_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_issuer: Option<String>,
+    /// Off-chain asset the receiver gets, in SEP-38 format (e.g. `iso4217:BRL`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_asset: Option<String>,
+    /// A firm SEP-38 quote fixing the exchange rate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_id: Option<String>,
+    pub sender_id: String,
+    pub receiver_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub funding_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund_memo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund_memo_type: Option<String>,
+}
+
+/// The answer to a `POST /transactions` request.
+#[derive(Debug, Clone)]
+pub enum DirectPaymentOutcome {
+    /// The anchor created the transaction; fetch it to get the payment details.
+    Created { id: String },
+    /// A customer (`sender` or `receiver` type, e.g. `sep31-receiver`) must
+    /// provide more information through SEP-12 first.
+    CustomerInfoNeeded { customer_type: Option<String> },
+}
+
+/// A cross-border payment, as returned by `/transactions/:id`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DirectPaymentTransaction {
+    pub id: String,
+    pub status: TransferStatus,
+    #[serde(default)]
+    pub status_eta: Option<i64>,
+    /// The amount to send on Stellar.
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub amount_in: Option<Amount>,
+    #[serde(default)]
+    pub amount_in_asset: Option<String>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub amount_out: Option<Amount>,
+    #[serde(default)]
+    pub amount_out_asset: Option<String>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub amount_fee: Option<Amount>,
+    #[serde(default)]
+    pub quote_id: Option<String>,
+    /// The anchor's account to pay.
+    #[serde(default)]
+    pub stellar_account_id: Option<String>,
+    #[serde(default)]
+    pub stellar_memo_type: Option<String>,
+    #[serde(default)]
+    pub stellar_memo: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+    #[serde(default)]
+    pub stellar_transaction_id: Option<String>,
+    #[serde(default)]
+    pub external_transaction_id: Option<String>,
+    /// Set with `pending_transaction_info_update`: what to fix.
+    #[serde(default)]
+    pub required_info_message: Option<String>,
+}
+
+impl DirectPaymentTransaction {
+    /// Where and how much to pay for a transaction waiting for the sender
+    /// (`pending_sender`).
+    pub fn payment(&self) -> Result<(ResolvedDestination, Amount), Box<dyn std::error::Error>> {
+        if self.status != TransferStatus::PendingSender {
+            return Err(format!("Transaction {} isn't waiting for a payment (status '{}')", self.id, self.status).into());
+        }
+        let account_id = self
+            .stellar_account_id
+            .clone()
+            .ok_or_else(|| format!("Transaction {} has no stellar_account_id", self.id))?;
+        let amount = self.amount_in.ok_or_else(|| format!("Transaction {} has no amount_in", self.id))?;
+        let memo = FederationMemo::parse(self.stellar_memo_type.as_deref(), self.stellar_memo.as_deref())?;
+        Ok((ResolvedDestination { account_id, memo, stellar_address: None }, amount))
+    }
+}
+
+#[derive(Deserialize)]
+struct CreatedResponse {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct TransactionResponse {
+    transaction: DirectPaymentTransaction,
+}
+
+/// SEP-31 sending client for one anchor's `DIRECT_PAYMENT_SERVER`.
+#[derive(Debug, Clone)]
+pub struct DirectPaymentClient {
+    pub direct_payment_server: String,
+    /// SEP-10 token of the sending business; required by every endpoint.
+    pub token: Option<WebAuthToken>,
+    pub poll_interval: Duration,
+}
+
+impl DirectPaymentClient {
+    /// Creates a client for `direct_payment_server` without a token.
+    pub fn new(direct_payment_server: &str) -> Self {
+        DirectPaymentClient {
+            direct_payment_server: direct_payment_server.to_string(),
+            token: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Creates a client from the anchor's stellar.toml.
+    pub fn from_stellar_toml(toml: &StellarToml) -> Result<Self, Box<dyn std::error::Error>> {
+        let server = toml.direct_payment_server.as_deref().ok_or("The anchor has no DIRECT_PAYMENT_SERVER")?;
+        Ok(Self::new(server))
+    }
+
+    /// Sets the SEP-10 token sent with requests.
+    pub fn with_token(mut self, token: WebAuthToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Sets how often [`DirectPaymentClient::wait_for`] polls.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = reqwest::Client::new().request(method, anchor::endpoint(&self.direct_payment_server, path));
+        anchor::authorize(request, self.token.as_ref())
+    }
+
+    /// Fetches the assets the anchor receives and the customer info it requires.
+    pub async fn info(&self) -> Result<DirectPaymentInfo, Box<dyn std::error::Error>> {
+        anchor::send_json(self.request(reqwest::Method::GET, "info")).await
+    }
+
+    /// Creates a payment with the anchor.
+    ///
+    /// When `info` is given, the request is checked against it first: the asset
+    /// must be enabled, the amount within limits and a quote attached if the
+    /// anchor requires one.
+    pub async fn create_transaction(
+        &self,
+        request: &DirectPaymentRequest,
+        info: Option<&DirectPaymentInfo>,
+    ) -> Result<DirectPaymentOutcome, Box<dyn std::error::Error>> {
+        if let Some(info) = info {
+            check_request(request, info)?;
+        }
+        let sent = anchor::send_json::<CreatedResponse>(self.request(reqwest::Method::POST, "transactions").json(request)).await;
+        let error = match sent {
+            Ok(created) => return Ok(DirectPaymentOutcome::Created { id: created.id }),
+            Err(error) => error.downcast::<AnchorError>()?,
+        };
+        if error.status == 400 && error.message == "customer_info_needed" {
+            return Ok(DirectPaymentOutcome::CustomerInfoNeeded {
+                customer_type: error.body["type"].as_str().map(str::to_string),
+            });
+        }
+        Err(error)
+    }
+
+    /// Fetches one transaction by its anchor ID.
+    pub async fn transaction(&self, id: &str) -> Result<DirectPaymentTransaction, Box<dyn std::error::Error>> {
+        let path = format!("transactions/{}", id);
+        let response: TransactionResponse = anchor::send_json(self.request(reqwest::Method::GET, &path)).await?;
+        Ok(response.transaction)
+    }
+
+    /// Polls a transaction until its status is one of `statuses` or terminal.
+    ///
+    /// Pass [`TransferStatus::PendingSender`] to wait for the payment details,
+    /// and the `pending_*_info_update` statuses to catch anchor requests for
+    /// corrections.
+    pub async fn wait_for(
+        &self,
+        id: &str,
+        statuses: &[TransferStatus],
+        timeout: Duration,
+    ) -> Result<DirectPaymentTransaction, Box<dyn std::error::Error>> {
+        anchor::wait_for(id, statuses, self.poll_interval, timeout, |t: &DirectPaymentTransaction| &t.status, || self.transaction(id)).await
+    }
+}
+
+/// Checks a request against the anchor's `/info`.
+fn check_request(request: &DirectPaymentRequest, info: &DirectPaymentInfo) -> Result<(), Box<dyn std::error::Error>> {
+    let asset = info
+        .receive
+        .get(&request.asset_code)
+        .filter(|asset| asset.enabled)
+        .ok_or_else(|| format!("The anchor doesn't receive {}", request.asset_code))?;
+    if asset.quotes_required && request.quote_id.is_none() {
+        return Err(format!("The anchor requires a quote for {}", request.asset_code).into());
+    }
+    if request.quote_id.is_some() && !(asset.quotes_supported || asset.quotes_required) {
+        return Err(format!("The anchor doesn't support quotes for {}", request.asset_code).into());
+    }
+    if asset.min_amount.is_some_and(|min| request.amount < min) {
+        return Err(format!("{} is below the anchor's minimum", request.amount).into());
+    }
+    if asset.max_amount.is_some_and(|max| request.amount > max) {
+        return Err(format!("{} is above the anchor's maximum", request.amount).into());
+    }
+    Ok(())
+}
+
+impl StellarWalletTools {
+    /// Builds, signs and submits the Stellar payment for a SEP-31 transaction
+    /// waiting for the sender (`pending_sender`).
+    ///
+    /// # Arguments
+    /// * `source_secret_key` - The secret key of the sending account.
+    /// * `transaction` - The anchor transaction, as returned by [`DirectPaymentClient::transaction`].
+    /// * `asset` - The asset sent (the anchor's `asset_code`/`asset_issuer`).
+    /// * `network` - The network profile to submit to.
+    ///
+    /// # Returns
+    /// A `Result` containing the hash of the submitted transaction.
+    pub async fn send_direct_payment(
+        source_secret_key: &str,
+        transaction: &DirectPaymentTransaction,
+        asset: &AssetId,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let (destination, amount) = transaction.payment()?;
+        let xdr = Self::create_payment_to_destination_transaction(source_secret_key, &destination, amount, asset, network).await?;
+        horizon::submit_transaction(&network.horizon_url, &xdr).await
+    }
+}
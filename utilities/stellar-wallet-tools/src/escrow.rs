@@ -0,0 +1,178 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\escrow.rs
+// Time-locked escrows built from pre-authorized (pre-signed) transactions.
+
+use crate::network::NetworkProfile;
+use crate::{sequence, strkey, tx, StellarWalletTools};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use stellar_sdk::{AccountMergeOperation, Keypair, SetOptionsOperation};
+
+/// Hash a pre-signed transaction is authorized by; see [`pre_auth_signer_operation`].
+pub use crate::tx::transaction_hash;
+
+/// Builds a `SetOptions` operation adding the transaction with `hash` as a `PreAuthTx` signer.
+///
+/// The signer is consumed (removed) automatically when that transaction is applied.
+pub fn pre_auth_signer_operation(hash: &[u8; 32], weight: u8) -> SetOptionsOperation {
+    SetOptionsOperation::new().signer(strkey::encode_pre_auth_tx(hash), weight)
+}
+
+/// The conditions of a time-locked escrow holding XLM.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowTerms {
+    /// Account receiving the escrowed funds once `unlock_time` has passed (G...).
+    pub beneficiary: String,
+    /// Account getting the funds back if they are not released (G...).
+    pub recovery_account: String,
+    /// UNIX time from which the release transaction is valid.
+    pub unlock_time: i64,
+    /// UNIX time from which the recovery transaction is valid; must be after `unlock_time`.
+    pub recovery_time: i64,
+}
+
+impl EscrowTerms {
+    /// Checks that the accounts are valid and the recovery window opens after the release one.
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        strkey::decode_account_id(&self.beneficiary)?;
+        strkey::decode_account_id(&self.recovery_account)?;
+        if self.unlock_time <= 0 {
+            return Err("The unlock time must be a UNIX timestamp".into());
+        }
+        if self.recovery_time <= self.unlock_time {
+            return Err("The recovery time must be after the unlock time".into());
+        }
+        Ok(())
+    }
+}
+
+/// A transaction signed in advance, authorized by a `PreAuthTx` signer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreSignedTransaction {
+    /// Base64-encoded envelope, ready to submit once `valid_from` has passed.
+    pub xdr: String,
+    /// Hex-encoded transaction hash.
+    pub hash: String,
+    /// The `T...` signer key registered on the escrow account.
+    pub signer_key: String,
+    pub sequence: i64,
+    /// UNIX time from which the transaction is valid.
+    pub valid_from: i64,
+}
+
+/// Everything needed to run an escrow, saved as JSON so it can be kept offline.
+///
+/// Submit `setup_xdr` first; afterwards only `release` (from the unlock time) or
+/// `recovery` (from the recovery time) can move the funds, and whichever goes
+/// first invalidates the other because both use the same sequence number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowBundle {
+    pub escrow_account: String,
+    pub network_passphrase: String,
+    pub terms: EscrowTerms,
+    /// Signed by the escrow key: adds both pre-auth signers and disables the master key.
+    pub setup_xdr: String,
+    pub release: PreSignedTransaction,
+    pub recovery: PreSignedTransaction,
+}
+
+impl EscrowBundle {
+    /// Writes the bundle to `path` as pretty-printed JSON.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Reads a bundle written by [`EscrowBundle::save`].
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+/// Builds an escrow bundle offline for an escrow account whose current sequence
+/// number is `current_sequence`.
+///
+/// The setup transaction uses `current_sequence + 1` and the release and recovery
+/// transactions `current_sequence + 2`. Both merge the escrow account into their
+/// destination, so the whole XLM balance is transferred.
+pub fn build_escrow_bundle(
+    escrow_keypair: &Keypair,
+    current_sequence: i64,
+    terms: &EscrowTerms,
+    network: &NetworkProfile,
+) -> Result<EscrowBundle, Box<dyn std::error::Error>> {
+    terms.validate()?;
+    let escrow_id = escrow_keypair.public_key().to_string();
+    let pre_signed_sequence = current_sequence + 2;
+
+    let pre_sign = |destination: &str, valid_from: i64| -> Result<PreSignedTransaction, Box<dyn std::error::Error>> {
+        let operation = AccountMergeOperation::new(destination.to_string());
+        let transaction = tx::build_transaction_at_sequence(
+            network,
+            &escrow_id,
+            pre_signed_sequence,
+            Some((valid_from, 0)),
+            None,
+            vec![operation.into()],
+        )?;
+        let hash = transaction.hash();
+        Ok(PreSignedTransaction {
+            xdr: transaction.to_xdr_base64(),
+            hash: hash.iter().map(|b| format!("{:02x}", b)).collect(),
+            signer_key: strkey::encode_pre_auth_tx(&hash),
+            sequence: pre_signed_sequence,
+            valid_from,
+        })
+    };
+    let release = pre_sign(&terms.beneficiary, terms.unlock_time)?;
+    let recovery = pre_sign(&terms.recovery_account, terms.recovery_time)?;
+
+    // Each pre-auth signer alone meets every threshold; the master key is disabled.
+    let operations = vec![
+        SetOptionsOperation::new().signer(release.signer_key.clone(), 1).into(),
+        SetOptionsOperation::new().signer(recovery.signer_key.clone(), 1).into(),
+        SetOptionsOperation::new()
+            .master_weight(0)
+            .low_threshold(1)
+            .med_threshold(1)
+            .high_threshold(1)
+            .into(),
+    ];
+    let setup = tx::build_transaction_at_sequence(network, &escrow_id, current_sequence + 1, None, None, operations)?;
+    let setup_xdr = setup.sign(escrow_keypair)?.to_xdr_base64();
+
+    Ok(EscrowBundle {
+        escrow_account: escrow_id,
+        network_passphrase: network.network_passphrase.clone(),
+        terms: terms.clone(),
+        setup_xdr,
+        release,
+        recovery,
+    })
+}
+
+impl StellarWalletTools {
+    /// Prepares a time-locked escrow on an existing, funded escrow account.
+    ///
+    /// Nothing is submitted: the returned bundle holds the signed setup transaction
+    /// and the pre-signed release and recovery transactions. Save it (see
+    /// [`EscrowBundle::save`]) before submitting the setup, since afterwards the
+    /// escrow key can no longer sign.
+    ///
+    /// # Arguments
+    /// * `escrow_secret_key` - The secret key of the escrow account.
+    /// * `terms` - Beneficiary, recovery account and time locks.
+    /// * `network` - The network profile to build the transactions for.
+    ///
+    /// # Returns
+    /// A `Result` containing the `EscrowBundle` on success.
+    pub async fn create_escrow_bundle(
+        escrow_secret_key: &str,
+        terms: &EscrowTerms,
+        network: &NetworkProfile,
+    ) -> Result<EscrowBundle, Box<dyn std::error::Error>> {
+        let escrow_keypair = Keypair::from_secret(escrow_secret_key)?;
+        let current_sequence =
+            sequence::fetch_sequence(&network.horizon_url, &escrow_keypair.public_key().to_string()).await?;
+        build_escrow_bundle(&escrow_keypair, current_sequence, terms, network)
+    }
+}
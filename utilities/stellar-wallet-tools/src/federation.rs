@@ -0,0 +1,250 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\federation.rs
+// Federation (SEP-0002): resolves `name*domain.com` addresses to account IDs.
+
+use crate::amount::Amount;
+use crate::asset::AssetId;
+use crate::network::NetworkProfile;
+use crate::stellar_toml::StellarTomlFetcher;
+use crate::{strkey, tx, StellarWalletTools};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use stellar_sdk::{Keypair, Memo, PaymentOperation};
+
+/// How long resolved addresses are cached by default.
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// A memo the recipient requires on payments to a federated address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum FederationMemo {
+    Text(String),
+    Id(u64),
+    /// Base64-encoded 32-byte hash.
+    Hash(String),
+}
+
+impl FederationMemo {
+    /// Reads a `memo_type`/`memo` pair (`text`, `id` or `hash`), as sent by federation
+    /// servers and anchors. A missing type means `text`; a missing or empty memo, no memo.
+    pub(crate) fn parse(memo_type: Option<&str>, memo: Option<&str>) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let memo = match memo {
+            Some(memo) if !memo.is_empty() => memo,
+            _ => return Ok(None),
+        };
+        Ok(Some(match memo_type.unwrap_or("text") {
+            "text" => FederationMemo::Text(memo.to_string()),
+            "id" => FederationMemo::Id(memo.parse().map_err(|_| format!("Invalid ID memo '{}'", memo))?),
+            "hash" => FederationMemo::Hash(memo.to_string()),
+            other => return Err(format!("Unsupported memo type '{}'", other).into()),
+        }))
+    }
+
+    /// The memo type as written in `memo_type` fields: `text`, `id` or `hash`.
+    pub fn memo_type(&self) -> &'static str {
+        match self {
+            FederationMemo::Text(_) => "text",
+            FederationMemo::Id(_) => "id",
+            FederationMemo::Hash(_) => "hash",
+        }
+    }
+
+    /// Converts the memo for a transaction builder.
+    pub fn to_sdk_memo(&self) -> Result<Memo, Box<dyn std::error::Error>> {
+        Ok(match self {
+            FederationMemo::Text(text) => Memo::text(text)?,
+            FederationMemo::Id(id) => Memo::id(*id),
+            FederationMemo::Hash(hash) => {
+                let bytes: [u8; 32] = BASE64
+                    .decode(hash)?
+                    .try_into()
+                    .map_err(|_| format!("Hash memo '{}' is not 32 bytes", hash))?;
+                Memo::hash(bytes)
+            }
+        })
+    }
+}
+
+impl fmt::Display for FederationMemo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FederationMemo::Text(text) => write!(f, "{}", text),
+            FederationMemo::Id(id) => write!(f, "{}", id),
+            FederationMemo::Hash(hash) => write!(f, "{}", hash),
+        }
+    }
+}
+
+/// The result of resolving a destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedDestination {
+    pub account_id: String,
+    /// The memo that must be attached to payments, if any.
+    pub memo: Option<FederationMemo>,
+    /// The federation address that was resolved, if the input was one.
+    pub stellar_address: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FederationResponse {
+    account_id: String,
+    #[serde(default)]
+    memo_type: Option<String>,
+    /// Servers disagree on whether ID memos are strings or numbers.
+    #[serde(default)]
+    memo: Option<serde_json::Value>,
+}
+
+/// Whether `input` looks like a federation address (`name*domain`).
+pub fn is_federation_address(input: &str) -> bool {
+    input.contains('*')
+}
+
+/// Splits a federation address into its name and domain.
+///
+/// The name may itself contain `*` (e.g. an email address), so the split is at the last one.
+pub fn split_federation_address(address: &str) -> Result<(&str, &str), Box<dyn std::error::Error>> {
+    match address.rsplit_once('*') {
+        Some((name, domain)) if !name.is_empty() && !domain.is_empty() => Ok((name, domain)),
+        _ => Err(format!("'{}' is not a federation address (name*domain)", address).into()),
+    }
+}
+
+/// Resolves federation addresses, caching the answers.
+///
+/// The resolver is meant to be kept around (it is `Sync`), so repeated payments
+/// to the same address don't hit the federation server every time.
+#[derive(Debug)]
+pub struct FederationResolver {
+    toml_fetcher: StellarTomlFetcher,
+    cache_ttl: Duration,
+    cache: Mutex<HashMap<String, (ResolvedDestination, Instant)>>,
+}
+
+impl Default for FederationResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FederationResolver {
+    pub fn new() -> Self {
+        FederationResolver {
+            toml_fetcher: StellarTomlFetcher::new(),
+            cache_ttl: DEFAULT_CACHE_TTL,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets how long answers are cached (zero disables the cache).
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Fetches stellar.toml over plain HTTP. Only meant for local test servers.
+    pub fn insecure_http(mut self) -> Self {
+        self.toml_fetcher = self.toml_fetcher.insecure_http();
+        self
+    }
+
+    /// Resolves a destination given either as an account ID (`G...`) or a federation address.
+    pub async fn resolve_destination(&self, destination: &str) -> Result<ResolvedDestination, Box<dyn std::error::Error>> {
+        let destination = destination.trim();
+        if is_federation_address(destination) {
+            return self.resolve(destination).await;
+        }
+        strkey::decode_account_id(destination)?;
+        Ok(ResolvedDestination { account_id: destination.to_string(), memo: None, stellar_address: None })
+    }
+
+    /// Resolves a federation address (`name*domain`) to an account ID and memo.
+    pub async fn resolve(&self, address: &str) -> Result<ResolvedDestination, Box<dyn std::error::Error>> {
+        let (name, domain) = split_federation_address(address)?;
+        // Domains are case-insensitive; names are left as given.
+        let cache_key = format!("{}*{}", name, domain.to_lowercase());
+        if let Some((resolved, at)) = self.cache.lock().unwrap().get(&cache_key) {
+            if at.elapsed() < self.cache_ttl {
+                return Ok(resolved.clone());
+            }
+        }
+
+        let federation_server = self.federation_server(domain).await?;
+        let response = reqwest::Client::new()
+            .get(&federation_server)
+            .query(&[("q", address), ("type", "name")])
+            .send()
+            .await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(format!("Federation address '{}' not found", address).into());
+        }
+        if !status.is_success() {
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let detail = body["detail"].as_str().or(body["error"].as_str()).unwrap_or("");
+            return Err(format!("Federation request failed ({}): {}", status, detail).trim_end().into());
+        }
+        let record: FederationResponse = response.json().await?;
+        strkey::decode_account_id(&record.account_id)?;
+
+        let memo = match &record.memo {
+            Some(serde_json::Value::String(memo)) => Some(memo.clone()),
+            Some(serde_json::Value::Number(memo)) => Some(memo.to_string()),
+            _ => None,
+        };
+        let resolved = ResolvedDestination {
+            memo: FederationMemo::parse(record.memo_type.as_deref(), memo.as_deref())?,
+            account_id: record.account_id,
+            stellar_address: Some(address.to_string()),
+        };
+        self.cache.lock().unwrap().insert(cache_key, (resolved.clone(), Instant::now()));
+        Ok(resolved)
+    }
+
+    /// Reads `FEDERATION_SERVER` from the domain's stellar.toml.
+    async fn federation_server(&self, domain: &str) -> Result<String, Box<dyn std::error::Error>> {
+        self.toml_fetcher
+            .fetch(domain)
+            .await?
+            .federation_server
+            .ok_or_else(|| format!("The stellar.toml of {} has no FEDERATION_SERVER", domain).into())
+    }
+}
+
+impl StellarWalletTools {
+    /// Creates a signed payment to a resolved destination, attaching the memo the
+    /// recipient requires.
+    ///
+    /// # Arguments
+    /// * `source_secret_key` - The secret key of the account sending the payment.
+    /// * `destination` - The destination, as returned by [`FederationResolver::resolve_destination`].
+    /// * `amount` - The amount to send.
+    /// * `asset` - The asset to send.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_payment_to_destination_transaction(
+        source_secret_key: &str,
+        destination: &ResolvedDestination,
+        amount: Amount,
+        asset: &AssetId,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let operation = PaymentOperation::new(destination.account_id.clone(), asset.to_sdk_asset()?, amount.to_string());
+        let memo = destination.memo.as_ref().map(FederationMemo::to_sdk_memo).transpose()?;
+        let transaction = tx::build_transaction_with_memo(
+            network,
+            &source_keypair.public_key().to_string(),
+            memo,
+            vec![operation.into()],
+        )
+        .await?;
+        Ok(transaction.sign(&source_keypair)?.to_xdr_base64())
+    }
+}
@@ -0,0 +1,129 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\horizon.rs
+// Small helpers for Horizon's JSON endpoints that the SDK client doesn't cover.
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+/// A page of records as returned by Horizon's collection endpoints.
+#[derive(Debug, Deserialize)]
+struct Page<T> {
+    #[serde(rename = "_embedded")]
+    embedded: Embedded<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Embedded<T> {
+    records: Vec<T>,
+}
+
+/// Sends a GET request to `url` and decodes the JSON response.
+///
+/// Non-success responses are turned into an error carrying the `title` and
+/// `detail` of Horizon's problem document.
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    url: &str,
+    query: &[(String, String)],
+) -> Result<T, Box<dyn std::error::Error>> {
+    let response = reqwest::Client::new().get(url).query(query).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        let problem: serde_json::Value = response.json().await.unwrap_or_default();
+        return Err(format!(
+            "Horizon request failed ({}): {} {}",
+            status,
+            problem["title"].as_str().unwrap_or(""),
+            problem["detail"].as_str().unwrap_or("")
+        )
+        .trim_end()
+        .into());
+    }
+    Ok(response.json().await?)
+}
+
+/// Like [`get_json`], but returns `None` when Horizon answers 404 (e.g. an account
+/// that doesn't exist yet).
+pub(crate) async fn get_json_optional<T: DeserializeOwned>(
+    url: &str,
+    query: &[(String, String)],
+) -> Result<Option<T>, Box<dyn std::error::Error>> {
+    let response = reqwest::Client::new().get(url).query(query).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return get_json(url, query).await.map(Some);
+    }
+    Ok(Some(response.json().await?))
+}
+
+/// Fetches a collection endpoint and returns the records of its first page.
+pub(crate) async fn get_records<T: DeserializeOwned>(
+    url: &str,
+    query: &[(String, String)],
+) -> Result<Vec<T>, Box<dyn std::error::Error>> {
+    let page: Page<T> = get_json(url, query).await?;
+    Ok(page.embedded.records)
+}
+
+/// A transaction rejected by Horizon, with the result codes explaining why.
+#[derive(Debug, Clone)]
+pub struct SubmissionError {
+    pub status: u16,
+    pub title: String,
+    /// Transaction-level result code, e.g. `tx_bad_seq` or `tx_failed`.
+    pub transaction_code: Option<String>,
+    /// One result code per operation when the transaction failed, e.g. `op_underfunded`.
+    pub operation_codes: Vec<String>,
+}
+
+impl std::fmt::Display for SubmissionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transaction rejected ({}): {}", self.status, self.title)?;
+        if let Some(code) = &self.transaction_code {
+            write!(f, " [{}", code)?;
+            if !self.operation_codes.is_empty() {
+                write!(f, ": {}", self.operation_codes.join(", "))?;
+            }
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SubmissionError {}
+
+#[derive(Debug, Deserialize)]
+struct SubmitResponse {
+    hash: String,
+}
+
+/// Submits a signed transaction envelope and returns its hash once it is in a ledger.
+///
+/// Rejections are returned as a [`SubmissionError`] (inside the boxed error) so
+/// callers can inspect the result codes.
+pub(crate) async fn submit_transaction(
+    horizon_url: &str,
+    transaction_xdr: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let response = reqwest::Client::new()
+        .post(format!("{}/transactions", horizon_url))
+        .form(&[("tx", transaction_xdr)])
+        .send()
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        let problem: serde_json::Value = response.json().await.unwrap_or_default();
+        let codes = &problem["extras"]["result_codes"];
+        return Err(Box::new(SubmissionError {
+            status: status.as_u16(),
+            title: problem["title"].as_str().unwrap_or("Unknown error").to_string(),
+            transaction_code: codes["transaction"].as_str().map(str::to_string),
+            operation_codes: codes["operations"]
+                .as_array()
+                .map(|ops| ops.iter().filter_map(|op| op.as_str().map(str::to_string)).collect())
+                .unwrap_or_default(),
+        }));
+    }
+    let result: SubmitResponse = response.json().await?;
+    Ok(result.hash)
+}
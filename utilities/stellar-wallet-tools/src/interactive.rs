@@ -0,0 +1,206 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\interactive.rs
+// Hosted (interactive) deposits and withdrawals with anchors (SEP-0024).
+
+use crate::amount::Amount;
+use crate::anchor::{self, AnchorTransaction, TransferStatus};
+use crate::asset::AssetId;
+use crate::network::NetworkProfile;
+use crate::stellar_toml::{StellarToml, StellarTomlFetcher};
+use crate::web_auth::WebAuthToken;
+use crate::StellarWalletTools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often [`InteractiveClient::wait_for`] polls by default.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Limits and fees for one asset in `/info`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct InteractiveAssetInfo {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub min_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub max_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub fee_fixed: Option<Amount>,
+    /// Percentage fee, e.g. `0.5` for 0.5%.
+    #[serde(default)]
+    pub fee_percent: Option<f64>,
+}
+
+/// What an anchor supports, as answered by `/info`. Keys are asset codes.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct InteractiveInfo {
+    #[serde(default)]
+    pub deposit: HashMap<String, InteractiveAssetInfo>,
+    #[serde(default)]
+    pub withdraw: HashMap<String, InteractiveAssetInfo>,
+}
+
+/// Parameters of an interactive deposit or withdrawal.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InteractiveRequest {
+    pub asset_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_issuer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+    /// The Stellar account to credit or debit; defaults to the authenticated one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Preferred language of the anchor's web pages, e.g. `en`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+impl InteractiveRequest {
+    /// A request for `asset`; the issuer is passed along for credit assets.
+    pub fn for_asset(asset: &AssetId) -> Self {
+        let (asset_code, asset_issuer) = match asset {
+            AssetId::Native => ("native".to_string(), None),
+            AssetId::Credit { code, issuer } => (code.clone(), Some(issuer.clone())),
+        };
+        InteractiveRequest { asset_code, asset_issuer, ..Default::default() }
+    }
+
+    /// Sets the amount.
+    pub fn amount(mut self, amount: Amount) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+}
+
+/// The anchor's answer to an interactive request: a page the user must open.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InteractiveResponse {
+    /// Always `interactive_customer_info_needed`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// URL of the anchor's web flow, to open in a browser or webview.
+    pub url: String,
+    /// ID of the anchor transaction, for [`InteractiveClient::transaction`].
+    pub id: String,
+}
+
+#[derive(Deserialize)]
+struct TransactionResponse {
+    transaction: AnchorTransaction,
+}
+
+/// SEP-24 client for one anchor's `TRANSFER_SERVER_SEP0024`.
+#[derive(Debug, Clone)]
+pub struct InteractiveClient {
+    pub transfer_server: String,
+    /// SEP-10 token; required by everything but `/info`.
+    pub token: Option<WebAuthToken>,
+    pub poll_interval: Duration,
+}
+
+impl InteractiveClient {
+    /// Creates a client for `transfer_server` without a token.
+    pub fn new(transfer_server: &str) -> Self {
+        InteractiveClient {
+            transfer_server: transfer_server.to_string(),
+            token: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Creates a client from the anchor's stellar.toml.
+    pub fn from_stellar_toml(toml: &StellarToml) -> Result<Self, Box<dyn std::error::Error>> {
+        let transfer_server = toml.transfer_server_sep0024.as_deref().ok_or("The anchor has no TRANSFER_SERVER_SEP0024")?;
+        Ok(Self::new(transfer_server))
+    }
+
+    /// Creates a client by fetching the stellar.toml of `home_domain`.
+    pub async fn from_domain(home_domain: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_stellar_toml(&StellarTomlFetcher::new().fetch(home_domain).await?)
+    }
+
+    /// Sets the SEP-10 token sent with requests.
+    pub fn with_token(mut self, token: WebAuthToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Sets how often [`InteractiveClient::wait_for`] polls.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        anchor::authorize(reqwest::Client::new().get(anchor::endpoint(&self.transfer_server, path)), self.token.as_ref())
+    }
+
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        anchor::authorize(reqwest::Client::new().post(anchor::endpoint(&self.transfer_server, path)), self.token.as_ref())
+    }
+
+    /// Fetches the assets the anchor supports and their limits.
+    pub async fn info(&self) -> Result<InteractiveInfo, Box<dyn std::error::Error>> {
+        anchor::send_json(self.get("info")).await
+    }
+
+    /// Starts an interactive deposit; the user continues at the returned URL.
+    pub async fn deposit(&self, request: &InteractiveRequest) -> Result<InteractiveResponse, Box<dyn std::error::Error>> {
+        anchor::send_json(self.post("transactions/deposit/interactive").json(request)).await
+    }
+
+    /// Starts an interactive withdrawal; the user continues at the returned URL.
+    pub async fn withdraw(&self, request: &InteractiveRequest) -> Result<InteractiveResponse, Box<dyn std::error::Error>> {
+        anchor::send_json(self.post("transactions/withdraw/interactive").json(request)).await
+    }
+
+    /// Fetches one transaction by its anchor ID.
+    pub async fn transaction(&self, id: &str) -> Result<AnchorTransaction, Box<dyn std::error::Error>> {
+        let response: TransactionResponse = anchor::send_json(self.get("transaction").query(&[("id", id)])).await?;
+        Ok(response.transaction)
+    }
+
+    /// Polls a transaction until its status is one of `statuses` or terminal.
+    ///
+    /// # Arguments
+    /// * `id` - The anchor transaction ID.
+    /// * `statuses` - Statuses to stop at, besides terminal ones.
+    /// * `timeout` - How long to wait before giving up.
+    pub async fn wait_for(
+        &self,
+        id: &str,
+        statuses: &[TransferStatus],
+        timeout: Duration,
+    ) -> Result<AnchorTransaction, Box<dyn std::error::Error>> {
+        anchor::wait_for(id, statuses, self.poll_interval, timeout, |t: &AnchorTransaction| &t.status, || self.transaction(id)).await
+    }
+
+    /// Polls a transaction until it is finished (completed, refunded, error, ...).
+    pub async fn wait_for_completion(&self, id: &str, timeout: Duration) -> Result<AnchorTransaction, Box<dyn std::error::Error>> {
+        self.wait_for(id, &[], timeout).await
+    }
+}
+
+impl StellarWalletTools {
+    /// Creates the signed payment that funds a SEP-24 withdrawal waiting for the
+    /// user's transfer, to the anchor's account and with the anchor's memo.
+    ///
+    /// # Arguments
+    /// * `source_secret_key` - The secret key of the withdrawing account.
+    /// * `withdrawal` - The withdrawal, in status `pending_user_transfer_start`.
+    /// * `asset` - The asset being withdrawn.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_withdrawal_payment_transaction(
+        source_secret_key: &str,
+        withdrawal: &AnchorTransaction,
+        asset: &AssetId,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let (destination, amount) = withdrawal.withdrawal_payment()?;
+        Self::create_payment_to_destination_transaction(source_secret_key, &destination, amount, asset, network).await
+    }
+}
@@ -0,0 +1,255 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\issuance.rs
+
+use crate::amount::Amount;
+use crate::asset::AssetId;
+use crate::authorization::{set_trustline_flags_operation, TrustlineAuthorization};
+use crate::network::NetworkProfile;
+use crate::{horizon, tx, StellarKeyPair, StellarWalletTools};
+use std::io::Write;
+use std::path::Path;
+use stellar_sdk::{
+    ChangeTrustOperation, CreateAccountOperation, Keypair, Operation, PaymentOperation,
+    SetOptionsOperation,
+};
+
+/// `AUTH_REQUIRED_FLAG`: holders must be authorized by the issuer before they can hold the asset.
+pub const AUTH_REQUIRED_FLAG: u32 = 0x1;
+/// `AUTH_REVOCABLE_FLAG`: the issuer can revoke a holder's authorization.
+pub const AUTH_REVOCABLE_FLAG: u32 = 0x2;
+/// `AUTH_IMMUTABLE_FLAG`: the flags can never be changed again.
+pub const AUTH_IMMUTABLE_FLAG: u32 = 0x4;
+/// `AUTH_CLAWBACK_ENABLED_FLAG`: new trustlines allow the issuer to claw the asset back.
+pub const AUTH_CLAWBACK_ENABLED_FLAG: u32 = 0x8;
+
+/// Account flags to set on the issuing account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IssuerFlags {
+    pub auth_required: bool,
+    pub auth_revocable: bool,
+    pub clawback_enabled: bool,
+}
+
+impl IssuerFlags {
+    /// The flags as the bit mask used by `SetOptions`.
+    pub fn bits(&self) -> u32 {
+        let mut bits = 0;
+        if self.auth_required {
+            bits |= AUTH_REQUIRED_FLAG;
+        }
+        if self.auth_revocable {
+            bits |= AUTH_REVOCABLE_FLAG;
+        }
+        if self.clawback_enabled {
+            bits |= AUTH_CLAWBACK_ENABLED_FLAG;
+        }
+        bits
+    }
+}
+
+/// How the new issuer and distributor accounts get their starting XLM.
+#[derive(Debug, Clone)]
+pub enum AccountFunding {
+    /// Ask the network's Friendbot (test networks only).
+    Friendbot,
+    /// Create both accounts from an existing funded account.
+    FromAccount { secret_key: String, starting_balance: Amount },
+}
+
+/// Everything needed to issue a new asset.
+#[derive(Debug, Clone)]
+pub struct IssuanceRequest {
+    pub asset_code: String,
+    /// Amount minted to the distribution account.
+    pub initial_supply: Amount,
+    pub flags: IssuerFlags,
+    /// Zero the issuer's master key weight afterwards, fixing the supply forever.
+    pub lock_issuer: bool,
+    /// Home domain set on the issuer, where the stellar.toml will be published.
+    pub home_domain: Option<String>,
+    pub funding: AccountFunding,
+}
+
+impl IssuanceRequest {
+    /// Checks the request for combinations the network would reject or that can't be used afterwards.
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.asset_code.is_empty()
+            || self.asset_code.len() > 12
+            || !self.asset_code.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(format!("Invalid asset code '{}'", self.asset_code).into());
+        }
+        if self.initial_supply.stroops() <= 0 {
+            return Err("The initial supply must be positive".into());
+        }
+        if self.flags.clawback_enabled && !self.flags.auth_revocable {
+            return Err("Clawback requires the auth revocable flag".into());
+        }
+        if self.lock_issuer && (self.flags.auth_required || self.flags.auth_revocable) {
+            // A locked issuer can no longer sign, so it could never authorize or revoke holders.
+            return Err("A locked issuer can't use auth required/revocable flags".into());
+        }
+        if let Some(domain) = &self.home_domain {
+            if domain.len() > 32 {
+                return Err("The home domain can be at most 32 characters".into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Result of a successful issuance.
+pub struct IssuedAsset {
+    pub asset: AssetId,
+    pub issuer: StellarKeyPair,
+    pub distributor: StellarKeyPair,
+    pub initial_supply: Amount,
+    pub flags: IssuerFlags,
+    pub locked: bool,
+    /// Hash of the transaction that configured the issuer and minted the supply.
+    pub transaction_hash: String,
+}
+
+impl IssuedAsset {
+    /// Renders the `[[CURRENCIES]]` entry describing this asset in a stellar.toml (SEP-1).
+    pub fn currency_toml(&self, name: Option<&str>, description: Option<&str>) -> String {
+        let (code, issuer) = match &self.asset {
+            AssetId::Credit { code, issuer } => (code.as_str(), issuer.as_str()),
+            AssetId::Native => ("XLM", ""),
+        };
+        let mut toml = String::from("[[CURRENCIES]]\n");
+        toml.push_str(&format!("code=\"{}\"\n", code));
+        toml.push_str(&format!("issuer=\"{}\"\n", issuer));
+        toml.push_str("display_decimals=7\n");
+        if let Some(name) = name {
+            toml.push_str(&format!("name=\"{}\"\n", escape_toml(name)));
+        }
+        if let Some(description) = description {
+            toml.push_str(&format!("desc=\"{}\"\n", escape_toml(description)));
+        }
+        let unit = crate::amount::STROOPS_PER_UNIT;
+        if self.locked && self.initial_supply.stroops() % unit == 0 {
+            // No more units can ever be minted (SEP-1 only takes whole numbers here).
+            toml.push_str(&format!("fixed_number={}\n", self.initial_supply.stroops() / unit));
+        }
+        if self.flags.auth_required {
+            toml.push_str("regulated=true\n");
+        }
+        toml
+    }
+
+    /// Appends the currency entry to a stellar.toml file, creating it if needed.
+    pub fn write_currency_toml(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        description: Option<&str>,
+    ) -> std::io::Result<()> {
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file)?;
+        file.write_all(self.currency_toml(name, description).as_bytes())
+    }
+}
+
+fn escape_toml(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The operations that configure the issuer and mint the initial supply, in order:
+/// issuer flags and home domain, distributor trustline, authorization (if required),
+/// the mint payment and, optionally, locking the issuer.
+///
+/// The transaction is sourced by the issuer; the trustline operation is executed
+/// by the distributor, so both have to sign.
+pub fn issuance_operations(
+    request: &IssuanceRequest,
+    issuer_id: &str,
+    distributor_id: &str,
+) -> Result<Vec<Operation>, Box<dyn std::error::Error>> {
+    let asset = AssetId::credit(&request.asset_code, issuer_id)?;
+    let sdk_asset = asset.to_sdk_asset()?;
+
+    let mut issuer_options = SetOptionsOperation::new().set_flags(request.flags.bits());
+    if let Some(domain) = &request.home_domain {
+        issuer_options = issuer_options.home_domain(domain.clone());
+    }
+
+    let mut operations: Vec<Operation> = vec![
+        issuer_options.into(),
+        ChangeTrustOperation::new(sdk_asset.clone().into(), None)
+            .with_source_account(distributor_id.to_string())
+            .into(),
+    ];
+    if request.flags.auth_required {
+        operations.push(
+            set_trustline_flags_operation(distributor_id, &asset, TrustlineAuthorization::Authorized)?.into(),
+        );
+    }
+    operations.push(
+        PaymentOperation::new(distributor_id.to_string(), sdk_asset, request.initial_supply.to_string()).into(),
+    );
+    if request.lock_issuer {
+        operations.push(SetOptionsOperation::new().master_weight(0).into());
+    }
+    Ok(operations)
+}
+
+impl StellarWalletTools {
+    /// Issues a new asset end to end.
+    ///
+    /// 1. Generates issuer and distributor key pairs and funds both accounts.
+    /// 2. Submits one transaction (signed by both) that sets the issuer flags and home domain,
+    ///    creates (and if needed authorizes) the distributor's trustline, mints the
+    ///    initial supply and optionally locks the issuer.
+    ///
+    /// # Arguments
+    /// * `request` - What to issue and how.
+    /// * `network` - The network profile to issue on.
+    ///
+    /// # Returns
+    /// A `Result` containing the `IssuedAsset` with both key pairs. Store the key pairs
+    /// safely: they are the only way to control the issuer and the distributed supply.
+    pub async fn issue_asset(
+        request: &IssuanceRequest,
+        network: &NetworkProfile,
+    ) -> Result<IssuedAsset, Box<dyn std::error::Error>> {
+        request.validate()?;
+
+        // 1. Create the two accounts.
+        let issuer = Self::generate_key_pair()?;
+        let distributor = Self::generate_key_pair()?;
+        match &request.funding {
+            AccountFunding::Friendbot => {
+                Self::fund_with_friendbot(&issuer.public_key, network).await?;
+                Self::fund_with_friendbot(&distributor.public_key, network).await?;
+            }
+            AccountFunding::FromAccount { secret_key, starting_balance } => {
+                let funder = Keypair::from_secret(secret_key)?;
+                let operations = vec![
+                    CreateAccountOperation::new(issuer.public_key.clone(), starting_balance.to_string()).into(),
+                    CreateAccountOperation::new(distributor.public_key.clone(), starting_balance.to_string()).into(),
+                ];
+                let xdr = tx::build_signed_transaction(network, &funder, operations).await?;
+                horizon::submit_transaction(&network.horizon_url, &xdr).await?;
+            }
+        }
+
+        // 2. Configure the issuer and mint the supply in a single atomic transaction.
+        let operations = issuance_operations(request, &issuer.public_key, &distributor.public_key)?;
+        let issuer_keypair = Keypair::from_secret(&issuer.secret_key)?;
+        let distributor_keypair = Keypair::from_secret(&distributor.secret_key)?;
+        let transaction = tx::build_transaction(network, &issuer.public_key, operations).await?;
+        let signed_transaction = transaction.sign(&issuer_keypair)?.sign(&distributor_keypair)?;
+        let transaction_hash =
+            horizon::submit_transaction(&network.horizon_url, &signed_transaction.to_xdr_base64()).await?;
+
+        Ok(IssuedAsset {
+            asset: AssetId::credit(&request.asset_code, &issuer.public_key)?,
+            issuer,
+            distributor,
+            initial_supply: request.initial_supply,
+            flags: request.flags,
+            locked: request.lock_issuer,
+            transaction_hash,
+        })
+    }
+}
@@ -0,0 +1,348 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\kyc.rs
+// Customer information (KYC) exchange with anchors (SEP-0012).
+
+use crate::anchor;
+use crate::stellar_toml::StellarToml;
+use crate::web_auth::WebAuthToken;
+use reqwest::multipart::{Form, Part};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Where the anchor stands on a customer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CustomerStatus {
+    /// All required information was provided and verified.
+    Accepted,
+    /// The information is being reviewed.
+    Processing,
+    /// More information is needed; see [`Customer::fields`].
+    NeedsInfo,
+    /// The customer can't be served.
+    Rejected,
+    Other(String),
+}
+
+impl<'de> Deserialize<'de> for CustomerStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "ACCEPTED" => CustomerStatus::Accepted,
+            "PROCESSING" => CustomerStatus::Processing,
+            "NEEDS_INFO" => CustomerStatus::NeedsInfo,
+            "REJECTED" => CustomerStatus::Rejected,
+            other => CustomerStatus::Other(other.to_string()),
+        })
+    }
+}
+
+/// The kind of value a field holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldType {
+    String,
+    /// A file, e.g. a photo of an ID document; sent as a multipart file part.
+    Binary,
+    Number,
+    /// An ISO 8601 date.
+    Date,
+    Other(String),
+}
+
+impl<'de> Deserialize<'de> for FieldType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "string" => FieldType::String,
+            "binary" => FieldType::Binary,
+            "number" => FieldType::Number,
+            "date" => FieldType::Date,
+            other => FieldType::Other(other.to_string()),
+        })
+    }
+}
+
+/// A field the anchor still needs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomerField {
+    #[serde(rename = "type")]
+    pub kind: FieldType,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Accepted values, if the field is a choice.
+    #[serde(default)]
+    pub choices: Vec<String>,
+    #[serde(default)]
+    pub optional: bool,
+}
+
+impl CustomerField {
+    /// Whether `value` fits this field's type and choices.
+    pub fn accepts(&self, value: &CustomerValue) -> bool {
+        match (value, &self.kind) {
+            (CustomerValue::Binary { .. }, FieldType::Binary) => true,
+            (CustomerValue::Binary { .. }, _) | (_, FieldType::Binary) => false,
+            (CustomerValue::Text(text), kind) => {
+                let type_ok = match kind {
+                    FieldType::Number => text.parse::<f64>().is_ok(),
+                    _ => true,
+                };
+                type_ok && (self.choices.is_empty() || self.choices.contains(text))
+            }
+        }
+    }
+}
+
+/// A field the customer already provided, with its review status.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProvidedField {
+    #[serde(rename = "type")]
+    pub kind: FieldType,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub optional: bool,
+    /// `ACCEPTED`, `PROCESSING`, `REJECTED` or `VERIFICATION_REQUIRED`.
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Why the value was rejected.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// The anchor's view of a customer, as answered by `GET /customer`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Customer {
+    /// Set once the anchor knows the customer.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub status: CustomerStatus,
+    /// Fields still to provide (SEP-9 names).
+    #[serde(default)]
+    pub fields: HashMap<String, CustomerField>,
+    #[serde(default)]
+    pub provided_fields: HashMap<String, ProvidedField>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl Customer {
+    /// Names of the required fields still missing, sorted.
+    pub fn missing_fields(&self) -> Vec<&str> {
+        let mut missing: Vec<&str> = self
+            .fields
+            .iter()
+            .filter(|(_, field)| !field.optional)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        missing.sort_unstable();
+        missing
+    }
+
+    /// Names of the provided fields the anchor rejected, sorted.
+    pub fn rejected_fields(&self) -> Vec<&str> {
+        let mut rejected: Vec<&str> = self
+            .provided_fields
+            .iter()
+            .filter(|(_, field)| field.status.as_deref() == Some("REJECTED"))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        rejected.sort_unstable();
+        rejected
+    }
+}
+
+/// Identifies the customer in `GET /customer`.
+///
+/// With a SEP-10 token, `account` defaults to the authenticated account; `memo`
+/// distinguishes users of a shared account.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CustomerQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo_type: Option<String>,
+    /// The kind of customer, e.g. `sep31-sender`, when the anchor has several.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+/// A value sent in `PUT /customer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CustomerValue {
+    Text(String),
+    Binary {
+        file_name: String,
+        /// MIME type, e.g. `image/png`.
+        content_type: Option<String>,
+        bytes: Vec<u8>,
+    },
+}
+
+/// The fields of a `PUT /customer` request, keyed by SEP-9 name.
+#[derive(Debug, Clone, Default)]
+pub struct CustomerUpdate {
+    /// Identifies the customer, as in [`CustomerQuery`].
+    pub customer: CustomerQuery,
+    pub fields: Vec<(String, CustomerValue)>,
+}
+
+impl CustomerUpdate {
+    /// An update for the customer identified by `customer`.
+    pub fn new(customer: CustomerQuery) -> Self {
+        CustomerUpdate { customer, fields: Vec::new() }
+    }
+
+    /// Adds a text field.
+    pub fn text(mut self, name: &str, value: &str) -> Self {
+        self.fields.push((name.to_string(), CustomerValue::Text(value.to_string())));
+        self
+    }
+
+    /// Adds a binary field.
+    pub fn binary(mut self, name: &str, file_name: &str, content_type: Option<&str>, bytes: Vec<u8>) -> Self {
+        self.fields.push((
+            name.to_string(),
+            CustomerValue::Binary {
+                file_name: file_name.to_string(),
+                content_type: content_type.map(str::to_string),
+                bytes,
+            },
+        ));
+        self
+    }
+
+    /// Adds a binary field read from `path`; the content type is guessed from the extension.
+    pub fn file(self, name: &str, path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let bytes = std::fs::read(path)?;
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| format!("Invalid file name '{}'", path.display()))?;
+        let content_type = match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("png") => Some("image/png"),
+            Some("jpg") | Some("jpeg") => Some("image/jpeg"),
+            Some("pdf") => Some("application/pdf"),
+            _ => None,
+        };
+        Ok(self.binary(name, file_name, content_type, bytes))
+    }
+
+    /// Checks the fields against the definitions in `customer`, returning one
+    /// message per field whose value doesn't fit.
+    pub fn check(&self, customer: &Customer) -> Vec<String> {
+        self.fields
+            .iter()
+            .filter_map(|(name, value)| {
+                let field = customer.fields.get(name)?;
+                (!field.accepts(value)).then(|| format!("'{}' doesn't accept this value (expected {:?})", name, field.kind))
+            })
+            .collect()
+    }
+
+    /// Builds the multipart body. Text fields come first: anchors may stream
+    /// the request and need the identifying fields before any file.
+    fn to_form(&self) -> Result<Form, Box<dyn std::error::Error>> {
+        let mut form = Form::new();
+        let identity = serde_json::to_value(&self.customer)?;
+        if let Some(identity) = identity.as_object() {
+            for (name, value) in identity {
+                form = form.text(name.clone(), value.as_str().unwrap_or_default().to_string());
+            }
+        }
+        for (name, value) in &self.fields {
+            if let CustomerValue::Text(text) = value {
+                form = form.text(name.clone(), text.clone());
+            }
+        }
+        for (name, value) in &self.fields {
+            if let CustomerValue::Binary { file_name, content_type, bytes } = value {
+                let mut part = Part::bytes(bytes.clone()).file_name(file_name.clone());
+                if let Some(content_type) = content_type {
+                    part = part.mime_str(content_type)?;
+                }
+                form = form.part(name.clone(), part);
+            }
+        }
+        Ok(form)
+    }
+}
+
+#[derive(Deserialize)]
+struct PutCustomerResponse {
+    id: String,
+}
+
+/// SEP-12 client for one anchor's `KYC_SERVER`.
+#[derive(Debug, Clone)]
+pub struct CustomerClient {
+    pub kyc_server: String,
+    /// SEP-10 token; required by every endpoint.
+    pub token: Option<WebAuthToken>,
+}
+
+impl CustomerClient {
+    /// Creates a client for `kyc_server` without a token.
+    pub fn new(kyc_server: &str) -> Self {
+        CustomerClient { kyc_server: kyc_server.to_string(), token: None }
+    }
+
+    /// Creates a client from the anchor's stellar.toml, falling back to
+    /// `TRANSFER_SERVER` when there is no `KYC_SERVER`, as the protocol allows.
+    pub fn from_stellar_toml(toml: &StellarToml) -> Result<Self, Box<dyn std::error::Error>> {
+        let kyc_server = toml
+            .kyc_server
+            .as_deref()
+            .or(toml.transfer_server.as_deref())
+            .ok_or("The anchor has no KYC_SERVER")?;
+        Ok(Self::new(kyc_server))
+    }
+
+    /// Sets the SEP-10 token sent with requests.
+    pub fn with_token(mut self, token: WebAuthToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = reqwest::Client::new().request(method, anchor::endpoint(&self.kyc_server, path));
+        anchor::authorize(request, self.token.as_ref())
+    }
+
+    /// Fetches the customer's status and the fields the anchor still needs.
+    pub async fn get_customer(&self, query: &CustomerQuery) -> Result<Customer, Box<dyn std::error::Error>> {
+        anchor::send_json(self.request(reqwest::Method::GET, "customer").query(query)).await
+    }
+
+    /// Uploads customer fields, binary ones as files.
+    ///
+    /// # Returns
+    /// A `Result` containing the anchor's customer ID, to use in later requests.
+    pub async fn put_customer(&self, update: &CustomerUpdate) -> Result<String, Box<dyn std::error::Error>> {
+        let request = self.request(reqwest::Method::PUT, "customer").multipart(update.to_form()?);
+        let response: PutCustomerResponse = anchor::send_json(request).await?;
+        Ok(response.id)
+    }
+
+    /// Deletes everything the anchor stores about `account` (and `memo`, for shared accounts).
+    pub async fn delete_customer(
+        &self,
+        account: &str,
+        memo: Option<&str>,
+        memo_type: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut body = serde_json::Map::new();
+        if let Some(memo) = memo {
+            body.insert("memo".to_string(), memo.into());
+            body.insert("memo_type".to_string(), memo_type.unwrap_or("id").into());
+        }
+        let request = self
+            .request(reqwest::Method::DELETE, &format!("customer/{}", account))
+            .json(&body);
+        anchor::send_json::<serde_json::Value>(request).await?;
+        Ok(())
+    }
+}
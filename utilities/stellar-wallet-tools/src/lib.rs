@@ -5,6 +5,68 @@ use stellar_sdk::sep::mnemonic::{Mnemonic, Language};
 use rand::thread_rng;
 use std::str::FromStr; // Required for parsing asset codes
 
+pub mod amount; // Fixed-point asset amounts (7 decimals)
+pub mod asset; // Asset identifiers ("native" or CODE:ISSUER)
+pub mod authorization; // Trustline authorization and clawback for regulated assets
+pub mod batch; // Batch payouts from CSV/JSON files with resumable progress
+pub mod channels; // Channel account pools for parallel transaction submission
+pub mod claimable_balance; // Claimable balances and claim predicates
+pub mod data; // Account data entries (ManageData)
+pub mod direct_payment; // Cross-border payments through receiving anchors (SEP-0031)
+pub mod escrow; // Pre-signed transactions, pre-auth tx signers and time-locked escrows
+pub mod federation; // Federation addresses (SEP-0002)
+pub mod interactive; // Interactive anchor deposits and withdrawals (SEP-0024)
+pub mod issuance; // Asset issuance: issuer/distributor setup, minting, stellar.toml entry
+pub mod kyc; // Customer information for anchors (SEP-0012)
+pub mod liquidity_pool; // AMM pool IDs, pool share trustlines, deposits and withdrawals
+pub mod network; // Network profiles (Testnet, Futurenet, local quickstart, Mainnet)
+pub mod offers; // SDEX offers (manage buy/sell, passive offers) and order books
+pub mod path_payment; // Path finding and strict send / strict receive path payments
+pub mod quotes; // Anchor price discovery and firm quotes (SEP-0038)
+pub mod sequence; // BumpSequence and local sequence number allocation for offline batches
+pub mod sponsorship; // Sponsored reserves (begin/end sponsoring, revoke sponsorship)
+pub mod stellar_toml; // stellar.toml (SEP-0001) parsing and fetching
+pub mod strkey; // StrKey encoding of account IDs and signer keys
+pub mod time; // RFC 3339 timestamps and the current UNIX time
+pub mod transfer; // Programmatic anchor deposits and withdrawals (SEP-0006)
+pub mod uri; // web+stellar: transaction and payment request URIs (SEP-0007)
+pub mod watcher; // Streaming payment watcher (Horizon server-sent events)
+pub mod web_auth; // Web authentication client (SEP-0010)
+pub mod web_auth_server; // Web authentication challenges and verification for services (SEP-0010)
+
+mod anchor; // Shared helpers for anchor APIs (auth header, errors, amounts)
+mod horizon; // Helpers for Horizon JSON endpoints
+mod tx; // Shared transaction building
+
+pub use amount::Amount;
+pub use anchor::{AnchorError, AnchorTransaction, TransferStatus};
+pub use asset::AssetId;
+pub use authorization::{AssetHolder, TrustlineAuthorization};
+pub use batch::{BatchPayout, PaymentRow, RowResult};
+pub use channels::{Channel, ChannelPool};
+pub use claimable_balance::{ClaimPredicate, ClaimableBalance, Claimant};
+pub use data::DataEntry;
+pub use direct_payment::{DirectPaymentClient, DirectPaymentOutcome, DirectPaymentRequest, DirectPaymentTransaction};
+pub use escrow::{EscrowBundle, EscrowTerms, PreSignedTransaction};
+pub use federation::{FederationMemo, FederationResolver, ResolvedDestination};
+pub use horizon::SubmissionError;
+pub use interactive::{InteractiveClient, InteractiveRequest, InteractiveResponse};
+pub use issuance::{IssuanceRequest, IssuedAsset, IssuerFlags};
+pub use kyc::{Customer, CustomerClient, CustomerQuery, CustomerStatus, CustomerUpdate, CustomerValue, FieldType};
+pub use liquidity_pool::{LiquidityPool, PoolPosition};
+pub use network::NetworkProfile;
+pub use offers::{Offer, OrderBook, Price};
+pub use path_payment::{PathAssets, PaymentPath};
+pub use quotes::{Quote, QuoteAsset, QuoteClient, QuoteRequest};
+pub use sequence::{SequenceAllocator, SequenceRange};
+pub use sponsorship::SponsoredEntry;
+pub use stellar_toml::{StellarToml, StellarTomlFetcher};
+pub use transfer::{DepositRequest, TransferClient, TransferOutcome, WithdrawRequest};
+pub use uri::{StellarUri, TransactionPreview, UriRequest};
+pub use watcher::{PaymentEvent, PaymentWatcher};
+pub use web_auth::{ChallengeError, WebAuthClient, WebAuthToken};
+pub use web_auth_server::{AuthenticatedAccount, WebAuthServer};
+
 /// Represents a generated Stellar key pair.
 pub struct StellarKeyPair {
     pub mnemonic_phrase: String,
@@ -51,6 +113,59 @@ impl StellarWalletTools {
         })
     }
 
+    /// Returns the public key (G...) belonging to a secret key (S...).
+    pub fn public_key_from_secret(secret_key: &str) -> Result<String, StellarSdkError> {
+        Ok(Keypair::from_secret(secret_key)?.public_key().to_string())
+    }
+
+    /// Funds a freshly generated account with Friendbot.
+    ///
+    /// New key pairs from `generate_key_pair` don't exist on the ledger until
+    /// someone sends them XLM; on test networks Friendbot does exactly that.
+    ///
+    /// # Arguments
+    /// * `public_key` - The account to create and fund (G...).
+    /// * `network` - The network profile whose Friendbot should be used.
+    ///
+    /// # Returns
+    /// A `Result` containing the hash of the funding transaction on success, or an error
+    /// if the network has no Friendbot or the request is rejected (e.g. the account already exists).
+    pub async fn fund_with_friendbot(
+        public_key: &str,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let friendbot_url = network
+            .friendbot_url
+            .as_deref()
+            .ok_or_else(|| format!("The {} network has no Friendbot", network.name))?;
+
+        // Make sure we only ask Friendbot for valid account IDs.
+        Keypair::from_public_key(public_key)?;
+
+        let response = reqwest::Client::new()
+            .get(friendbot_url)
+            .query(&[("addr", public_key)])
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            // Friendbot answers with a Horizon "problem" document on failure,
+            // but a proxy in front of it may not.
+            let text = response.text().await.unwrap_or_default();
+            let detail = serde_json::from_str::<serde_json::Value>(&text)
+                .ok()
+                .and_then(|body| body["detail"].as_str().map(str::to_string))
+                .unwrap_or(text);
+            return Err(format!("Friendbot request failed ({}): {}", status, detail).into());
+        }
+        let body: serde_json::Value = response.json().await?;
+
+        body["hash"]
+            .as_str()
+            .map(|hash| hash.to_string())
+            .ok_or_else(|| "Friendbot response did not contain a transaction hash".into())
+    }
+
     /// Creates a payment transaction.
     ///
     /// This is also an associated function, as transaction creation typically
@@ -116,4 +231,38 @@ impl StellarWalletTools {
         // Return the base64-encoded XDR
         Ok(signed_transaction.to_xdr_base64())
     }
+
+    /// Adds a signature to a transaction built elsewhere (e.g. by a sponsor or a co-signer).
+    ///
+    /// # Arguments
+    /// * `transaction_xdr` - The base64-encoded transaction envelope.
+    /// * `secret_key` - The secret key to sign with.
+    /// * `network_passphrase` - The passphrase of the network the transaction is for.
+    ///
+    /// # Returns
+    /// A `Result` containing the envelope with the extra signature, base64-encoded.
+    pub fn sign_transaction(
+        transaction_xdr: &str,
+        secret_key: &str,
+        network_passphrase: &str,
+    ) -> Result<String, StellarSdkError> {
+        let keypair = Keypair::from_secret(secret_key)?;
+        tx::add_signature(transaction_xdr, &keypair, network_passphrase)
+    }
+
+    /// Submits a signed transaction to the network's Horizon.
+    ///
+    /// # Arguments
+    /// * `transaction_xdr` - The signed, base64-encoded transaction envelope.
+    /// * `network` - The network profile to submit to.
+    ///
+    /// # Returns
+    /// A `Result` containing the transaction hash on success. If Horizon rejects the
+    /// transaction the error is a `SubmissionError` carrying the result codes.
+    pub async fn submit_transaction(
+        transaction_xdr: &str,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        horizon::submit_transaction(&network.horizon_url, transaction_xdr).await
+    }
 }
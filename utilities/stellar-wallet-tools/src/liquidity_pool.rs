@@ -0,0 +1,281 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\liquidity_pool.rs
+
+use crate::amount::Amount;
+use crate::asset::AssetId;
+use crate::network::NetworkProfile;
+use crate::offers::Price;
+use crate::{horizon, strkey, tx, StellarWalletTools};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use stellar_sdk::{
+    ChangeTrustAsset, ChangeTrustOperation, Keypair, LiquidityPoolAsset, LiquidityPoolDepositOperation,
+    LiquidityPoolWithdrawOperation, StellarSdkError,
+};
+
+/// Fee of constant product pools, in basis points (the only fee the protocol currently allows).
+pub const LIQUIDITY_POOL_FEE_V18: i32 = 30;
+
+/// XDR encoding of an `Asset`, which is also the order the protocol sorts pool assets by.
+fn asset_xdr(asset: &AssetId) -> Result<Vec<u8>, StellarSdkError> {
+    let mut xdr = Vec::new();
+    match asset {
+        AssetId::Native => xdr.extend_from_slice(&0i32.to_be_bytes()),
+        AssetId::Credit { code, issuer } => {
+            let width = if code.len() <= 4 { 4 } else { 12 };
+            let asset_type: i32 = if width == 4 { 1 } else { 2 };
+            xdr.extend_from_slice(&asset_type.to_be_bytes());
+            let mut padded = code.as_bytes().to_vec();
+            padded.resize(width, 0);
+            xdr.extend_from_slice(&padded);
+            // AccountID: PUBLIC_KEY_TYPE_ED25519 followed by the raw key
+            xdr.extend_from_slice(&0i32.to_be_bytes());
+            xdr.extend_from_slice(&strkey::decode_account_id(issuer).map_err(StellarSdkError::InvalidAsset)?);
+        }
+    }
+    Ok(xdr)
+}
+
+/// Returns the pool's assets in protocol order (A < B), rejecting identical assets.
+pub fn ordered_assets(asset_a: &AssetId, asset_b: &AssetId) -> Result<(AssetId, AssetId), StellarSdkError> {
+    let (xdr_a, xdr_b) = (asset_xdr(asset_a)?, asset_xdr(asset_b)?);
+    match xdr_a.cmp(&xdr_b) {
+        std::cmp::Ordering::Less => Ok((asset_a.clone(), asset_b.clone())),
+        std::cmp::Ordering::Greater => Ok((asset_b.clone(), asset_a.clone())),
+        std::cmp::Ordering::Equal => Err(StellarSdkError::InvalidAsset(
+            "A liquidity pool needs two different assets".to_string(),
+        )),
+    }
+}
+
+/// Computes the ID of the constant product pool for an asset pair, in either order.
+///
+/// The ID is the hex SHA-256 of the XDR-encoded `LiquidityPoolParameters`.
+pub fn compute_pool_id(asset_a: &AssetId, asset_b: &AssetId) -> Result<String, StellarSdkError> {
+    let (asset_a, asset_b) = ordered_assets(asset_a, asset_b)?;
+
+    let mut params = Vec::new();
+    params.extend_from_slice(&0i32.to_be_bytes()); // LIQUIDITY_POOL_CONSTANT_PRODUCT
+    params.extend_from_slice(&asset_xdr(&asset_a)?);
+    params.extend_from_slice(&asset_xdr(&asset_b)?);
+    params.extend_from_slice(&LIQUIDITY_POOL_FEE_V18.to_be_bytes());
+
+    Ok(Sha256::digest(&params).iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// One side of a pool.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PoolReserve {
+    pub asset: AssetId,
+    pub amount: Amount,
+}
+
+/// State of a liquidity pool as reported by Horizon.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LiquidityPool {
+    pub id: String,
+    pub fee_bp: u32,
+    pub total_trustlines: String,
+    pub total_shares: Amount,
+    /// Reserves in pool order (asset A first).
+    pub reserves: Vec<PoolReserve>,
+}
+
+impl LiquidityPool {
+    /// Current reserve ratio as `(reserve_a, reserve_b)` stroops, i.e. units of
+    /// asset A per unit of asset B, the ratio deposit price bounds are checked against.
+    pub fn price(&self) -> Option<(i64, i64)> {
+        match self.reserves.as_slice() {
+            [a, b] if a.amount.stroops() > 0 && b.amount.stroops() > 0 => {
+                Some((a.amount.stroops(), b.amount.stroops()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Amounts of each reserve that `shares` pool shares can be redeemed for.
+    pub fn underlying(&self, shares: Amount) -> Vec<PoolReserve> {
+        self.reserves
+            .iter()
+            .map(|reserve| {
+                let amount = if self.total_shares.stroops() == 0 {
+                    0
+                } else {
+                    (reserve.amount.stroops() as i128 * shares.stroops() as i128 / self.total_shares.stroops() as i128) as i64
+                };
+                PoolReserve {
+                    asset: reserve.asset.clone(),
+                    amount: Amount::from_stroops(amount),
+                }
+            })
+            .collect()
+    }
+}
+
+/// An account's position in a pool.
+#[derive(Debug, Clone)]
+pub struct PoolPosition {
+    pub pool: LiquidityPool,
+    /// Pool shares held by the account (zero if it has a trustline but no deposit).
+    pub shares: Amount,
+    /// The account's share of each reserve.
+    pub underlying: Vec<PoolReserve>,
+}
+
+#[derive(Deserialize)]
+struct AccountBalances {
+    balances: Vec<BalanceRecord>,
+}
+
+#[derive(Deserialize)]
+struct BalanceRecord {
+    asset_type: String,
+    liquidity_pool_id: Option<String>,
+    balance: Amount,
+}
+
+/// Fetches a pool's reserves and total shares.
+pub async fn get_pool(horizon_url: &str, pool_id: &str) -> Result<LiquidityPool, Box<dyn std::error::Error>> {
+    horizon::get_json(&format!("{}/liquidity_pools/{}", horizon_url, pool_id), &[]).await
+}
+
+/// Fetches a pool together with the shares `account_id` holds in it.
+pub async fn get_pool_position(
+    horizon_url: &str,
+    pool_id: &str,
+    account_id: &str,
+) -> Result<PoolPosition, Box<dyn std::error::Error>> {
+    let pool = get_pool(horizon_url, pool_id).await?;
+    let account: AccountBalances = horizon::get_json(&format!("{}/accounts/{}", horizon_url, account_id), &[]).await?;
+    let shares = account
+        .balances
+        .iter()
+        .find(|b| b.asset_type == "liquidity_pool_shares" && b.liquidity_pool_id.as_deref() == Some(pool_id))
+        .map(|b| b.balance)
+        .unwrap_or_default();
+
+    Ok(PoolPosition {
+        underlying: pool.underlying(shares),
+        pool,
+        shares,
+    })
+}
+
+/// Price bounds for a deposit: the pool's current A-per-B ratio widened by `slippage_bps` on each side.
+pub fn deposit_price_bounds(pool: &LiquidityPool, slippage_bps: u32) -> Result<(Price, Price), Box<dyn std::error::Error>> {
+    let (a, b) = pool.price().ok_or("The pool is empty; pass explicit price bounds")?;
+    let bps = slippage_bps.min(9_999) as u128;
+    let min = Price::from_ratio(a as u128 * (10_000 - bps), b as u128 * 10_000)?;
+    let max = Price::from_ratio(a as u128 * (10_000 + bps), b as u128 * 10_000)?;
+    Ok((min, max))
+}
+
+/// Builds a `ChangeTrust` operation for the shares of the pool of `asset_a`/`asset_b`.
+///
+/// `limit = None` trusts the maximum amount; a limit of zero removes the trustline.
+pub fn pool_share_trust_operation(
+    asset_a: &AssetId,
+    asset_b: &AssetId,
+    limit: Option<Amount>,
+) -> Result<ChangeTrustOperation, StellarSdkError> {
+    let (asset_a, asset_b) = ordered_assets(asset_a, asset_b)?;
+    let pool_asset = LiquidityPoolAsset::new(asset_a.to_sdk_asset()?, asset_b.to_sdk_asset()?, LIQUIDITY_POOL_FEE_V18)?;
+    Ok(ChangeTrustOperation::new(
+        ChangeTrustAsset::PoolShare(pool_asset),
+        limit.map(|l| l.to_string()),
+    ))
+}
+
+/// Builds a `LiquidityPoolDeposit` operation depositing at most the given amounts.
+/// The deposit fails unless the pool's A/B price lies within `[min_price, max_price]`.
+pub fn deposit_operation(
+    pool_id: &str,
+    max_amount_a: Amount,
+    max_amount_b: Amount,
+    min_price: Price,
+    max_price: Price,
+) -> LiquidityPoolDepositOperation {
+    LiquidityPoolDepositOperation::new(
+        pool_id.to_string(),
+        max_amount_a.to_string(),
+        max_amount_b.to_string(),
+        stellar_sdk::Price::new(min_price.n, min_price.d),
+        stellar_sdk::Price::new(max_price.n, max_price.d),
+    )
+}
+
+/// Builds a `LiquidityPoolWithdraw` operation redeeming `shares` for at least the given amounts.
+pub fn withdraw_operation(
+    pool_id: &str,
+    shares: Amount,
+    min_amount_a: Amount,
+    min_amount_b: Amount,
+) -> LiquidityPoolWithdrawOperation {
+    LiquidityPoolWithdrawOperation::new(
+        pool_id.to_string(),
+        shares.to_string(),
+        min_amount_a.to_string(),
+        min_amount_b.to_string(),
+    )
+}
+
+impl StellarWalletTools {
+    /// Creates a signed transaction that adds (or, with a zero limit, removes) a pool share trustline.
+    pub async fn create_pool_trust_transaction(
+        source_secret_key: &str,
+        asset_a: &AssetId,
+        asset_b: &AssetId,
+        limit: Option<Amount>,
+        network: &NetworkProfile,
+    ) -> Result<String, StellarSdkError> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let operation = pool_share_trust_operation(asset_a, asset_b, limit)?;
+        tx::build_signed_transaction(network, &source_keypair, vec![operation.into()]).await
+    }
+
+    /// Creates a signed deposit into a pool.
+    ///
+    /// # Arguments
+    /// * `source_secret_key` - The secret key of the depositing account (it must trust the pool shares).
+    /// * `pool_id` - The pool, as returned by `compute_pool_id`.
+    /// * `max_amount_a` / `max_amount_b` - Upper limits of each reserve asset to deposit.
+    /// * `slippage_bps` - Allowed movement of the pool price from its current value, in basis points.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_pool_deposit_transaction(
+        source_secret_key: &str,
+        pool_id: &str,
+        max_amount_a: Amount,
+        max_amount_b: Amount,
+        slippage_bps: u32,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let pool = get_pool(&network.horizon_url, pool_id).await?;
+        let (min_price, max_price) = deposit_price_bounds(&pool, slippage_bps)?;
+        let operation = deposit_operation(pool_id, max_amount_a, max_amount_b, min_price, max_price);
+        Ok(tx::build_signed_transaction(network, &source_keypair, vec![operation.into()]).await?)
+    }
+
+    /// Creates a signed withdrawal of `shares` from a pool.
+    ///
+    /// The minimum amounts are the current value of the shares minus `slippage_bps` basis points.
+    pub async fn create_pool_withdraw_transaction(
+        source_secret_key: &str,
+        pool_id: &str,
+        shares: Amount,
+        slippage_bps: u32,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let pool = get_pool(&network.horizon_url, pool_id).await?;
+        let expected = pool.underlying(shares);
+        let (min_a, min_b) = match expected.as_slice() {
+            [a, b] => (a.amount.minus_bps(slippage_bps), b.amount.minus_bps(slippage_bps)),
+            _ => return Err("Unexpected pool reserves".into()),
+        };
+        let operation = withdraw_operation(pool_id, shares, min_a, min_b);
+        Ok(tx::build_signed_transaction(network, &source_keypair, vec![operation.into()]).await?)
+    }
+}
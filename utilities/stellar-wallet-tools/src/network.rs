@@ -0,0 +1,98 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\network.rs
+
+/// Passphrase of the Stellar Testnet.
+pub const TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+/// Passphrase of Futurenet.
+pub const FUTURENET_PASSPHRASE: &str = "Test SDF Future Network ; October 2022";
+/// Passphrase of the public Stellar network (Mainnet).
+pub const PUBLIC_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+/// Passphrase used by the `stellar/quickstart` image in local (standalone) mode.
+pub const LOCAL_PASSPHRASE: &str = "Standalone Network ; February 2017";
+
+/// Everything the wallet needs to know to talk to one Stellar network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub horizon_url: String,
+    pub network_passphrase: String,
+    /// Friendbot endpoint, if the network has one (Mainnet does not).
+    pub friendbot_url: Option<String>,
+}
+
+impl NetworkProfile {
+    /// The SDF Testnet.
+    pub fn testnet() -> Self {
+        NetworkProfile {
+            name: "testnet".to_string(),
+            horizon_url: "https://horizon-testnet.stellar.org".to_string(),
+            network_passphrase: TESTNET_PASSPHRASE.to_string(),
+            friendbot_url: Some("https://friendbot.stellar.org".to_string()),
+        }
+    }
+
+    /// Futurenet, where upcoming protocol features are deployed first.
+    pub fn futurenet() -> Self {
+        NetworkProfile {
+            name: "futurenet".to_string(),
+            horizon_url: "https://horizon-futurenet.stellar.org".to_string(),
+            network_passphrase: FUTURENET_PASSPHRASE.to_string(),
+            friendbot_url: Some("https://friendbot-futurenet.stellar.org".to_string()),
+        }
+    }
+
+    /// A local `stellar/quickstart` container started with `--local`.
+    pub fn local() -> Self {
+        NetworkProfile {
+            name: "local".to_string(),
+            horizon_url: "http://localhost:8000".to_string(),
+            network_passphrase: LOCAL_PASSPHRASE.to_string(),
+            friendbot_url: Some("http://localhost:8000/friendbot".to_string()),
+        }
+    }
+
+    /// The public network. There is no Friendbot here: accounts must be funded with real XLM.
+    pub fn public() -> Self {
+        NetworkProfile {
+            name: "public".to_string(),
+            horizon_url: "https://horizon.stellar.org".to_string(),
+            network_passphrase: PUBLIC_PASSPHRASE.to_string(),
+            friendbot_url: None,
+        }
+    }
+
+    /// Looks up a built-in profile by name (`testnet`, `futurenet`, `local`, `public`/`mainnet`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "testnet" => Some(Self::testnet()),
+            "futurenet" => Some(Self::futurenet()),
+            "local" | "standalone" => Some(Self::local()),
+            "public" | "mainnet" => Some(Self::public()),
+            _ => None,
+        }
+    }
+
+    /// Returns the active profile.
+    ///
+    /// The profile is selected with the `STELLAR_NETWORK` environment variable
+    /// (defaulting to Testnet); `STELLAR_HORIZON_URL` and `STELLAR_FRIENDBOT_URL`
+    /// override the endpoints of the selected profile, e.g. for a quickstart
+    /// container on another port.
+    pub fn active() -> Result<Self, Box<dyn std::error::Error>> {
+        let name = std::env::var("STELLAR_NETWORK").unwrap_or_else(|_| "testnet".to_string());
+        let profile = Self::from_name(&name)
+            .ok_or_else(|| format!("Unknown network profile '{}'", name))?;
+        Ok(profile.with_env_overrides())
+    }
+
+    /// Applies the `STELLAR_HORIZON_URL` and `STELLAR_FRIENDBOT_URL` overrides
+    /// described in [`NetworkProfile::active`] to this profile.
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Ok(url) = std::env::var("STELLAR_HORIZON_URL") {
+            self.horizon_url = url;
+        }
+        if let Ok(url) = std::env::var("STELLAR_FRIENDBOT_URL") {
+            self.friendbot_url = Some(url);
+        }
+        self
+    }
+}
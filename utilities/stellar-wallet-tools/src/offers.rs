@@ -0,0 +1,367 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\offers.rs
+
+use crate::amount::Amount;
+use crate::asset::{AssetId, HorizonAsset};
+use crate::network::NetworkProfile;
+use crate::{horizon, tx, StellarWalletTools};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+use stellar_sdk::{
+    CreatePassiveSellOfferOperation, Keypair, ManageBuyOfferOperation, ManageSellOfferOperation,
+    StellarSdkError,
+};
+
+/// Error returned for prices that are malformed, zero, or don't fit the ledger's price format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPriceError(String);
+
+impl fmt::Display for InvalidPriceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid price '{}'", self.0)
+    }
+}
+
+impl std::error::Error for InvalidPriceError {}
+
+/// An offer price as the ledger stores it: a fraction `n / d` of two positive 32-bit integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Price {
+    pub n: i32,
+    pub d: i32,
+}
+
+impl Price {
+    /// Creates a price from a numerator and denominator, both of which must be positive.
+    pub fn new(n: i32, d: i32) -> Result<Self, InvalidPriceError> {
+        if n <= 0 || d <= 0 {
+            return Err(InvalidPriceError(format!("{}/{}", n, d)));
+        }
+        Ok(Price { n, d })
+    }
+
+    /// Approximates a decimal price with the closest fraction that fits in 32 bits.
+    ///
+    /// Decimals that can be represented exactly (e.g. `1.25` -> `5/4`) are; others use
+    /// the last continued-fraction convergent that doesn't overflow `i32`.
+    pub fn from_decimal(decimal: &str) -> Result<Self, InvalidPriceError> {
+        let error = || InvalidPriceError(decimal.to_string());
+        let trimmed = decimal.trim();
+        let (whole, fraction) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+        if (whole.is_empty() && fraction.is_empty())
+            || whole.len() > 10
+            || fraction.len() > 18
+            || !whole.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(error());
+        }
+
+        // Read the decimal exactly as numerator / 10^digits.
+        let digits = format!("{}{}", whole, fraction);
+        let numerator: u128 = digits.parse().map_err(|_| error())?;
+        let denominator = 10u128.pow(fraction.len() as u32);
+
+        Price::from_ratio(numerator, denominator).map_err(|_| error())
+    }
+
+    /// Approximates the ratio `num / den` with the closest fraction that fits in 32 bits.
+    pub fn from_ratio(num: u128, den: u128) -> Result<Self, InvalidPriceError> {
+        let (n, d) = best_rational(num, den).ok_or_else(|| InvalidPriceError(format!("{}/{}", num, den)))?;
+        Price::new(n, d)
+    }
+
+    /// The price as a decimal string with 7 decimals, like Horizon's `price` field.
+    pub fn to_decimal_string(&self) -> String {
+        let stroops = (self.n as i128 * crate::amount::STROOPS_PER_UNIT as i128) / self.d as i128;
+        Amount::from_stroops(stroops as i64).to_string()
+    }
+
+    fn to_sdk_price(self) -> stellar_sdk::Price {
+        stellar_sdk::Price::new(self.n, self.d)
+    }
+}
+
+/// Continued-fraction approximation of `num / den` with numerator and denominator <= `i32::MAX`.
+fn best_rational(mut num: u128, mut den: u128) -> Option<(i32, i32)> {
+    let max = i32::MAX as u128;
+    // Convergents h/k, seeded with h(-2)/k(-2) = 0/1 and h(-1)/k(-1) = 1/0.
+    let (mut h_prev, mut h) = (0u128, 1u128);
+    let (mut k_prev, mut k) = (1u128, 0u128);
+
+    while den != 0 {
+        let a = num / den;
+        let h_next = a.checked_mul(h)?.checked_add(h_prev)?;
+        let k_next = a.checked_mul(k)?.checked_add(k_prev)?;
+        if h_next > max || k_next > max {
+            break;
+        }
+        h_prev = h;
+        h = h_next;
+        k_prev = k;
+        k = k_next;
+
+        let remainder = num % den;
+        num = den;
+        den = remainder;
+    }
+
+    if h == 0 || k == 0 {
+        return None;
+    }
+    Some((h as i32, k as i32))
+}
+
+impl FromStr for Price {
+    type Err = InvalidPriceError;
+
+    /// Accepts either a fraction (`"5/4"`) or a decimal (`"1.25"`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('/') {
+            Some((n, d)) => {
+                let error = || InvalidPriceError(s.to_string());
+                Price::new(n.trim().parse().map_err(|_| error())?, d.trim().parse().map_err(|_| error())?)
+            }
+            None => Price::from_decimal(s),
+        }
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.n, self.d)
+    }
+}
+
+/// An open offer of an account, as listed by Horizon.
+#[derive(Debug, Clone)]
+pub struct Offer {
+    pub id: i64,
+    pub seller: String,
+    pub selling: AssetId,
+    pub buying: AssetId,
+    /// Amount of `selling` still on offer.
+    pub amount: Amount,
+    /// Units of `buying` asked per unit of `selling`.
+    pub price: Price,
+}
+
+#[derive(Deserialize)]
+struct OfferRecord {
+    id: String,
+    paging_token: String,
+    seller: String,
+    selling: HorizonAsset,
+    buying: HorizonAsset,
+    amount: Amount,
+    price_r: Price,
+}
+
+impl TryFrom<OfferRecord> for Offer {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(record: OfferRecord) -> Result<Self, Self::Error> {
+        Ok(Offer {
+            id: record.id.parse()?,
+            seller: record.seller,
+            selling: record.selling.try_into()?,
+            buying: record.buying.try_into()?,
+            amount: record.amount,
+            price: record.price_r,
+        })
+    }
+}
+
+/// One price level of an order book.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderBookLevel {
+    pub price_r: Price,
+    pub amount: Amount,
+}
+
+/// Bids and asks for an asset pair, best prices first.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderBook {
+    pub bids: Vec<OrderBookLevel>,
+    pub asks: Vec<OrderBookLevel>,
+}
+
+/// Lists the open offers of `account_id`.
+///
+/// Follows Horizon's pagination until all offers have been read.
+pub async fn list_offers(horizon_url: &str, account_id: &str) -> Result<Vec<Offer>, Box<dyn std::error::Error>> {
+    const PAGE_SIZE: usize = 200;
+    let mut offers = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let query = vec![
+            ("limit".to_string(), PAGE_SIZE.to_string()),
+            ("cursor".to_string(), cursor.clone()),
+        ];
+        let records: Vec<OfferRecord> =
+            horizon::get_records(&format!("{}/accounts/{}/offers", horizon_url, account_id), &query).await?;
+        let page_len = records.len();
+
+        for record in records {
+            cursor = record.paging_token.clone();
+            offers.push(Offer::try_from(record)?);
+        }
+
+        if page_len < PAGE_SIZE {
+            return Ok(offers);
+        }
+    }
+}
+
+/// Loads a single offer by its ID.
+pub async fn get_offer(horizon_url: &str, offer_id: i64) -> Result<Offer, Box<dyn std::error::Error>> {
+    let record: OfferRecord = horizon::get_json(&format!("{}/offers/{}", horizon_url, offer_id), &[]).await?;
+    Offer::try_from(record)
+}
+
+/// Fetches the order book for selling `selling` in exchange for `buying`.
+pub async fn order_book(
+    horizon_url: &str,
+    selling: &AssetId,
+    buying: &AssetId,
+    limit: u32,
+) -> Result<OrderBook, Box<dyn std::error::Error>> {
+    let mut query = selling.horizon_params("selling_");
+    query.extend(buying.horizon_params("buying_"));
+    query.push(("limit".to_string(), limit.to_string()));
+    horizon::get_json(&format!("{}/order_book", horizon_url), &query).await
+}
+
+/// Builds a `ManageSellOffer` operation selling `amount` of `selling` at `price` units of `buying` each.
+///
+/// Pass `offer_id = 0` to create a new offer, or an existing ID to update it
+/// (an `amount` of zero deletes it).
+pub fn manage_sell_offer_operation(
+    selling: &AssetId,
+    buying: &AssetId,
+    amount: Amount,
+    price: Price,
+    offer_id: i64,
+) -> Result<ManageSellOfferOperation, StellarSdkError> {
+    Ok(ManageSellOfferOperation::new(
+        selling.to_sdk_asset()?,
+        buying.to_sdk_asset()?,
+        amount.to_string(),
+        price.to_sdk_price(),
+        offer_id,
+    ))
+}
+
+/// Builds a `ManageBuyOffer` operation buying `buy_amount` of `buying` at `price` units of `selling` each.
+///
+/// `offer_id` behaves as for [`manage_sell_offer_operation`].
+pub fn manage_buy_offer_operation(
+    selling: &AssetId,
+    buying: &AssetId,
+    buy_amount: Amount,
+    price: Price,
+    offer_id: i64,
+) -> Result<ManageBuyOfferOperation, StellarSdkError> {
+    Ok(ManageBuyOfferOperation::new(
+        selling.to_sdk_asset()?,
+        buying.to_sdk_asset()?,
+        buy_amount.to_string(),
+        price.to_sdk_price(),
+        offer_id,
+    ))
+}
+
+/// Builds a `CreatePassiveSellOffer` operation, which doesn't take offers at exactly the same price.
+pub fn create_passive_sell_offer_operation(
+    selling: &AssetId,
+    buying: &AssetId,
+    amount: Amount,
+    price: Price,
+) -> Result<CreatePassiveSellOfferOperation, StellarSdkError> {
+    Ok(CreatePassiveSellOfferOperation::new(
+        selling.to_sdk_asset()?,
+        buying.to_sdk_asset()?,
+        amount.to_string(),
+        price.to_sdk_price(),
+    ))
+}
+
+impl StellarWalletTools {
+    /// Creates a signed transaction that creates (`offer_id = None`) or updates a sell offer.
+    ///
+    /// # Arguments
+    /// * `source_secret_key` - The secret key of the account placing the offer.
+    /// * `selling` / `buying` - The asset pair.
+    /// * `amount` - Amount of `selling` to offer.
+    /// * `price` - Units of `buying` per unit of `selling`.
+    /// * `offer_id` - The offer to update, or `None` for a new offer.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_sell_offer_transaction(
+        source_secret_key: &str,
+        selling: &AssetId,
+        buying: &AssetId,
+        amount: Amount,
+        price: Price,
+        offer_id: Option<i64>,
+        network: &NetworkProfile,
+    ) -> Result<String, StellarSdkError> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let operation = manage_sell_offer_operation(selling, buying, amount, price, offer_id.unwrap_or(0))?;
+        tx::build_signed_transaction(network, &source_keypair, vec![operation.into()]).await
+    }
+
+    /// Creates a signed transaction that creates (`offer_id = None`) or updates a buy offer.
+    ///
+    /// Arguments are as for `create_sell_offer_transaction`, except that `buy_amount`
+    /// is the amount of `buying` wanted and `price` is in units of `selling` per unit of `buying`.
+    pub async fn create_buy_offer_transaction(
+        source_secret_key: &str,
+        selling: &AssetId,
+        buying: &AssetId,
+        buy_amount: Amount,
+        price: Price,
+        offer_id: Option<i64>,
+        network: &NetworkProfile,
+    ) -> Result<String, StellarSdkError> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let operation = manage_buy_offer_operation(selling, buying, buy_amount, price, offer_id.unwrap_or(0))?;
+        tx::build_signed_transaction(network, &source_keypair, vec![operation.into()]).await
+    }
+
+    /// Creates a signed transaction that places a passive sell offer.
+    pub async fn create_passive_sell_offer_transaction(
+        source_secret_key: &str,
+        selling: &AssetId,
+        buying: &AssetId,
+        amount: Amount,
+        price: Price,
+        network: &NetworkProfile,
+    ) -> Result<String, StellarSdkError> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let operation = create_passive_sell_offer_operation(selling, buying, amount, price)?;
+        tx::build_signed_transaction(network, &source_keypair, vec![operation.into()]).await
+    }
+
+    /// Creates a signed transaction that cancels an open offer.
+    ///
+    /// The offer is loaded from Horizon first, since a cancellation has to repeat
+    /// the offer's asset pair and price with an amount of zero.
+    pub async fn create_cancel_offer_transaction(
+        source_secret_key: &str,
+        offer_id: i64,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let offer = get_offer(&network.horizon_url, offer_id).await?;
+        if offer.seller != source_keypair.public_key().to_string() {
+            return Err(format!("Offer {} does not belong to this account", offer_id).into());
+        }
+
+        let operation =
+            manage_sell_offer_operation(&offer.selling, &offer.buying, Amount::default(), offer.price, offer_id)?;
+        Ok(tx::build_signed_transaction(network, &source_keypair, vec![operation.into()]).await?)
+    }
+}
@@ -0,0 +1,225 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\path_payment.rs
+
+use crate::amount::Amount;
+use crate::asset::{AssetId, HorizonAsset};
+use crate::federation::FederationMemo;
+use crate::network::NetworkProfile;
+use crate::{horizon, tx, StellarWalletTools};
+use serde::Deserialize;
+use stellar_sdk::{
+    Keypair, PathPaymentStrictReceiveOperation, PathPaymentStrictSendOperation, StellarSdkError,
+};
+
+/// A candidate conversion path returned by Horizon's path-finding endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentPath {
+    pub source_asset: AssetId,
+    pub source_amount: Amount,
+    pub destination_asset: AssetId,
+    pub destination_amount: Amount,
+    /// Intermediate assets hopped through, excluding the source and destination assets.
+    pub path: Vec<AssetId>,
+}
+
+/// The "other side" of a path search: either the assets an account can hold
+/// (its trustlines plus XLM), or an explicit list of assets.
+#[derive(Debug, Clone)]
+pub enum PathAssets {
+    HeldBy(String),
+    List(Vec<AssetId>),
+}
+
+#[derive(Deserialize)]
+struct PathRecord {
+    source_asset_type: String,
+    source_asset_code: Option<String>,
+    source_asset_issuer: Option<String>,
+    source_amount: Amount,
+    destination_asset_type: String,
+    destination_asset_code: Option<String>,
+    destination_asset_issuer: Option<String>,
+    destination_amount: Amount,
+    path: Vec<HorizonAsset>,
+}
+
+impl TryFrom<PathRecord> for PaymentPath {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(record: PathRecord) -> Result<Self, Self::Error> {
+        let source_asset = AssetId::from_horizon(
+            &record.source_asset_type,
+            record.source_asset_code.as_deref(),
+            record.source_asset_issuer.as_deref(),
+        )
+        .ok_or_else(|| format!("Unsupported source asset type '{}'", record.source_asset_type))?;
+        let destination_asset = AssetId::from_horizon(
+            &record.destination_asset_type,
+            record.destination_asset_code.as_deref(),
+            record.destination_asset_issuer.as_deref(),
+        )
+        .ok_or_else(|| format!("Unsupported destination asset type '{}'", record.destination_asset_type))?;
+
+        Ok(PaymentPath {
+            source_asset,
+            source_amount: record.source_amount,
+            destination_asset,
+            destination_amount: record.destination_amount,
+            path: record.path.into_iter().map(AssetId::try_from).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+fn path_assets_param(name_account: &str, name_assets: &str, assets: &PathAssets) -> (String, String) {
+    match assets {
+        PathAssets::HeldBy(account) => (name_account.to_string(), account.clone()),
+        PathAssets::List(list) => (
+            name_assets.to_string(),
+            list.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(","),
+        ),
+    }
+}
+
+/// Finds paths that send exactly `source_amount` of `source_asset`.
+///
+/// Each returned path carries the `destination_amount` that would be received
+/// at current order book and pool prices, best offers first.
+pub async fn find_strict_send_paths(
+    horizon_url: &str,
+    source_asset: &AssetId,
+    source_amount: Amount,
+    destination: &PathAssets,
+) -> Result<Vec<PaymentPath>, Box<dyn std::error::Error>> {
+    let mut query = source_asset.horizon_params("source_");
+    query.push(("source_amount".to_string(), source_amount.to_string()));
+    query.push(path_assets_param("destination_account", "destination_assets", destination));
+
+    let records: Vec<PathRecord> =
+        horizon::get_records(&format!("{}/paths/strict-send", horizon_url), &query).await?;
+    records.into_iter().map(PaymentPath::try_from).collect()
+}
+
+/// Finds paths that deliver exactly `destination_amount` of `destination_asset`.
+///
+/// Each returned path carries the `source_amount` that would have to be sent.
+pub async fn find_strict_receive_paths(
+    horizon_url: &str,
+    source: &PathAssets,
+    destination_asset: &AssetId,
+    destination_amount: Amount,
+) -> Result<Vec<PaymentPath>, Box<dyn std::error::Error>> {
+    let mut query = destination_asset.horizon_params("destination_");
+    query.push(("destination_amount".to_string(), destination_amount.to_string()));
+    query.push(path_assets_param("source_account", "source_assets", source));
+
+    let records: Vec<PathRecord> =
+        horizon::get_records(&format!("{}/paths/strict-receive", horizon_url), &query).await?;
+    records.into_iter().map(PaymentPath::try_from).collect()
+}
+
+/// Builds a `PathPaymentStrictSend` operation from a quoted path.
+///
+/// The whole `source_amount` is sent; the payment fails unless at least the quoted
+/// `destination_amount` minus `slippage_bps` basis points arrives.
+pub fn strict_send_operation(
+    path: &PaymentPath,
+    destination: &str,
+    slippage_bps: u32,
+) -> Result<PathPaymentStrictSendOperation, StellarSdkError> {
+    Keypair::from_public_key(destination)?;
+    Ok(PathPaymentStrictSendOperation::new(
+        path.source_asset.to_sdk_asset()?,
+        path.source_amount.to_string(),
+        destination.to_string(),
+        path.destination_asset.to_sdk_asset()?,
+        path.destination_amount.minus_bps(slippage_bps).to_string(),
+        path.path.iter().map(AssetId::to_sdk_asset).collect::<Result<Vec<_>, _>>()?,
+    ))
+}
+
+/// Builds a `PathPaymentStrictReceive` operation from a quoted path.
+///
+/// Exactly `destination_amount` arrives; the payment fails if that would cost more
+/// than the quoted `source_amount` plus `slippage_bps` basis points.
+pub fn strict_receive_operation(
+    path: &PaymentPath,
+    destination: &str,
+    slippage_bps: u32,
+) -> Result<PathPaymentStrictReceiveOperation, StellarSdkError> {
+    Keypair::from_public_key(destination)?;
+    Ok(PathPaymentStrictReceiveOperation::new(
+        path.source_asset.to_sdk_asset()?,
+        path.source_amount.plus_bps(slippage_bps).to_string(),
+        destination.to_string(),
+        path.destination_asset.to_sdk_asset()?,
+        path.destination_amount.to_string(),
+        path.path.iter().map(AssetId::to_sdk_asset).collect::<Result<Vec<_>, _>>()?,
+    ))
+}
+
+impl StellarWalletTools {
+    /// Creates a signed strict-send path payment transaction.
+    ///
+    /// # Arguments
+    /// * `source_secret_key` - The secret key of the account sending the payment.
+    /// * `destination_public_key` - The receiving account (the source itself for a swap).
+    /// * `path` - A path from `find_strict_send_paths`.
+    /// * `slippage_bps` - How far below the quoted destination amount the result may fall, in basis points.
+    /// * `memo` - The memo the destination requires, e.g. from a federation lookup.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_path_payment_strict_send_transaction(
+        source_secret_key: &str,
+        destination_public_key: &str,
+        path: &PaymentPath,
+        slippage_bps: u32,
+        memo: Option<&FederationMemo>,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let operation = strict_send_operation(path, destination_public_key, slippage_bps)?;
+        let memo = memo.map(FederationMemo::to_sdk_memo).transpose()?;
+        let transaction = tx::build_transaction_with_memo(
+            network,
+            &source_keypair.public_key().to_string(),
+            memo,
+            vec![operation.into()],
+        )
+        .await?;
+        Ok(transaction.sign(&source_keypair)?.to_xdr_base64())
+    }
+
+    /// Creates a signed strict-receive path payment transaction.
+    ///
+    /// # Arguments
+    /// * `source_secret_key` - The secret key of the account sending the payment.
+    /// * `destination_public_key` - The receiving account.
+    /// * `path` - A path from `find_strict_receive_paths`.
+    /// * `slippage_bps` - How far above the quoted source amount the cost may rise, in basis points.
+    /// * `memo` - The memo the destination requires, e.g. from a federation lookup.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_path_payment_strict_receive_transaction(
+        source_secret_key: &str,
+        destination_public_key: &str,
+        path: &PaymentPath,
+        slippage_bps: u32,
+        memo: Option<&FederationMemo>,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let source_keypair = Keypair::from_secret(source_secret_key)?;
+        let operation = strict_receive_operation(path, destination_public_key, slippage_bps)?;
+        let memo = memo.map(FederationMemo::to_sdk_memo).transpose()?;
+        let transaction = tx::build_transaction_with_memo(
+            network,
+            &source_keypair.public_key().to_string(),
+            memo,
+            vec![operation.into()],
+        )
+        .await?;
+        Ok(transaction.sign(&source_keypair)?.to_xdr_base64())
+    }
+}
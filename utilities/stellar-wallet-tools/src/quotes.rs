@@ -0,0 +1,337 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\quotes.rs
+// Anchor request-for-quote: indicative prices and firm quotes (SEP-0038).
+
+use crate::amount::Amount;
+use crate::anchor;
+use crate::asset::AssetId;
+use crate::time::{format_rfc3339, parse_rfc3339};
+use crate::path_payment::PaymentPath;
+use crate::stellar_toml::StellarToml;
+use crate::web_auth::WebAuthToken;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// An asset in SEP-38 notation: `stellar:CODE:ISSUER` (or `stellar:native`)
+/// for Stellar assets, `iso4217:USD` and the like for off-chain ones.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QuoteAsset {
+    Stellar(AssetId),
+    /// An off-chain asset, kept as written (`scheme:code`).
+    OffChain(String),
+}
+
+impl QuoteAsset {
+    /// The Stellar asset, if this is one.
+    pub fn stellar(&self) -> Option<&AssetId> {
+        match self {
+            QuoteAsset::Stellar(asset) => Some(asset),
+            QuoteAsset::OffChain(_) => None,
+        }
+    }
+}
+
+impl From<AssetId> for QuoteAsset {
+    fn from(asset: AssetId) -> Self {
+        QuoteAsset::Stellar(asset)
+    }
+}
+
+impl FromStr for QuoteAsset {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (scheme, rest) = s.split_once(':').ok_or_else(|| format!("Invalid SEP-38 asset '{}'", s))?;
+        match scheme {
+            "stellar" if rest == "native" => Ok(QuoteAsset::Stellar(AssetId::Native)),
+            "stellar" => {
+                let (code, issuer) = rest.split_once(':').ok_or_else(|| format!("Invalid Stellar asset '{}'", s))?;
+                Ok(QuoteAsset::Stellar(AssetId::credit(code, issuer)?))
+            }
+            _ if !rest.is_empty() => Ok(QuoteAsset::OffChain(s.to_string())),
+            _ => Err(format!("Invalid SEP-38 asset '{}'", s).into()),
+        }
+    }
+}
+
+impl fmt::Display for QuoteAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuoteAsset::Stellar(AssetId::Native) => write!(f, "stellar:native"),
+            QuoteAsset::Stellar(AssetId::Credit { code, issuer }) => write!(f, "stellar:{}:{}", code, issuer),
+            QuoteAsset::OffChain(asset) => write!(f, "{}", asset),
+        }
+    }
+}
+
+impl Serialize for QuoteAsset {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for QuoteAsset {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(|e: Box<dyn std::error::Error>| serde::de::Error::custom(e.to_string()))
+    }
+}
+
+/// A way to deliver or receive off-chain funds, e.g. `SEPA`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeliveryMethod {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// An asset the anchor quotes, from `/info`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuoteAssetInfo {
+    pub asset: QuoteAsset,
+    #[serde(default)]
+    pub sell_delivery_methods: Vec<DeliveryMethod>,
+    #[serde(default)]
+    pub buy_delivery_methods: Vec<DeliveryMethod>,
+    /// ISO 3166 country codes the asset is available in.
+    #[serde(default)]
+    pub country_codes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct InfoResponse {
+    assets: Vec<QuoteAssetInfo>,
+}
+
+/// An indicative price for one asset, from `/prices`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndicativePrice {
+    pub asset: QuoteAsset,
+    /// Units of the sell asset per unit of the buy asset, as a decimal string.
+    pub price: String,
+    /// Decimals the anchor uses for this asset.
+    #[serde(default)]
+    pub decimals: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct PricesResponse {
+    #[serde(default)]
+    buy_assets: Vec<IndicativePrice>,
+}
+
+/// One line of a fee breakdown.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeeDetail {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub amount: Option<Amount>,
+}
+
+/// The fee included in a price or quote.
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuoteFee {
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub total: Option<Amount>,
+    pub asset: QuoteAsset,
+    #[serde(default)]
+    pub details: Vec<FeeDetail>,
+}
+
+/// An indicative price for an exchange, from `/price`. Not binding.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriceEstimate {
+    /// Price including fees, as a decimal string.
+    pub total_price: String,
+    /// Price excluding fees, as a decimal string.
+    pub price: String,
+    #[serde(deserialize_with = "required_amount")]
+    pub sell_amount: Amount,
+    #[serde(deserialize_with = "required_amount")]
+    pub buy_amount: Amount,
+    pub fee: QuoteFee,
+}
+
+/// A firm quote from `/quote`: the anchor honours it until `expires_at`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Quote {
+    pub id: String,
+    /// UNIX time after which the quote can't be used anymore.
+    #[serde(deserialize_with = "timestamp")]
+    pub expires_at: i64,
+    pub total_price: String,
+    pub price: String,
+    pub sell_asset: QuoteAsset,
+    #[serde(deserialize_with = "required_amount")]
+    pub sell_amount: Amount,
+    pub buy_asset: QuoteAsset,
+    #[serde(deserialize_with = "required_amount")]
+    pub buy_amount: Amount,
+    pub fee: QuoteFee,
+}
+
+impl Quote {
+    /// Whether the quote has expired at `now` (UNIX seconds).
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Seconds left before the quote expires, zero once expired.
+    pub fn seconds_left(&self, now: i64) -> i64 {
+        (self.expires_at - now).max(0)
+    }
+
+    /// Compares the quote's rate with an on-chain path for the same pair of
+    /// Stellar assets.
+    ///
+    /// # Returns
+    /// `Some(Ordering::Greater)` if the quote buys more per unit sold than the
+    /// path, `Some(Ordering::Less)` if the path is better, and `None` if the
+    /// assets differ or one side of the quote is off-chain.
+    pub fn compare_with_path(&self, path: &PaymentPath) -> Option<Ordering> {
+        if self.sell_asset.stellar()? != &path.source_asset || self.buy_asset.stellar()? != &path.destination_asset {
+            return None;
+        }
+        // buy / sell rates, compared by cross-multiplying to stay in integers.
+        let quote = self.buy_amount.stroops() as i128 * path.source_amount.stroops() as i128;
+        let on_chain = path.destination_amount.stroops() as i128 * self.sell_amount.stroops() as i128;
+        Some(quote.cmp(&on_chain))
+    }
+}
+
+/// Parameters of `/price` and `/quote` requests. Set exactly one of
+/// `sell_amount` and `buy_amount`.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuoteRequest {
+    pub sell_asset: QuoteAsset,
+    pub buy_asset: QuoteAsset,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sell_amount: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buy_amount: Option<Amount>,
+    /// The protocol the quote will be used with: `sep6`, `sep24` or `sep31`.
+    pub context: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sell_delivery_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buy_delivery_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+    /// Firm quotes: the earliest expiry the wallet accepts (UNIX seconds).
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "serialize_timestamp")]
+    pub expire_after: Option<i64>,
+}
+
+impl QuoteRequest {
+    /// A request to sell exactly `amount` of `sell_asset`.
+    pub fn sell(sell_asset: QuoteAsset, amount: Amount, buy_asset: QuoteAsset, context: &str) -> Self {
+        QuoteRequest {
+            sell_asset,
+            buy_asset,
+            sell_amount: Some(amount),
+            buy_amount: None,
+            context: context.to_string(),
+            sell_delivery_method: None,
+            buy_delivery_method: None,
+            country_code: None,
+            expire_after: None,
+        }
+    }
+
+    /// A request to buy exactly `amount` of `buy_asset`.
+    pub fn buy(sell_asset: QuoteAsset, buy_asset: QuoteAsset, amount: Amount, context: &str) -> Self {
+        QuoteRequest { sell_amount: None, buy_amount: Some(amount), ..Self::sell(sell_asset, amount, buy_asset, context) }
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.sell_amount.is_some() == self.buy_amount.is_some() {
+            return Err("Set exactly one of sell_amount and buy_amount".into());
+        }
+        Ok(())
+    }
+}
+
+fn required_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Amount, D::Error> {
+    anchor::optional_amount(deserializer)?.ok_or_else(|| serde::de::Error::custom("missing amount"))
+}
+
+fn timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse_rfc3339(&s).ok_or_else(|| serde::de::Error::custom(format!("Invalid timestamp '{}'", s)))
+}
+
+fn serialize_timestamp<S: Serializer>(timestamp: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error> {
+    match timestamp {
+        Some(timestamp) => serializer.serialize_str(&format_rfc3339(*timestamp)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// SEP-38 client for one anchor's `ANCHOR_QUOTE_SERVER`.
+#[derive(Debug, Clone)]
+pub struct QuoteClient {
+    pub quote_server: String,
+    /// SEP-10 token; optional for prices, required for firm quotes.
+    pub token: Option<WebAuthToken>,
+}
+
+impl QuoteClient {
+    /// Creates a client for `quote_server` without a token.
+    pub fn new(quote_server: &str) -> Self {
+        QuoteClient { quote_server: quote_server.to_string(), token: None }
+    }
+
+    /// Creates a client from the anchor's stellar.toml.
+    pub fn from_stellar_toml(toml: &StellarToml) -> Result<Self, Box<dyn std::error::Error>> {
+        let server = toml.anchor_quote_server.as_deref().ok_or("The anchor has no ANCHOR_QUOTE_SERVER")?;
+        Ok(Self::new(server))
+    }
+
+    /// Sets the SEP-10 token sent with requests.
+    pub fn with_token(mut self, token: WebAuthToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = reqwest::Client::new().request(method, anchor::endpoint(&self.quote_server, path));
+        anchor::authorize(request, self.token.as_ref())
+    }
+
+    /// Lists the assets the anchor quotes and their delivery methods.
+    pub async fn info(&self) -> Result<Vec<QuoteAssetInfo>, Box<dyn std::error::Error>> {
+        let response: InfoResponse = anchor::send_json(self.request(reqwest::Method::GET, "info")).await?;
+        Ok(response.assets)
+    }
+
+    /// Indicative prices for everything `sell_amount` of `sell_asset` can buy.
+    pub async fn prices(
+        &self,
+        sell_asset: &QuoteAsset,
+        sell_amount: Amount,
+    ) -> Result<Vec<IndicativePrice>, Box<dyn std::error::Error>> {
+        let query = [("sell_asset", sell_asset.to_string()), ("sell_amount", sell_amount.to_string())];
+        let response: PricesResponse = anchor::send_json(self.request(reqwest::Method::GET, "prices").query(&query)).await?;
+        Ok(response.buy_assets)
+    }
+
+    /// An indicative (non-binding) price for one exchange.
+    pub async fn price(&self, request: &QuoteRequest) -> Result<PriceEstimate, Box<dyn std::error::Error>> {
+        request.validate()?;
+        anchor::send_json(self.request(reqwest::Method::GET, "price").query(request)).await
+    }
+
+    /// Requests a firm quote. Needs a SEP-10 token.
+    pub async fn quote(&self, request: &QuoteRequest) -> Result<Quote, Box<dyn std::error::Error>> {
+        request.validate()?;
+        anchor::send_json(self.request(reqwest::Method::POST, "quote").json(request)).await
+    }
+
+    /// Fetches a firm quote by ID.
+    pub async fn get_quote(&self, id: &str) -> Result<Quote, Box<dyn std::error::Error>> {
+        anchor::send_json(self.request(reqwest::Method::GET, &format!("quote/{}", id))).await
+    }
+}
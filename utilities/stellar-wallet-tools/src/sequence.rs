@@ -0,0 +1,156 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\sequence.rs
+
+use crate::network::NetworkProfile;
+use crate::{horizon, tx, StellarWalletTools};
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use stellar_sdk::{BumpSequenceOperation, Keypair, Operation, StellarSdkError, Transaction};
+
+/// Builds a `BumpSequence` operation setting the source account's sequence number to `bump_to`.
+///
+/// Bumping to a value at or below the current sequence number is a no-op on the network.
+pub fn bump_sequence_operation(bump_to: i64) -> Result<BumpSequenceOperation, Box<dyn std::error::Error>> {
+    if bump_to < 0 {
+        return Err("Sequence numbers can't be negative".into());
+    }
+    Ok(BumpSequenceOperation::new(bump_to))
+}
+
+/// A block of consecutive sequence numbers reserved from a [`SequenceAllocator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceRange {
+    pub first: i64,
+    pub last: i64,
+}
+
+impl SequenceRange {
+    pub fn len(&self) -> usize {
+        (self.last - self.first + 1) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.last < self.first
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = i64> {
+        self.first..=self.last
+    }
+}
+
+#[derive(Deserialize)]
+struct AccountSequence {
+    sequence: String,
+}
+
+/// Hands out the sequence numbers of one source account to transactions built offline.
+///
+/// The allocator is cheap to clone and every clone shares the same counter, so
+/// parallel jobs building transactions for the same account never receive the
+/// same number. Transactions still have to be submitted in sequence order: a
+/// transaction is only valid once every earlier number has been consumed.
+#[derive(Debug, Clone)]
+pub struct SequenceAllocator {
+    account_id: String,
+    /// Last sequence number handed out (or the account's sequence when nothing was).
+    last_used: Arc<Mutex<i64>>,
+}
+
+impl SequenceAllocator {
+    /// Creates an allocator for an account whose current sequence number is `current_sequence`.
+    pub fn new(account_id: &str, current_sequence: i64) -> Self {
+        SequenceAllocator {
+            account_id: account_id.to_string(),
+            last_used: Arc::new(Mutex::new(current_sequence)),
+        }
+    }
+
+    /// Creates an allocator starting from the account's current sequence number on Horizon.
+    pub async fn load(horizon_url: &str, account_id: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let sequence = fetch_sequence(horizon_url, account_id).await?;
+        Ok(Self::new(account_id, sequence))
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// The last sequence number handed out.
+    pub fn current(&self) -> i64 {
+        *self.last_used.lock().unwrap()
+    }
+
+    /// Reserves the next sequence number.
+    pub fn next(&self) -> i64 {
+        self.reserve(1).first
+    }
+
+    /// Reserves `count` consecutive sequence numbers.
+    pub fn reserve(&self, count: usize) -> SequenceRange {
+        let mut last_used = self.last_used.lock().unwrap();
+        let first = *last_used + 1;
+        *last_used += count as i64;
+        SequenceRange { first, last: *last_used }
+    }
+
+    /// Re-reads the account's sequence number after transactions failed or were dropped.
+    ///
+    /// The counter only moves forward, so numbers already handed out are never reused.
+    pub async fn resync(&self, horizon_url: &str) -> Result<i64, Box<dyn std::error::Error>> {
+        let sequence = fetch_sequence(horizon_url, &self.account_id).await?;
+        let mut last_used = self.last_used.lock().unwrap();
+        *last_used = (*last_used).max(sequence);
+        Ok(*last_used)
+    }
+
+    /// Builds an unsigned transaction using the next sequence number.
+    pub fn build_transaction(
+        &self,
+        network: &NetworkProfile,
+        operations: Vec<Operation>,
+    ) -> Result<Transaction, StellarSdkError> {
+        tx::build_transaction_at_sequence(network, &self.account_id, self.next(), None, None, operations)
+    }
+
+    /// Builds and signs a transaction using the next sequence number.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub fn build_signed_transaction(
+        &self,
+        network: &NetworkProfile,
+        source_keypair: &Keypair,
+        operations: Vec<Operation>,
+    ) -> Result<String, StellarSdkError> {
+        let transaction = self.build_transaction(network, operations)?;
+        Ok(transaction.sign(source_keypair)?.to_xdr_base64())
+    }
+}
+
+/// Reads the current sequence number of an account from Horizon.
+pub(crate) async fn fetch_sequence(horizon_url: &str, account_id: &str) -> Result<i64, Box<dyn std::error::Error>> {
+    let account: AccountSequence = horizon::get_json(&format!("{}/accounts/{}", horizon_url, account_id), &[]).await?;
+    Ok(account.sequence.parse()?)
+}
+
+impl StellarWalletTools {
+    /// Creates a signed transaction that bumps the signer's sequence number to `bump_to`.
+    ///
+    /// This invalidates every pre-signed transaction with a lower sequence number.
+    ///
+    /// # Arguments
+    /// * `secret_key` - The secret key of the account.
+    /// * `bump_to` - The new sequence number.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_bump_sequence_transaction(
+        secret_key: &str,
+        bump_to: i64,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let keypair = Keypair::from_secret(secret_key)?;
+        let operation = bump_sequence_operation(bump_to)?;
+        Ok(tx::build_signed_transaction(network, &keypair, vec![operation.into()]).await?)
+    }
+}
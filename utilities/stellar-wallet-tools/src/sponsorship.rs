@@ -0,0 +1,155 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\sponsorship.rs
+
+use crate::amount::Amount;
+use crate::asset::AssetId;
+use crate::network::NetworkProfile;
+use crate::{tx, StellarWalletTools};
+use stellar_sdk::{
+    BeginSponsoringFutureReservesOperation, ChangeTrustOperation, CreateAccountOperation,
+    EndSponsoringFutureReservesOperation, Keypair, LedgerKey, Operation, RevokeSponsorshipOperation,
+    StellarSdkError,
+};
+
+/// A ledger entry (or signer) whose sponsorship can be revoked or transferred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SponsoredEntry {
+    Account(String),
+    Trustline { account_id: String, asset: AssetId },
+    Offer { seller_id: String, offer_id: i64 },
+    Data { account_id: String, name: String },
+    ClaimableBalance(String),
+    /// A signer on `account_id`; `signer_key` is a G..., T... or X... key.
+    Signer { account_id: String, signer_key: String },
+}
+
+/// Builds a `BeginSponsoringFutureReserves` operation, executed by `sponsor_id`.
+///
+/// Every reserve created for `sponsored_id` until the matching
+/// [`end_sponsoring_operation`] is paid by the sponsor.
+pub fn begin_sponsoring_operation(
+    sponsor_id: &str,
+    sponsored_id: &str,
+) -> Result<BeginSponsoringFutureReservesOperation, StellarSdkError> {
+    Keypair::from_public_key(sponsor_id)?;
+    Keypair::from_public_key(sponsored_id)?;
+    Ok(BeginSponsoringFutureReservesOperation::new(sponsored_id.to_string())
+        .with_source_account(sponsor_id.to_string()))
+}
+
+/// Builds the `EndSponsoringFutureReserves` operation closing a sponsorship sandwich.
+/// It must be executed (and therefore signed) by the sponsored account.
+pub fn end_sponsoring_operation(sponsored_id: &str) -> Result<EndSponsoringFutureReservesOperation, StellarSdkError> {
+    Keypair::from_public_key(sponsored_id)?;
+    Ok(EndSponsoringFutureReservesOperation::new().with_source_account(sponsored_id.to_string()))
+}
+
+/// Builds a `RevokeSponsorship` operation for a ledger entry or signer.
+///
+/// Submitted by the current sponsor, it either transfers the reserve back to the
+/// owner or, inside another sponsorship sandwich, to a new sponsor.
+pub fn revoke_sponsorship_operation(entry: &SponsoredEntry) -> Result<RevokeSponsorshipOperation, StellarSdkError> {
+    let ledger_key = match entry {
+        SponsoredEntry::Signer { account_id, signer_key } => {
+            return Ok(RevokeSponsorshipOperation::signer(account_id.clone(), signer_key.clone()));
+        }
+        SponsoredEntry::Account(account_id) => LedgerKey::Account(account_id.clone()),
+        SponsoredEntry::Trustline { account_id, asset } => {
+            LedgerKey::Trustline(account_id.clone(), asset.to_sdk_asset()?)
+        }
+        SponsoredEntry::Offer { seller_id, offer_id } => LedgerKey::Offer(seller_id.clone(), *offer_id),
+        SponsoredEntry::Data { account_id, name } => LedgerKey::Data(account_id.clone(), name.clone()),
+        SponsoredEntry::ClaimableBalance(balance_id) => LedgerKey::ClaimableBalance(balance_id.clone()),
+    };
+    Ok(RevokeSponsorshipOperation::ledger_entry(ledger_key))
+}
+
+/// The operations of a sponsored onboarding:
+///
+/// 1. `BeginSponsoringFutureReserves` (sponsor)
+/// 2. `CreateAccount` with `starting_balance` (sponsor)
+/// 3. `ChangeTrust` for each asset in `trust_assets` (new account)
+/// 4. `EndSponsoringFutureReserves` (new account)
+///
+/// The new account can start with a zero balance because its base reserve and
+/// every trustline reserve are carried by the sponsor.
+pub fn sponsored_onboarding_operations(
+    sponsor_id: &str,
+    new_account_id: &str,
+    trust_assets: &[AssetId],
+    starting_balance: Amount,
+) -> Result<Vec<Operation>, Box<dyn std::error::Error>> {
+    if sponsor_id == new_account_id {
+        return Err("An account can't sponsor itself".into());
+    }
+
+    let mut operations: Vec<Operation> = vec![
+        begin_sponsoring_operation(sponsor_id, new_account_id)?.into(),
+        CreateAccountOperation::new(new_account_id.to_string(), starting_balance.to_string())
+            .with_source_account(sponsor_id.to_string())
+            .into(),
+    ];
+    for asset in trust_assets {
+        if *asset == AssetId::Native {
+            continue; // XLM needs no trustline
+        }
+        operations.push(
+            ChangeTrustOperation::new(asset.to_sdk_asset()?.into(), None)
+                .with_source_account(new_account_id.to_string())
+                .into(),
+        );
+    }
+    operations.push(end_sponsoring_operation(new_account_id)?.into());
+    Ok(operations)
+}
+
+impl StellarWalletTools {
+    /// Assembles a sponsored onboarding transaction for a new account.
+    ///
+    /// The transaction is sourced and signed by the sponsor. It is only valid once
+    /// the new account has signed it too, either by passing `new_account_secret_key`
+    /// here or later with `StellarWalletTools::sign_transaction` on the user's device.
+    ///
+    /// # Arguments
+    /// * `sponsor_secret_key` - The secret key of the sponsoring account (pays fee and reserves).
+    /// * `new_account_public_key` - The account to create (G...).
+    /// * `new_account_secret_key` - The new account's secret key, if available here.
+    /// * `trust_assets` - Assets the new account should trust.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded transaction XDR string on success.
+    pub async fn create_sponsored_onboarding_transaction(
+        sponsor_secret_key: &str,
+        new_account_public_key: &str,
+        new_account_secret_key: Option<&str>,
+        trust_assets: &[AssetId],
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let sponsor_keypair = Keypair::from_secret(sponsor_secret_key)?;
+        let sponsor_id = sponsor_keypair.public_key().to_string();
+        let operations =
+            sponsored_onboarding_operations(&sponsor_id, new_account_public_key, trust_assets, Amount::default())?;
+
+        let transaction = tx::build_transaction(network, &sponsor_id, operations).await?;
+        let mut signed_transaction = transaction.sign(&sponsor_keypair)?;
+        if let Some(secret) = new_account_secret_key {
+            let new_account_keypair = Keypair::from_secret(secret)?;
+            if new_account_keypair.public_key().to_string() != new_account_public_key {
+                return Err("The secret key doesn't match the new account".into());
+            }
+            signed_transaction = signed_transaction.sign(&new_account_keypair)?;
+        }
+        Ok(signed_transaction.to_xdr_base64())
+    }
+
+    /// Creates a signed transaction that revokes the sponsorship of a ledger entry or signer.
+    pub async fn create_revoke_sponsorship_transaction(
+        sponsor_secret_key: &str,
+        entry: &SponsoredEntry,
+        network: &NetworkProfile,
+    ) -> Result<String, StellarSdkError> {
+        let sponsor_keypair = Keypair::from_secret(sponsor_secret_key)?;
+        let operation = revoke_sponsorship_operation(entry)?;
+        tx::build_signed_transaction(network, &sponsor_keypair, vec![operation.into()]).await
+    }
+}
@@ -0,0 +1,262 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\stellar_toml.rs
+// stellar.toml (SEP-0001): the file a domain publishes to describe its Stellar services.
+
+use crate::strkey;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// SEP-1 caps stellar.toml files at 100 KB.
+pub const MAX_STELLAR_TOML_BYTES: usize = 100 * 1024;
+
+/// The fields of a stellar.toml this library uses. Unknown fields are ignored.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct StellarToml {
+    pub version: Option<String>,
+    pub network_passphrase: Option<String>,
+    /// SEP-2 federation endpoint.
+    pub federation_server: Option<String>,
+    /// SEP-6 deposit/withdrawal endpoint.
+    pub transfer_server: Option<String>,
+    /// SEP-24 interactive deposit/withdrawal endpoint.
+    pub transfer_server_sep0024: Option<String>,
+    /// SEP-12 KYC endpoint.
+    pub kyc_server: Option<String>,
+    /// SEP-10 web authentication endpoint.
+    pub web_auth_endpoint: Option<String>,
+    /// SEP-31 cross-border payments endpoint.
+    pub direct_payment_server: Option<String>,
+    /// SEP-38 quote endpoint.
+    pub anchor_quote_server: Option<String>,
+    /// Key the domain signs SEP-10 challenges with (G...).
+    pub signing_key: Option<String>,
+    /// Key the domain signs SEP-7 URIs with (G...).
+    pub uri_request_signing_key: Option<String>,
+    pub horizon_url: Option<String>,
+    /// Accounts controlled by the domain.
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    pub documentation: Option<Documentation>,
+    #[serde(default)]
+    pub currencies: Vec<Currency>,
+    #[serde(default)]
+    pub validators: Vec<Validator>,
+}
+
+/// The `[DOCUMENTATION]` table describing the organization.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct Documentation {
+    pub org_name: Option<String>,
+    pub org_url: Option<String>,
+    pub org_logo: Option<String>,
+    pub org_description: Option<String>,
+    pub org_official_email: Option<String>,
+    pub org_support_email: Option<String>,
+}
+
+/// A `[[CURRENCIES]]` entry.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Currency {
+    pub code: Option<String>,
+    pub issuer: Option<String>,
+    pub status: Option<String>,
+    pub display_decimals: Option<u8>,
+    pub name: Option<String>,
+    pub desc: Option<String>,
+    pub image: Option<String>,
+    pub fixed_number: Option<u64>,
+    pub max_number: Option<u64>,
+    #[serde(default)]
+    pub is_unlimited: bool,
+    #[serde(default)]
+    pub is_asset_anchored: bool,
+    pub anchor_asset_type: Option<String>,
+    pub anchor_asset: Option<String>,
+    #[serde(default)]
+    pub regulated: bool,
+    /// SEP-8 approval server, for regulated assets.
+    pub approval_server: Option<String>,
+}
+
+/// A `[[VALIDATORS]]` entry.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct Validator {
+    pub alias: Option<String>,
+    pub display_name: Option<String>,
+    pub public_key: Option<String>,
+    pub host: Option<String>,
+    pub history: Option<String>,
+}
+
+impl StellarToml {
+    /// Parses the contents of a stellar.toml file.
+    pub fn parse(contents: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    /// Checks the file for mistakes that would break clients, returning one
+    /// human-readable warning per problem. An empty list means no problems were found.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        let endpoints = [
+            ("FEDERATION_SERVER", &self.federation_server),
+            ("TRANSFER_SERVER", &self.transfer_server),
+            ("TRANSFER_SERVER_SEP0024", &self.transfer_server_sep0024),
+            ("KYC_SERVER", &self.kyc_server),
+            ("WEB_AUTH_ENDPOINT", &self.web_auth_endpoint),
+            ("DIRECT_PAYMENT_SERVER", &self.direct_payment_server),
+            ("ANCHOR_QUOTE_SERVER", &self.anchor_quote_server),
+            ("HORIZON_URL", &self.horizon_url),
+        ];
+        for (name, url) in endpoints {
+            if let Some(url) = url {
+                if !url.starts_with("https://") {
+                    warnings.push(format!("{} should be an https:// URL, got '{}'", name, url));
+                }
+            }
+        }
+
+        for (name, key) in [("SIGNING_KEY", &self.signing_key), ("URI_REQUEST_SIGNING_KEY", &self.uri_request_signing_key)] {
+            if let Some(key) = key {
+                if strkey::decode_account_id(key).is_err() {
+                    warnings.push(format!("{} is not a valid public key: '{}'", name, key));
+                }
+            }
+        }
+        if self.web_auth_endpoint.is_some() && self.signing_key.is_none() {
+            warnings.push("WEB_AUTH_ENDPOINT is set but SIGNING_KEY is missing".to_string());
+        }
+        if self.network_passphrase.is_none() && (self.transfer_server.is_some() || self.transfer_server_sep0024.is_some()) {
+            warnings.push("NETWORK_PASSPHRASE is missing".to_string());
+        }
+        for account in &self.accounts {
+            if strkey::decode_account_id(account).is_err() {
+                warnings.push(format!("ACCOUNTS contains an invalid account '{}'", account));
+            }
+        }
+
+        for (index, currency) in self.currencies.iter().enumerate() {
+            let label = currency.code.clone().unwrap_or_else(|| format!("#{}", index + 1));
+            match &currency.code {
+                Some(code) if code.is_empty() || code.len() > 12 => {
+                    warnings.push(format!("Currency {}: code must be 1 to 12 characters", label));
+                }
+                None => warnings.push(format!("Currency {}: code is missing", label)),
+                _ => {}
+            }
+            match &currency.issuer {
+                Some(issuer) if strkey::decode_account_id(issuer).is_err() => {
+                    warnings.push(format!("Currency {}: invalid issuer '{}'", label, issuer));
+                }
+                None => warnings.push(format!("Currency {}: issuer is missing", label)),
+                _ => {}
+            }
+            if currency.display_decimals.is_some_and(|d| d > 7) {
+                warnings.push(format!("Currency {}: display_decimals can be at most 7", label));
+            }
+            if currency.regulated && currency.approval_server.is_none() {
+                warnings.push(format!("Currency {}: regulated assets need an approval_server", label));
+            }
+        }
+
+        for (index, validator) in self.validators.iter().enumerate() {
+            let label = validator.alias.clone().unwrap_or_else(|| format!("#{}", index + 1));
+            match &validator.public_key {
+                Some(key) if strkey::decode_account_id(key).is_err() => {
+                    warnings.push(format!("Validator {}: invalid PUBLIC_KEY '{}'", label, key));
+                }
+                None => warnings.push(format!("Validator {}: PUBLIC_KEY is missing", label)),
+                _ => {}
+            }
+        }
+        warnings
+    }
+
+    /// The currency entry for `code` issued by `issuer`, if the domain lists it.
+    pub fn currency(&self, code: &str, issuer: &str) -> Option<&Currency> {
+        self.currencies
+            .iter()
+            .find(|c| c.code.as_deref() == Some(code) && c.issuer.as_deref() == Some(issuer))
+    }
+}
+
+/// Downloads stellar.toml files from `https://<domain>/.well-known/stellar.toml`.
+#[derive(Debug, Clone)]
+pub struct StellarTomlFetcher {
+    scheme: String,
+    max_size: usize,
+    timeout: Duration,
+}
+
+impl Default for StellarTomlFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StellarTomlFetcher {
+    pub fn new() -> Self {
+        StellarTomlFetcher { scheme: "https".to_string(), max_size: MAX_STELLAR_TOML_BYTES, timeout: Duration::from_secs(10) }
+    }
+
+    /// Fetches over plain HTTP. Only meant for local test servers.
+    pub fn insecure_http(mut self) -> Self {
+        self.scheme = "http".to_string();
+        self
+    }
+
+    /// Sets the largest accepted file, in bytes.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The URL the stellar.toml of `domain` is fetched from.
+    pub fn url(&self, domain: &str) -> Result<String, Box<dyn std::error::Error>> {
+        if domain.is_empty() || domain.contains(['/', '?', '#', '@']) || domain.contains(char::is_whitespace) {
+            return Err(format!("'{}' is not a domain name", domain).into());
+        }
+        Ok(format!("{}://{}/.well-known/stellar.toml", self.scheme, domain))
+    }
+
+    /// Downloads the raw stellar.toml of `domain`, refusing files above the size limit.
+    pub async fn fetch_text(&self, domain: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let url = self.url(domain)?;
+        let client = reqwest::Client::builder().timeout(self.timeout).build()?;
+        let mut response = client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(format!("Could not fetch {} ({})", url, response.status()).into());
+        }
+        let too_large = || format!("{} is larger than {} bytes", url, self.max_size);
+        if response.content_length().is_some_and(|len| len as usize > self.max_size) {
+            return Err(too_large().into());
+        }
+        // The declared length can be missing or wrong, so count while reading.
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > self.max_size {
+                return Err(too_large().into());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(String::from_utf8(body)?)
+    }
+
+    /// Downloads and parses the stellar.toml of `domain`.
+    pub async fn fetch(&self, domain: &str) -> Result<StellarToml, Box<dyn std::error::Error>> {
+        StellarToml::parse(&self.fetch_text(domain).await?)
+    }
+}
+
+/// Downloads and parses the stellar.toml of `domain` with the default limits.
+pub async fn fetch_stellar_toml(domain: &str) -> Result<StellarToml, Box<dyn std::error::Error>> {
+    StellarTomlFetcher::new().fetch(domain).await
+}
@@ -0,0 +1,91 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\strkey.rs
+// StrKey (SEP-0023) encoding: base32 of version byte + payload + CRC16 checksum.
+
+/// Version byte of account IDs (`G...`).
+pub const VERSION_ACCOUNT_ID: u8 = 6 << 3;
+/// Version byte of secret seeds (`S...`).
+pub const VERSION_SEED: u8 = 18 << 3;
+/// Version byte of pre-authorized transaction hashes (`T...`).
+pub const VERSION_PRE_AUTH_TX: u8 = 19 << 3;
+/// Version byte of SHA-256 hash signers (`X...`).
+pub const VERSION_SHA256_HASH: u8 = 23 << 3;
+
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encodes a 32-byte payload with the given version byte.
+pub fn encode(version: u8, payload: &[u8]) -> String {
+    let mut data = Vec::with_capacity(payload.len() + 3);
+    data.push(version);
+    data.extend_from_slice(payload);
+    let checksum = crc16_xmodem(&data);
+    data.extend_from_slice(&checksum.to_le_bytes());
+    base32_encode(&data)
+}
+
+/// Decodes a StrKey, checking its version byte and checksum, and returns the payload.
+pub fn decode(version: u8, strkey: &str) -> Result<Vec<u8>, String> {
+    let data = base32_decode(strkey).ok_or_else(|| format!("Invalid StrKey '{}'", strkey))?;
+    if data.len() < 3 || data[0] != version {
+        return Err(format!("Invalid StrKey '{}': unexpected version", strkey));
+    }
+    let (body, checksum) = data.split_at(data.len() - 2);
+    if crc16_xmodem(body).to_le_bytes() != checksum {
+        return Err(format!("Invalid StrKey '{}': bad checksum", strkey));
+    }
+    Ok(body[1..].to_vec())
+}
+
+/// Returns the raw 32-byte ed25519 key of an account ID (`G...`).
+pub fn decode_account_id(account_id: &str) -> Result<[u8; 32], String> {
+    decode(VERSION_ACCOUNT_ID, account_id)?
+        .try_into()
+        .map_err(|_| format!("Invalid account ID '{}'", account_id))
+}
+
+/// Encodes a transaction hash as a pre-authorized transaction signer key (`T...`).
+pub fn encode_pre_auth_tx(hash: &[u8; 32]) -> String {
+    encode(VERSION_PRE_AUTH_TX, hash)
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in s.bytes() {
+        let value = ALPHABET.iter().position(|a| *a == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
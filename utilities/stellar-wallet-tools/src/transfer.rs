@@ -0,0 +1,352 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\transfer.rs
+// Programmatic (non-interactive) deposits and withdrawals with anchors (SEP-0006).
+
+use crate::amount::Amount;
+use crate::anchor::{self, AnchorError, AnchorTransaction};
+use crate::federation::{FederationMemo, ResolvedDestination};
+use crate::network::NetworkProfile;
+use crate::stellar_toml::{StellarToml, StellarTomlFetcher};
+use crate::web_auth::{WebAuthClient, WebAuthToken};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A field the anchor asks for, in `/info`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TransferField {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub optional: bool,
+    /// Accepted values, if the field is a choice.
+    #[serde(default)]
+    pub choices: Vec<String>,
+}
+
+/// A withdrawal method (e.g. `bank_account`) and the fields it needs.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WithdrawType {
+    #[serde(default)]
+    pub fields: HashMap<String, TransferField>,
+}
+
+/// Limits, fees and fields for one asset in `/info`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TransferAssetInfo {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub authentication_required: bool,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub min_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub max_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub fee_fixed: Option<Amount>,
+    #[serde(default)]
+    pub fee_percent: Option<f64>,
+    /// Deposits: extra fields the request may carry.
+    #[serde(default)]
+    pub fields: HashMap<String, TransferField>,
+    /// Withdrawals: the supported withdrawal methods.
+    #[serde(default)]
+    pub types: HashMap<String, WithdrawType>,
+}
+
+/// Availability of an optional endpoint such as `/transactions`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EndpointInfo {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub authentication_required: bool,
+}
+
+/// What an anchor supports, as answered by `/info`. Keys are asset codes.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TransferInfo {
+    #[serde(default)]
+    pub deposit: HashMap<String, TransferAssetInfo>,
+    #[serde(default)]
+    pub withdraw: HashMap<String, TransferAssetInfo>,
+    #[serde(default)]
+    pub transactions: Option<EndpointInfo>,
+    #[serde(default)]
+    pub transaction: Option<EndpointInfo>,
+}
+
+/// Parameters of a `/deposit` request.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DepositRequest {
+    pub asset_code: String,
+    /// The Stellar account to credit (G...).
+    pub account: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_address: Option<String>,
+    /// Deposit method, e.g. `SEPA` or `SWIFT`.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claimable_balance_supported: Option<bool>,
+}
+
+/// Parameters of a `/withdraw` request.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WithdrawRequest {
+    pub asset_code: String,
+    /// Withdrawal method, one of the `types` in `/info`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The Stellar account sending the funds (G...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// Memo the anchor should use if it refunds the payment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund_memo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund_memo_type: Option<String>,
+}
+
+/// One line of deposit instructions, e.g. the bank account number.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DepositInstruction {
+    pub value: String,
+    pub description: String,
+}
+
+/// Free-form extra information from the anchor.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExtraInfo {
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// How to send the off-chain funds for a deposit.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DepositInstructions {
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Structured instructions keyed by SEP-9 field names.
+    #[serde(default)]
+    pub instructions: HashMap<String, DepositInstruction>,
+    /// Plain-text instructions (deprecated by the protocol, still common).
+    #[serde(default)]
+    pub how: Option<String>,
+    /// Estimated seconds until the deposit is credited.
+    #[serde(default)]
+    pub eta: Option<i64>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub min_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub max_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub fee_fixed: Option<Amount>,
+    #[serde(default)]
+    pub fee_percent: Option<f64>,
+    #[serde(default)]
+    pub extra_info: Option<ExtraInfo>,
+}
+
+/// Where to send the Stellar payment for a withdrawal.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WithdrawInstructions {
+    #[serde(default)]
+    pub id: Option<String>,
+    /// The anchor's account; may be missing if the anchor can't accept funds yet.
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub memo_type: Option<String>,
+    #[serde(default)]
+    pub memo: Option<String>,
+    #[serde(default)]
+    pub eta: Option<i64>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub min_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub max_amount: Option<Amount>,
+    #[serde(default, deserialize_with = "anchor::optional_amount")]
+    pub fee_fixed: Option<Amount>,
+    #[serde(default)]
+    pub fee_percent: Option<f64>,
+    #[serde(default)]
+    pub extra_info: Option<ExtraInfo>,
+}
+
+impl WithdrawInstructions {
+    /// The destination (account and memo) for the withdrawal payment, ready for
+    /// `StellarWalletTools::create_payment_to_destination_transaction`.
+    pub fn destination(&self) -> Result<ResolvedDestination, Box<dyn std::error::Error>> {
+        let account_id = self.account_id.clone().ok_or("The anchor didn't provide an account to pay")?;
+        let memo = FederationMemo::parse(self.memo_type.as_deref(), self.memo.as_deref())?;
+        Ok(ResolvedDestination { account_id, memo, stellar_address: None })
+    }
+}
+
+/// The answer to a deposit or withdrawal request.
+///
+/// Anchors answer `403` when they need to know more about the customer first;
+/// those answers are part of the normal flow, so they are returned here rather
+/// than as errors.
+#[derive(Debug, Clone)]
+pub enum TransferOutcome<T> {
+    /// The anchor accepted the request.
+    Ready(T),
+    /// The anchor needs these SEP-9 fields, to be sent through SEP-12.
+    CustomerInfoNeeded { fields: Vec<String> },
+    /// The customer's information is being reviewed (`pending`) or was `denied`.
+    CustomerInfoStatus {
+        status: String,
+        more_info_url: Option<String>,
+        eta: Option<i64>,
+    },
+}
+
+#[derive(Deserialize)]
+struct TransactionResponse {
+    transaction: AnchorTransaction,
+}
+
+#[derive(Deserialize)]
+struct TransactionsResponse {
+    transactions: Vec<AnchorTransaction>,
+}
+
+/// SEP-6 client for one anchor's `TRANSFER_SERVER`.
+#[derive(Debug, Clone)]
+pub struct TransferClient {
+    pub transfer_server: String,
+    /// SEP-10 token, sent with every request when set.
+    pub token: Option<WebAuthToken>,
+}
+
+impl TransferClient {
+    /// Creates a client for `transfer_server` without a token.
+    pub fn new(transfer_server: &str) -> Self {
+        TransferClient { transfer_server: transfer_server.to_string(), token: None }
+    }
+
+    /// Creates a client from the anchor's stellar.toml.
+    pub fn from_stellar_toml(toml: &StellarToml) -> Result<Self, Box<dyn std::error::Error>> {
+        let transfer_server = toml.transfer_server.as_deref().ok_or("The anchor has no TRANSFER_SERVER")?;
+        Ok(Self::new(transfer_server))
+    }
+
+    /// Fetches the stellar.toml of `home_domain`, authenticates `account` with
+    /// SEP-10 on `network` and returns a client carrying the token.
+    ///
+    /// # Arguments
+    /// * `home_domain` - The anchor's domain.
+    /// * `network` - The network; the anchor must run on it.
+    /// * `account` - The account to authenticate (G...).
+    /// * `signer_secret_keys` - Secret keys to sign the challenge with.
+    pub async fn connect(
+        home_domain: &str,
+        network: &NetworkProfile,
+        account: &str,
+        signer_secret_keys: &[&str],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let toml = StellarTomlFetcher::new().fetch(home_domain).await?;
+        let client = Self::from_stellar_toml(&toml)?;
+        let token = WebAuthClient::from_stellar_toml(home_domain, &toml, network)?
+            .authenticate(account, signer_secret_keys)
+            .await?;
+        Ok(client.with_token(token))
+    }
+
+    /// Sets the SEP-10 token sent with requests.
+    pub fn with_token(mut self, token: WebAuthToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        anchor::authorize(reqwest::Client::new().get(anchor::endpoint(&self.transfer_server, path)), self.token.as_ref())
+    }
+
+    /// Fetches the assets the anchor supports, their limits and required fields.
+    pub async fn info(&self) -> Result<TransferInfo, Box<dyn std::error::Error>> {
+        anchor::send_json(self.get("info")).await
+    }
+
+    /// Requests deposit instructions.
+    pub async fn deposit(
+        &self,
+        request: &DepositRequest,
+    ) -> Result<TransferOutcome<DepositInstructions>, Box<dyn std::error::Error>> {
+        send_transfer(self.get("deposit").query(request)).await
+    }
+
+    /// Requests withdrawal instructions.
+    pub async fn withdraw(
+        &self,
+        request: &WithdrawRequest,
+    ) -> Result<TransferOutcome<WithdrawInstructions>, Box<dyn std::error::Error>> {
+        send_transfer(self.get("withdraw").query(request)).await
+    }
+
+    /// Fetches one transaction by its anchor ID.
+    pub async fn transaction(&self, id: &str) -> Result<AnchorTransaction, Box<dyn std::error::Error>> {
+        let response: TransactionResponse = anchor::send_json(self.get("transaction").query(&[("id", id)])).await?;
+        Ok(response.transaction)
+    }
+
+    /// Lists the authenticated account's transactions for an asset, newest first.
+    ///
+    /// # Arguments
+    /// * `asset_code` - The asset code.
+    /// * `kind` - Only `deposit` or `withdrawal` transactions, if set.
+    /// * `limit` - Maximum number of transactions, if set.
+    pub async fn transactions(
+        &self,
+        asset_code: &str,
+        kind: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<Vec<AnchorTransaction>, Box<dyn std::error::Error>> {
+        let mut query = vec![("asset_code", asset_code.to_string())];
+        if let Some(kind) = kind {
+            query.push(("kind", kind.to_string()));
+        }
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        let response: TransactionsResponse = anchor::send_json(self.get("transactions").query(&query)).await?;
+        Ok(response.transactions)
+    }
+}
+
+/// Sends a deposit/withdraw request, turning "customer info" answers into outcomes.
+async fn send_transfer<T: DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+) -> Result<TransferOutcome<T>, Box<dyn std::error::Error>> {
+    let error = match anchor::send_json(request).await {
+        Ok(answer) => return Ok(TransferOutcome::Ready(answer)),
+        Err(error) => error.downcast::<AnchorError>()?,
+    };
+    if error.status != 403 {
+        return Err(error);
+    }
+    match error.body["type"].as_str() {
+        Some("non_interactive_customer_info_needed") => Ok(TransferOutcome::CustomerInfoNeeded {
+            fields: serde_json::from_value(error.body["fields"].clone()).unwrap_or_default(),
+        }),
+        Some("customer_info_status") => Ok(TransferOutcome::CustomerInfoStatus {
+            status: error.body["status"].as_str().unwrap_or("pending").to_string(),
+            more_info_url: error.body["more_info_url"].as_str().map(str::to_string),
+            eta: error.body["eta"].as_i64(),
+        }),
+        _ => Err(error),
+    }
+}
@@ -0,0 +1,109 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\tx.rs
+
+use crate::network::NetworkProfile;
+use std::str::FromStr;
+use stellar_sdk::{Account, Keypair, Memo, Network, Operation, StellarSdkError, Transaction, TransactionBuilder};
+
+/// Loads `source_account_id` from the profile's Horizon and builds an unsigned
+/// transaction containing `operations`, using the account's next sequence number.
+pub(crate) async fn build_transaction(
+    network: &NetworkProfile,
+    source_account_id: &str,
+    operations: Vec<Operation>,
+) -> Result<Transaction, StellarSdkError> {
+    build_transaction_with_memo(network, source_account_id, None, operations).await
+}
+
+/// Same as [`build_transaction`], attaching a memo when given.
+pub(crate) async fn build_transaction_with_memo(
+    network: &NetworkProfile,
+    source_account_id: &str,
+    memo: Option<Memo>,
+    operations: Vec<Operation>,
+) -> Result<Transaction, StellarSdkError> {
+    // Load the source account to get its sequence number
+    let horizon_client = stellar_sdk::horizon::HorizonClient::new(&network.horizon_url);
+    let source_account = horizon_client.get_account(source_account_id).await?;
+
+    // Build the transaction
+    let mut builder = TransactionBuilder::new(
+        source_account,
+        Network::from_str(&network.network_passphrase)?,
+    );
+    if let Some(memo) = memo {
+        builder = builder.add_memo(memo);
+    }
+    for operation in operations {
+        builder = builder.add_operation(operation);
+    }
+    Ok(builder.build())
+}
+
+/// Builds an unsigned transaction with an explicit sequence number, without
+/// contacting Horizon. Used for transactions prepared offline.
+///
+/// `time_bounds` is an optional `(min_time, max_time)` pair of UNIX timestamps;
+/// a `max_time` of 0 means the transaction never expires.
+pub(crate) fn build_transaction_at_sequence(
+    network: &NetworkProfile,
+    source_account_id: &str,
+    sequence: i64,
+    time_bounds: Option<(i64, i64)>,
+    memo: Option<Memo>,
+    operations: Vec<Operation>,
+) -> Result<Transaction, StellarSdkError> {
+    // The builder uses the account's sequence number plus one.
+    let source_account = Account::new(source_account_id.to_string(), sequence - 1)?;
+    let mut builder = TransactionBuilder::new(
+        source_account,
+        Network::from_str(&network.network_passphrase)?,
+    );
+    if let Some((min_time, max_time)) = time_bounds {
+        builder = builder.time_bounds(min_time, max_time);
+    }
+    if let Some(memo) = memo {
+        builder = builder.add_memo(memo);
+    }
+    for operation in operations {
+        builder = builder.add_operation(operation);
+    }
+    Ok(builder.build())
+}
+
+/// Loads the source account from the profile's Horizon, adds `operations` to a new
+/// transaction, signs it with `source_keypair` and returns the base64-encoded XDR.
+///
+/// This is the same flow as `StellarWalletTools::create_payment_transaction`,
+/// shared by the builders that need more than a single payment.
+pub(crate) async fn build_signed_transaction(
+    network: &NetworkProfile,
+    source_keypair: &Keypair,
+    operations: Vec<Operation>,
+) -> Result<String, StellarSdkError> {
+    let transaction = build_transaction(network, &source_keypair.public_key().to_string(), operations).await?;
+
+    // Sign the transaction and return the base64-encoded XDR
+    let signed_transaction = transaction.sign(source_keypair)?;
+    Ok(signed_transaction.to_xdr_base64())
+}
+
+/// Adds a signature to an already built (and possibly partially signed) transaction.
+pub(crate) fn add_signature(
+    transaction_xdr: &str,
+    keypair: &Keypair,
+    network_passphrase: &str,
+) -> Result<String, StellarSdkError> {
+    let transaction = Transaction::from_xdr_base64(transaction_xdr, Network::from_str(network_passphrase)?)?;
+    let signed_transaction = transaction.sign(keypair)?;
+    Ok(signed_transaction.to_xdr_base64())
+}
+
+/// Computes the hash of a transaction, the value its signatures are made over.
+///
+/// # Arguments
+/// * `transaction_xdr` - The base64-encoded transaction envelope (signatures are ignored).
+/// * `network_passphrase` - The passphrase of the network the transaction is for.
+pub fn transaction_hash(transaction_xdr: &str, network_passphrase: &str) -> Result<[u8; 32], StellarSdkError> {
+    let transaction = Transaction::from_xdr_base64(transaction_xdr, Network::from_str(network_passphrase)?)?;
+    Ok(transaction.hash())
+}
@@ -0,0 +1,461 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\uri.rs
+// `web+stellar:` URIs (SEP-0007): transaction and payment requests, with
+// optional signatures by the requesting domain.
+
+use crate::amount::Amount;
+use crate::asset::AssetId;
+use crate::federation::{FederationMemo, ResolvedDestination};
+use crate::network::NetworkProfile;
+use crate::stellar_toml::StellarTomlFetcher;
+use crate::{horizon, strkey, tx, StellarWalletTools};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::fmt;
+use std::str::FromStr;
+use stellar_sdk::{Keypair, Network, Transaction};
+
+/// The URI scheme of SEP-7 requests.
+pub const URI_SCHEME: &str = "web+stellar:";
+
+/// Maximum length of the `msg` parameter, in characters.
+pub const MAX_MESSAGE_LENGTH: usize = 300;
+
+/// Signed payloads start with 35 zero bytes and a 4, then this text, then the URI.
+const SIGNATURE_PREFIX: &str = "stellar.sep.7 - URI Scheme";
+
+/// What a URI asks the wallet to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UriRequest {
+    /// Sign (and submit) a prepared transaction.
+    Tx {
+        /// Base64-encoded transaction envelope.
+        xdr: String,
+        /// Txrep fields the wallet should let the user fill in.
+        replace: Option<String>,
+        /// The account expected to sign (G...).
+        pubkey: Option<String>,
+    },
+    /// Pay `destination`; the wallet builds the transaction.
+    Pay {
+        destination: String,
+        /// `None` lets the user choose the amount.
+        amount: Option<Amount>,
+        asset: AssetId,
+        memo: Option<FederationMemo>,
+    },
+}
+
+/// The envelope of a `tx` request, decoded for the user to review before signing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionPreview {
+    pub source_account: String,
+    pub sequence: i64,
+    /// One description per operation, in order.
+    pub operations: Vec<String>,
+}
+
+/// A parsed or built `web+stellar:` URI.
+///
+/// A parsed URI remembers its original text, which is what the signature
+/// covers: [`StellarUri::verify_signature`] and `to_string` use it, so
+/// fields of a parsed URI shouldn't be modified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StellarUri {
+    pub request: UriRequest,
+    /// URL the signed transaction is POSTed to instead of being submitted.
+    pub callback: Option<String>,
+    /// Message shown to the user.
+    pub message: Option<String>,
+    /// Network of the request; the public network when `None`.
+    pub network_passphrase: Option<String>,
+    /// Domain vouching for the request with `signature`.
+    pub origin_domain: Option<String>,
+    /// Base64 signature by the origin domain's `URI_REQUEST_SIGNING_KEY`.
+    pub signature: Option<String>,
+    /// The URI as received, without the signature parameter.
+    original: Option<String>,
+}
+
+impl StellarUri {
+    fn with_request(request: UriRequest) -> Self {
+        StellarUri {
+            request,
+            callback: None,
+            message: None,
+            network_passphrase: None,
+            origin_domain: None,
+            signature: None,
+            original: None,
+        }
+    }
+
+    /// A request to sign the transaction `xdr`.
+    pub fn tx(xdr: &str) -> Self {
+        Self::with_request(UriRequest::Tx { xdr: xdr.to_string(), replace: None, pubkey: None })
+    }
+
+    /// A request to pay `asset` to `destination`.
+    pub fn pay(destination: &str, asset: AssetId, amount: Option<Amount>, memo: Option<FederationMemo>) -> Self {
+        Self::with_request(UriRequest::Pay { destination: destination.to_string(), amount, asset, memo })
+    }
+
+    /// Sets the network, omitted for the public network.
+    pub fn network(mut self, network: &NetworkProfile) -> Self {
+        self.network_passphrase = Some(network.network_passphrase.clone())
+            .filter(|passphrase| *passphrase != NetworkProfile::public().network_passphrase);
+        self
+    }
+
+    /// Sets the message shown to the user.
+    pub fn message(mut self, message: &str) -> Self {
+        self.message = Some(message.to_string());
+        self
+    }
+
+    /// Sets the callback URL.
+    pub fn callback(mut self, url: &str) -> Self {
+        self.callback = Some(url.to_string());
+        self
+    }
+
+    /// The network passphrase the request is for.
+    pub fn network_passphrase(&self) -> String {
+        self.network_passphrase
+            .clone()
+            .unwrap_or_else(|| NetworkProfile::public().network_passphrase)
+    }
+
+    /// Decodes the transaction of a `tx` request on the request's network.
+    pub fn preview_transaction(&self) -> Result<TransactionPreview, Box<dyn std::error::Error>> {
+        let UriRequest::Tx { xdr, .. } = &self.request else {
+            return Err("Only tx requests carry a transaction".into());
+        };
+        let transaction = Transaction::from_xdr_base64(xdr, Network::from_str(&self.network_passphrase())?)?;
+        Ok(TransactionPreview {
+            source_account: transaction.source_account().to_string(),
+            sequence: transaction.sequence_number(),
+            operations: transaction.operations().iter().map(|operation| format!("{:?}", operation)).collect(),
+        })
+    }
+
+    /// Parses a `web+stellar:` URI and checks its parameters.
+    pub fn parse(uri: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let scheme = uri.get(..URI_SCHEME.len()).filter(|s| s.eq_ignore_ascii_case(URI_SCHEME));
+        let rest = &uri[scheme.ok_or("Not a web+stellar: URI")?.len()..];
+        let (operation, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let mut params: Vec<(String, String)> = Vec::new();
+        let mut unsigned: Vec<&str> = Vec::new();
+        let mut signature = None;
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value)?;
+            if name == "signature" {
+                signature = Some(value);
+            } else {
+                unsigned.push(pair);
+                params.push((name.to_string(), value));
+            }
+        }
+        let param = |name: &str| params.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone());
+        let required = |name: &str| param(name).ok_or_else(|| format!("The URI has no '{}' parameter", name));
+
+        let request = match operation {
+            "tx" => {
+                let pubkey = param("pubkey");
+                if let Some(pubkey) = &pubkey {
+                    strkey::decode_account_id(pubkey)?;
+                }
+                UriRequest::Tx { xdr: required("xdr")?, replace: param("replace"), pubkey }
+            }
+            "pay" => {
+                let destination = required("destination")?;
+                strkey::decode_account_id(&destination)?;
+                let asset = match (param("asset_code"), param("asset_issuer")) {
+                    (None, _) => AssetId::Native,
+                    (Some(code), None) if code.eq_ignore_ascii_case("XLM") => AssetId::Native,
+                    (Some(code), Some(issuer)) => AssetId::credit(&code, &issuer)?,
+                    (Some(code), None) => return Err(format!("Asset '{}' has no asset_issuer", code).into()),
+                };
+                let amount = param("amount").map(|a| a.parse::<Amount>()).transpose()?;
+                let memo = match (param("memo"), param("memo_type").as_deref()) {
+                    (None, _) => None,
+                    (Some(memo), None) | (Some(memo), Some("MEMO_TEXT")) => Some(FederationMemo::Text(memo)),
+                    (Some(memo), Some("MEMO_ID")) => {
+                        Some(FederationMemo::Id(memo.parse().map_err(|_| format!("Invalid ID memo '{}'", memo))?))
+                    }
+                    (Some(memo), Some("MEMO_HASH")) => Some(FederationMemo::Hash(memo)),
+                    // A return memo names a payment to refund; paying it back isn't supported.
+                    (Some(_), Some("MEMO_RETURN")) => return Err("MEMO_RETURN payment requests are not supported".into()),
+                    (Some(_), Some(other)) => return Err(format!("Unknown memo type '{}'", other).into()),
+                };
+                UriRequest::Pay { destination, amount, asset, memo }
+            }
+            other => return Err(format!("Unsupported SEP-7 operation '{}'", other).into()),
+        };
+
+        let callback = match param("callback") {
+            Some(callback) => Some(
+                callback
+                    .strip_prefix("url:")
+                    .ok_or("The callback must start with 'url:'")?
+                    .to_string(),
+            ),
+            None => None,
+        };
+        let message = param("msg");
+        if message.as_ref().is_some_and(|m| m.chars().count() > MAX_MESSAGE_LENGTH) {
+            return Err(format!("The message is longer than {} characters", MAX_MESSAGE_LENGTH).into());
+        }
+        let origin_domain = param("origin_domain");
+        if origin_domain.is_some() && signature.is_none() {
+            return Err("The URI has an origin_domain but no signature".into());
+        }
+
+        Ok(StellarUri {
+            request,
+            callback,
+            message,
+            network_passphrase: param("network_passphrase"),
+            origin_domain,
+            signature,
+            original: Some(format!("{}{}?{}", URI_SCHEME, operation, unsigned.join("&"))),
+        })
+    }
+
+    /// The URI without its signature, in the exact form the signature covers.
+    pub fn unsigned_uri(&self) -> String {
+        if let Some(original) = &self.original {
+            return original.clone();
+        }
+        let mut params: Vec<(&str, String)> = Vec::new();
+        let operation = match &self.request {
+            UriRequest::Tx { xdr, replace, pubkey } => {
+                params.push(("xdr", xdr.clone()));
+                if let Some(replace) = replace {
+                    params.push(("replace", replace.clone()));
+                }
+                if let Some(pubkey) = pubkey {
+                    params.push(("pubkey", pubkey.clone()));
+                }
+                "tx"
+            }
+            UriRequest::Pay { destination, amount, asset, memo } => {
+                params.push(("destination", destination.clone()));
+                if let Some(amount) = amount {
+                    params.push(("amount", amount.to_string()));
+                }
+                if let AssetId::Credit { code, issuer } = asset {
+                    params.push(("asset_code", code.clone()));
+                    params.push(("asset_issuer", issuer.clone()));
+                }
+                match memo {
+                    Some(FederationMemo::Text(text)) => {
+                        params.push(("memo", text.clone()));
+                        params.push(("memo_type", "MEMO_TEXT".to_string()));
+                    }
+                    Some(FederationMemo::Id(id)) => {
+                        params.push(("memo", id.to_string()));
+                        params.push(("memo_type", "MEMO_ID".to_string()));
+                    }
+                    Some(FederationMemo::Hash(hash)) => {
+                        params.push(("memo", hash.clone()));
+                        params.push(("memo_type", "MEMO_HASH".to_string()));
+                    }
+                    None => {}
+                }
+                "pay"
+            }
+        };
+        if let Some(callback) = &self.callback {
+            params.push(("callback", format!("url:{}", callback)));
+        }
+        if let Some(message) = &self.message {
+            params.push(("msg", message.clone()));
+        }
+        if let Some(passphrase) = &self.network_passphrase {
+            params.push(("network_passphrase", passphrase.clone()));
+        }
+        if let Some(domain) = &self.origin_domain {
+            params.push(("origin_domain", domain.clone()));
+        }
+        let query: Vec<String> = params
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, percent_encode(value)))
+            .collect();
+        format!("{}{}?{}", URI_SCHEME, operation, query.join("&"))
+    }
+
+    /// Sets `origin_domain` and signs the URI with the domain's
+    /// `URI_REQUEST_SIGNING_KEY` secret.
+    pub fn sign(mut self, origin_domain: &str, signing_secret_key: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let keypair = Keypair::from_secret(signing_secret_key)?;
+        self.origin_domain = Some(origin_domain.to_string());
+        self.original = None;
+        let signature = keypair.sign(&signature_payload(&self.unsigned_uri()))?;
+        self.signature = Some(BASE64.encode(signature));
+        Ok(self)
+    }
+
+    /// Checks the signature against `signing_key` (G...).
+    pub fn verify_signature(&self, signing_key: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let signature = self.signature.as_ref().ok_or("The URI isn't signed")?;
+        let signature = BASE64.decode(signature)?;
+        Keypair::from_public_key(signing_key)?
+            .verify(&signature_payload(&self.unsigned_uri()), &signature)
+            .map_err(|_| "The URI signature is invalid")?;
+        Ok(())
+    }
+
+    /// Verifies the signature against the `URI_REQUEST_SIGNING_KEY` in the
+    /// stellar.toml of `origin_domain`.
+    ///
+    /// # Returns
+    /// A `Result` containing the verified origin domain, or `None` for unsigned
+    /// URIs, which wallets should present as coming from an unknown source.
+    pub async fn verify_origin(&self) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        self.verify_origin_with(&StellarTomlFetcher::new()).await
+    }
+
+    /// Same as [`StellarUri::verify_origin`], fetching stellar.toml with `fetcher`.
+    pub async fn verify_origin_with(&self, fetcher: &StellarTomlFetcher) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let domain = match &self.origin_domain {
+            Some(domain) => domain,
+            None => return Ok(None),
+        };
+        let toml = fetcher.fetch(domain).await?;
+        let signing_key = toml
+            .uri_request_signing_key
+            .ok_or_else(|| format!("{} publishes no URI_REQUEST_SIGNING_KEY", domain))?;
+        self.verify_signature(&signing_key)?;
+        Ok(Some(domain.clone()))
+    }
+}
+
+impl FromStr for StellarUri {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StellarUri::parse(s)
+    }
+}
+
+impl fmt::Display for StellarUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.unsigned_uri())?;
+        if let Some(signature) = &self.signature {
+            write!(f, "&signature={}", percent_encode(signature))?;
+        }
+        Ok(())
+    }
+}
+
+fn signature_payload(unsigned_uri: &str) -> Vec<u8> {
+    let mut payload = vec![0u8; 35];
+    payload.push(4);
+    payload.extend_from_slice(SIGNATURE_PREFIX.as_bytes());
+    payload.extend_from_slice(unsigned_uri.as_bytes());
+    payload
+}
+
+/// Percent-encodes everything but the characters JavaScript's `encodeURIComponent` keeps.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn percent_decode(value: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = value.get(i + 1..i + 3).ok_or_else(|| format!("Invalid escape in '{}'", value))?;
+            decoded.push(u8::from_str_radix(hex, 16).map_err(|_| format!("Invalid escape in '{}'", value))?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Ok(String::from_utf8(decoded)?)
+}
+
+impl StellarWalletTools {
+    /// Builds and signs the transaction a SEP-7 URI asks for.
+    ///
+    /// # Arguments
+    /// * `uri` - The request; its network must match `network`.
+    /// * `secret_key` - The secret key to sign with.
+    /// * `amount` - The amount to pay, for `pay` requests that leave it open.
+    /// * `network` - The network profile to build the transaction for.
+    ///
+    /// # Returns
+    /// A `Result` containing the signed transaction XDR (base64).
+    pub async fn sign_uri_request(
+        uri: &StellarUri,
+        secret_key: &str,
+        amount: Option<Amount>,
+        network: &NetworkProfile,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        if uri.network_passphrase() != network.network_passphrase {
+            return Err(format!("The request is for another network ('{}')", uri.network_passphrase()).into());
+        }
+        let keypair = Keypair::from_secret(secret_key)?;
+        match &uri.request {
+            UriRequest::Tx { xdr, replace, pubkey } => {
+                if replace.is_some() {
+                    return Err("Requests with fields to replace aren't supported".into());
+                }
+                if let Some(pubkey) = pubkey {
+                    if *pubkey != keypair.public_key().to_string() {
+                        return Err(format!("The request must be signed by {}", pubkey).into());
+                    }
+                }
+                // Check that the envelope is for this network before signing it.
+                Transaction::from_xdr_base64(xdr, Network::from_str(&network.network_passphrase)?)?;
+                Ok(tx::add_signature(xdr, &keypair, &network.network_passphrase)?)
+            }
+            UriRequest::Pay { destination, amount: requested, asset, memo } => {
+                let amount = requested.or(amount).ok_or("The request doesn't set an amount")?;
+                let destination = ResolvedDestination {
+                    account_id: destination.clone(),
+                    memo: memo.clone(),
+                    stellar_address: None,
+                };
+                Self::create_payment_to_destination_transaction(secret_key, &destination, amount, asset, network).await
+            }
+        }
+    }
+
+    /// Delivers a transaction signed for a SEP-7 request: to the request's
+    /// callback if it has one, to Horizon otherwise.
+    ///
+    /// # Returns
+    /// A `Result` containing the transaction hash when submitted to Horizon, or
+    /// `None` when the transaction was handed to the callback.
+    pub async fn submit_uri_transaction(
+        uri: &StellarUri,
+        signed_xdr: &str,
+        network: &NetworkProfile,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        match &uri.callback {
+            Some(callback) => {
+                let response = reqwest::Client::new().post(callback).form(&[("xdr", signed_xdr)]).send().await?;
+                if !response.status().is_success() {
+                    return Err(format!("The callback answered {}", response.status()).into());
+                }
+                Ok(None)
+            }
+            None => Ok(Some(horizon::submit_transaction(&network.horizon_url, signed_xdr).await?)),
+        }
+    }
+}
@@ -0,0 +1,304 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\watcher.rs
+
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Error type used by the payment stream. It has to be `Send + Sync` so the
+/// stream can be driven from a spawned tokio task.
+pub type WatchError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A single record from Horizon's `/accounts/{id}/payments` endpoint.
+///
+/// Horizon reports several operation types on this endpoint (`payment`,
+/// `create_account`, `path_payment_strict_send`, `path_payment_strict_receive`
+/// and `account_merge`), so every type-specific field is optional.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentEvent {
+    pub id: String,
+    pub paging_token: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub transaction_hash: String,
+    pub source_account: Option<String>,
+    pub created_at: Option<String>,
+    // `payment` and path payment fields
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub amount: Option<String>,
+    pub asset_type: Option<String>,
+    pub asset_code: Option<String>,
+    pub asset_issuer: Option<String>,
+    // `create_account` fields
+    pub funder: Option<String>,
+    pub account: Option<String>,
+    pub starting_balance: Option<String>,
+    // `account_merge` fields
+    pub into: Option<String>,
+}
+
+impl PaymentEvent {
+    /// Returns the asset of the payment as `CODE:ISSUER`, or `XLM` for the native asset.
+    pub fn asset(&self) -> String {
+        match (&self.asset_code, &self.asset_issuer) {
+            (Some(code), Some(issuer)) => format!("{}:{}", code, issuer),
+            _ => "XLM".to_string(),
+        }
+    }
+
+    /// Returns the sending account: the payer, the funder of `create_account`
+    /// or the account merged away by `account_merge`.
+    pub fn sender(&self) -> Option<&str> {
+        match self.kind.as_str() {
+            "create_account" => self.funder.as_deref(),
+            "account_merge" => self.account.as_deref(),
+            _ => self.from.as_deref(),
+        }
+    }
+
+    /// Returns the receiving account: the destination of a payment, the account
+    /// created by `create_account` or the account merged into by `account_merge`.
+    pub fn recipient(&self) -> Option<&str> {
+        match self.kind.as_str() {
+            "create_account" => self.account.as_deref(),
+            "account_merge" => self.into.as_deref(),
+            _ => self.to.as_deref(),
+        }
+    }
+
+    /// Returns `true` if `account_id` is on the receiving side of this record.
+    pub fn is_incoming(&self, account_id: &str) -> bool {
+        self.recipient() == Some(account_id)
+    }
+}
+
+/// Follows the payments of a single account using Horizon's server-sent events.
+///
+/// The watcher keeps track of the last paging token it handed out and reconnects
+/// from there whenever the connection drops. If a cursor file is configured,
+/// the cursor is also written to disk so a restarted process resumes where it stopped.
+pub struct PaymentWatcher {
+    horizon_url: String,
+    account_id: String,
+    cursor: String,
+    cursor_file: Option<PathBuf>,
+    reconnect_delay: Duration,
+}
+
+impl PaymentWatcher {
+    /// Creates a watcher for `account_id` starting at cursor `now`
+    /// (i.e. only payments made after the stream is opened).
+    pub fn new(horizon_url: &str, account_id: &str) -> Self {
+        PaymentWatcher {
+            horizon_url: horizon_url.trim_end_matches('/').to_string(),
+            account_id: account_id.to_string(),
+            cursor: "now".to_string(),
+            cursor_file: None,
+            reconnect_delay: Duration::from_secs(5),
+        }
+    }
+
+    /// Starts streaming from the given paging token instead of `now`.
+    pub fn cursor(mut self, cursor: &str) -> Self {
+        self.cursor = cursor.to_string();
+        self
+    }
+
+    /// Persists the cursor to `path` after each payment has been consumed.
+    ///
+    /// If the file already contains a cursor it takes precedence over the one
+    /// set with [`PaymentWatcher::cursor`], so a restarted watcher resumes.
+    pub fn cursor_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        if let Ok(saved) = std::fs::read_to_string(&path) {
+            let saved = saved.trim();
+            if !saved.is_empty() {
+                self.cursor = saved.to_string();
+            }
+        }
+        self.cursor_file = Some(path);
+        self
+    }
+
+    /// Sets how long to wait before reconnecting after the stream ends or fails.
+    /// Horizon may override this with a `retry:` field in the stream.
+    pub fn reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// Turns the watcher into an endless stream of payments.
+    ///
+    /// Connection errors are yielded as `Err` items, after which the stream
+    /// reconnects on its own; it never terminates by itself.
+    pub fn stream(self) -> impl Stream<Item = Result<PaymentEvent, WatchError>> {
+        let state = WatchState {
+            client: reqwest::Client::new(),
+            url: format!("{}/accounts/{}/payments", self.horizon_url, self.account_id),
+            cursor: self.cursor,
+            cursor_file: self.cursor_file,
+            unpersisted: None,
+            reconnect_delay: self.reconnect_delay,
+            reconnecting: false,
+            body: None,
+            parser: SseParser::default(),
+        };
+
+        stream::unfold(state, |mut state| async move {
+            let item = state.next_payment().await;
+            Some((item, state))
+        })
+    }
+}
+
+type ByteStream = Pin<Box<dyn Stream<Item = reqwest::Result<Vec<u8>>> + Send>>;
+
+struct WatchState {
+    client: reqwest::Client,
+    url: String,
+    cursor: String,
+    cursor_file: Option<PathBuf>,
+    // Cursor of the payment handed out last; written to disk once the caller asks
+    // for the next item, so a crash while handling a payment replays it on restart.
+    unpersisted: Option<String>,
+    reconnect_delay: Duration,
+    reconnecting: bool,
+    body: Option<ByteStream>,
+    parser: SseParser,
+}
+
+impl WatchState {
+    async fn next_payment(&mut self) -> Result<PaymentEvent, WatchError> {
+        if let Some(cursor) = self.unpersisted.take() {
+            if let Some(path) = &self.cursor_file {
+                std::fs::write(path, &cursor)?;
+            }
+        }
+
+        loop {
+            // 1. Hand out any event already buffered from the current connection.
+            while let Some(event) = self.parser.next_event() {
+                if let Some(retry) = event.retry {
+                    self.reconnect_delay = Duration::from_millis(retry);
+                }
+                if let Some(payment) = parse_payment(&event) {
+                    self.cursor = event.id.unwrap_or_else(|| payment.paging_token.clone());
+                    self.unpersisted = Some(self.cursor.clone());
+                    return Ok(payment);
+                }
+            }
+
+            // 2. (Re)connect if we have no open connection.
+            if self.body.is_none() {
+                if self.reconnecting {
+                    tokio::time::sleep(self.reconnect_delay).await;
+                }
+                self.reconnecting = true;
+                self.parser = SseParser::default();
+                self.body = Some(self.connect().await?);
+            }
+
+            // 3. Feed the next chunk into the parser.
+            let chunk = match self.body.as_mut() {
+                Some(body) => body.next().await,
+                None => None,
+            };
+            match chunk {
+                Some(Ok(chunk)) => self.parser.push(&chunk),
+                Some(Err(e)) => {
+                    self.body = None;
+                    return Err(e.into());
+                }
+                None => self.body = None,
+            }
+        }
+    }
+
+    async fn connect(&self) -> Result<ByteStream, WatchError> {
+        let response = self
+            .client
+            .get(&self.url)
+            .query(&[("cursor", self.cursor.as_str())])
+            .header("Accept", "text/event-stream")
+            .header("Last-Event-ID", self.cursor.as_str())
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(Box::pin(response.bytes_stream().map(|chunk| chunk.map(|b| b.to_vec()))))
+    }
+}
+
+/// Decodes the payment record carried by an event, if any.
+fn parse_payment(event: &SseEvent) -> Option<PaymentEvent> {
+    // Horizon opens every stream with a `"hello"` message and may send
+    // `"byebye"` before closing it; neither is a JSON object.
+    serde_json::from_str(&event.data).ok()
+}
+
+/// One dispatched server-sent event.
+#[derive(Debug, Default)]
+struct SseEvent {
+    id: Option<String>,
+    data: String,
+    retry: Option<u64>,
+}
+
+/// Minimal incremental parser for the `text/event-stream` format.
+#[derive(Default)]
+struct SseParser {
+    // Bytes of the last, incomplete line; a chunk may end in the middle of a
+    // multi-byte UTF-8 character, so only complete lines are decoded.
+    pending: Vec<u8>,
+    buffer: String,
+}
+
+impl SseParser {
+    fn push(&mut self, chunk: &[u8]) {
+        self.pending.extend_from_slice(chunk);
+        if let Some(end) = self.pending.iter().rposition(|&b| b == b'\n') {
+            let lines: Vec<u8> = self.pending.drain(..=end).collect();
+            self.buffer.push_str(&String::from_utf8_lossy(&lines));
+        }
+    }
+
+    fn next_event(&mut self) -> Option<SseEvent> {
+        loop {
+            let normalized = self.buffer.replace("\r\n", "\n");
+            let end = normalized.find("\n\n")?;
+            let block = normalized[..end].to_string();
+            self.buffer = normalized[end + 2..].to_string();
+
+            let mut event = SseEvent::default();
+            let mut has_field = false;
+            for line in block.lines() {
+                // Lines starting with ':' are comments (Horizon uses them as keep-alives).
+                if line.starts_with(':') {
+                    continue;
+                }
+                let (field, value) = match line.split_once(':') {
+                    Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                    None => (line, ""),
+                };
+                match field {
+                    "id" => event.id = Some(value.to_string()),
+                    "data" => {
+                        if !event.data.is_empty() {
+                            event.data.push('\n');
+                        }
+                        event.data.push_str(value);
+                    }
+                    "retry" => event.retry = value.parse().ok(),
+                    _ => {}
+                }
+                has_field = true;
+            }
+
+            if has_field {
+                return Some(event);
+            }
+        }
+    }
+}
@@ -0,0 +1,378 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\web_auth.rs
+// Stellar Web Authentication (SEP-0010) client: proves control of an account to
+// an anchor and obtains a JWT for its other endpoints.
+
+use crate::network::NetworkProfile;
+use crate::stellar_toml::{StellarToml, StellarTomlFetcher};
+use crate::strkey;
+use crate::time::unix_now;
+use base64::engine::general_purpose::{STANDARD as BASE64, URL_SAFE_NO_PAD as BASE64_URL};
+use base64::Engine;
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+use stellar_sdk::{Keypair, Memo, Network, Operation, Transaction};
+
+/// Clock skew tolerated when checking a challenge's time bounds, in seconds.
+pub const TIME_BOUNDS_GRACE_SECONDS: i64 = 300;
+/// Length of the base64-encoded random nonce in the first operation (48 random bytes).
+pub const NONCE_LENGTH: usize = 64;
+/// Name of the operation carrying the auth endpoint's domain.
+pub const WEB_AUTH_DOMAIN_KEY: &str = "web_auth_domain";
+/// Name of the operation identifying the client (wallet) domain.
+pub const CLIENT_DOMAIN_KEY: &str = "client_domain";
+
+/// A challenge that doesn't meet the SEP-10 rules. It must not be signed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeError(pub String);
+
+impl fmt::Display for ChallengeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid SEP-10 challenge: {}", self.0)
+    }
+}
+
+impl std::error::Error for ChallengeError {}
+
+fn invalid<T>(reason: impl Into<String>) -> Result<T, Box<dyn std::error::Error>> {
+    Err(Box::new(ChallengeError(reason.into())))
+}
+
+/// A `manage_data` operation of a challenge.
+#[derive(Debug, Clone)]
+pub(crate) struct ManageDataView {
+    pub source_account: Option<String>,
+    pub name: String,
+    pub value: Option<Vec<u8>>,
+}
+
+/// The parts of a challenge transaction SEP-10 cares about.
+#[derive(Debug, Clone)]
+pub(crate) struct ChallengeView {
+    pub source_account: String,
+    pub sequence: i64,
+    pub time_bounds: Option<(i64, i64)>,
+    pub memo_id: Option<u64>,
+    pub operations: Vec<ManageDataView>,
+    pub hash: [u8; 32],
+    pub signatures: Vec<Vec<u8>>,
+}
+
+impl ChallengeView {
+    /// Whether one of the envelope's signatures was made by `public_key`.
+    pub fn is_signed_by(&self, public_key: &str) -> bool {
+        match Keypair::from_public_key(public_key) {
+            Ok(keypair) => self.signatures.iter().any(|signature| keypair.verify(&self.hash, signature).is_ok()),
+            Err(_) => false,
+        }
+    }
+
+    /// Matches every signature to one of `candidates` and returns the keys that signed.
+    ///
+    /// A signature made by any other key is an error: SEP-10 forbids extra signatures.
+    pub fn signers_among(&self, candidates: &[String]) -> Result<Vec<String>, ChallengeError> {
+        let keypairs: Vec<(&String, Keypair)> = candidates
+            .iter()
+            .filter_map(|key| Keypair::from_public_key(key).ok().map(|keypair| (key, keypair)))
+            .collect();
+        let mut signers: Vec<String> = Vec::new();
+        for signature in &self.signatures {
+            let signer = keypairs
+                .iter()
+                .find(|(_, keypair)| keypair.verify(&self.hash, signature).is_ok())
+                .map(|(key, _)| (*key).clone())
+                .ok_or_else(|| ChallengeError("the challenge carries an unrecognized signature".to_string()))?;
+            if !signers.contains(&signer) {
+                signers.push(signer);
+            }
+        }
+        Ok(signers)
+    }
+}
+
+/// Decodes a challenge envelope, refusing anything but `manage_data` operations.
+pub(crate) fn read_challenge(
+    challenge_xdr: &str,
+    network_passphrase: &str,
+) -> Result<ChallengeView, Box<dyn std::error::Error>> {
+    let transaction = Transaction::from_xdr_base64(challenge_xdr, Network::from_str(network_passphrase)?)
+        .map_err(|e| ChallengeError(format!("not a transaction envelope ({})", e)))?;
+
+    let mut operations = Vec::new();
+    for operation in transaction.operations() {
+        match operation {
+            Operation::ManageData(data) => operations.push(ManageDataView {
+                source_account: data.source_account().map(str::to_string),
+                name: data.name().to_string(),
+                value: data.value().map(<[u8]>::to_vec),
+            }),
+            _ => return invalid("only manage_data operations are allowed"),
+        }
+    }
+    let memo_id = match transaction.memo() {
+        Memo::None => None,
+        Memo::Id(id) => Some(*id),
+        _ => return invalid("only ID memos are allowed"),
+    };
+
+    Ok(ChallengeView {
+        source_account: transaction.source_account().to_string(),
+        sequence: transaction.sequence_number(),
+        time_bounds: transaction.time_bounds(),
+        memo_id,
+        operations,
+        hash: transaction.hash(),
+        signatures: transaction.signatures().iter().map(|s| s.signature().to_vec()).collect(),
+    })
+}
+
+/// The `host[:port]` of a URL, as used in the `web_auth_domain` operation.
+pub(crate) fn url_authority(url: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let url = reqwest::Url::parse(url)?;
+    let host = url.host_str().ok_or_else(|| format!("'{}' has no host", url))?;
+    Ok(match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    })
+}
+
+/// Checks a challenge against every SEP-10 rule, from the point of view of the
+/// client (which has no secret of the server).
+///
+/// `client_domain` operations are only accepted with `allow_client_domain`: the
+/// server checks them itself, while the client never asks for one.
+///
+/// Returns the decoded challenge so the caller can go on to sign it.
+pub(crate) fn validate_challenge(
+    challenge_xdr: &str,
+    server_signing_key: &str,
+    network_passphrase: &str,
+    home_domain: &str,
+    web_auth_domain: &str,
+    client_account: &str,
+    allow_client_domain: bool,
+    now: i64,
+) -> Result<ChallengeView, Box<dyn std::error::Error>> {
+    let challenge = read_challenge(challenge_xdr, network_passphrase)?;
+
+    if challenge.source_account != server_signing_key {
+        return invalid("the transaction source is not the server signing key");
+    }
+    if challenge.sequence != 0 {
+        return invalid("the sequence number must be 0");
+    }
+    match challenge.time_bounds {
+        Some((min_time, max_time)) if max_time != 0 => {
+            if now < min_time - TIME_BOUNDS_GRACE_SECONDS || now > max_time {
+                return invalid("the challenge has expired or is not valid yet");
+            }
+        }
+        _ => return invalid("the challenge must have time bounds with a maximum time"),
+    }
+    if challenge.memo_id.is_some() && client_account.starts_with('M') {
+        return invalid("a memo can't be combined with a muxed account");
+    }
+
+    let (first, rest) = match challenge.operations.split_first() {
+        Some(split) => split,
+        None => return invalid("the challenge has no operations"),
+    };
+    if first.source_account.as_deref() != Some(client_account) {
+        return invalid("the first operation's source is not the client account");
+    }
+    if first.name != format!("{} auth", home_domain) {
+        return invalid(format!("the challenge is not for home domain '{}'", home_domain));
+    }
+    match &first.value {
+        Some(nonce) if nonce.len() == NONCE_LENGTH && BASE64.decode(nonce).is_ok_and(|n| n.len() == 48) => {}
+        _ => return invalid("the nonce must be 48 random bytes, base64-encoded"),
+    }
+
+    for operation in rest {
+        if operation.name == CLIENT_DOMAIN_KEY {
+            if !allow_client_domain {
+                return invalid("the challenge has a client_domain operation that wasn't requested");
+            }
+            // Sourced by the wallet's own signing key; it signs separately.
+            continue;
+        }
+        if operation.source_account.as_deref() != Some(server_signing_key) {
+            return invalid(format!("operation '{}' is not sourced by the server", operation.name));
+        }
+        if operation.name == WEB_AUTH_DOMAIN_KEY && operation.value.as_deref() != Some(web_auth_domain.as_bytes()) {
+            return invalid(format!("web_auth_domain doesn't match '{}'", web_auth_domain));
+        }
+    }
+
+    if !challenge.is_signed_by(server_signing_key) {
+        return invalid("the challenge is not signed by the server");
+    }
+    Ok(challenge)
+}
+
+/// A JWT issued by a SEP-10 server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebAuthToken {
+    pub token: String,
+    /// The authenticated account (the JWT `sub`).
+    pub account: String,
+    /// UNIX time the token expires (the JWT `exp`), if present.
+    pub expires_at: Option<i64>,
+}
+
+impl WebAuthToken {
+    /// Reads the account and expiry from a JWT. The signature is not verified:
+    /// only the server that issued the token can do that.
+    pub fn from_jwt(token: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let payload = token.split('.').nth(1).ok_or("The token is not a JWT")?;
+        let claims: serde_json::Value = serde_json::from_slice(&BASE64_URL.decode(payload.trim_end_matches('='))?)?;
+        Ok(WebAuthToken {
+            token: token.to_string(),
+            account: claims["sub"].as_str().ok_or("The token has no 'sub' claim")?.to_string(),
+            expires_at: claims["exp"].as_i64(),
+        })
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+#[derive(Deserialize)]
+struct ChallengeResponse {
+    transaction: String,
+    #[serde(default)]
+    network_passphrase: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    token: String,
+}
+
+/// SEP-10 client for one anchor (home domain).
+#[derive(Debug, Clone)]
+pub struct WebAuthClient {
+    pub home_domain: String,
+    pub endpoint: String,
+    pub server_signing_key: String,
+    pub network_passphrase: String,
+}
+
+impl WebAuthClient {
+    /// Creates a client from the anchor's stellar.toml.
+    pub fn from_stellar_toml(
+        home_domain: &str,
+        toml: &StellarToml,
+        network: &NetworkProfile,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let endpoint = toml.web_auth_endpoint.clone().ok_or("The anchor has no WEB_AUTH_ENDPOINT")?;
+        let server_signing_key = toml.signing_key.clone().ok_or("The anchor has no SIGNING_KEY")?;
+        strkey::decode_account_id(&server_signing_key)?;
+        if let Some(passphrase) = &toml.network_passphrase {
+            if *passphrase != network.network_passphrase {
+                return Err(format!("{} runs on another network ('{}')", home_domain, passphrase).into());
+            }
+        }
+        Ok(WebAuthClient {
+            home_domain: home_domain.to_string(),
+            endpoint,
+            server_signing_key,
+            network_passphrase: network.network_passphrase.clone(),
+        })
+    }
+
+    /// Creates a client by fetching the stellar.toml of `home_domain`.
+    pub async fn from_domain(home_domain: &str, network: &NetworkProfile) -> Result<Self, Box<dyn std::error::Error>> {
+        let toml = StellarTomlFetcher::new().fetch(home_domain).await?;
+        Self::from_stellar_toml(home_domain, &toml, network)
+    }
+
+    /// Requests a challenge for `account` and validates it.
+    ///
+    /// # Returns
+    /// A `Result` containing the challenge envelope (base64 XDR), safe to sign.
+    pub async fn get_challenge(&self, account: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let response = reqwest::Client::new()
+            .get(&self.endpoint)
+            .query(&[("account", account), ("home_domain", self.home_domain.as_str())])
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            return Err(format!("Challenge request failed ({}): {}", status, body["error"].as_str().unwrap_or("")).into());
+        }
+        let challenge: ChallengeResponse = response.json().await?;
+        if let Some(passphrase) = &challenge.network_passphrase {
+            if *passphrase != self.network_passphrase {
+                return invalid(format!("the server answered for another network ('{}')", passphrase));
+            }
+        }
+        self.validate_challenge(&challenge.transaction, account, unix_now())?;
+        Ok(challenge.transaction)
+    }
+
+    /// Validates a challenge for `account` at time `now`.
+    pub fn validate_challenge(&self, challenge_xdr: &str, account: &str, now: i64) -> Result<(), Box<dyn std::error::Error>> {
+        validate_challenge(
+            challenge_xdr,
+            &self.server_signing_key,
+            &self.network_passphrase,
+            &self.home_domain,
+            &url_authority(&self.endpoint)?,
+            account,
+            false,
+            now,
+        )?;
+        Ok(())
+    }
+
+    /// Adds a signature from each signer. Multisig accounts need enough signers
+    /// to reach the account's medium threshold.
+    pub fn sign_challenge(&self, challenge_xdr: &str, signers: &[Keypair]) -> Result<String, Box<dyn std::error::Error>> {
+        let mut transaction = Transaction::from_xdr_base64(challenge_xdr, Network::from_str(&self.network_passphrase)?)?;
+        for signer in signers {
+            transaction = transaction.sign(signer)?;
+        }
+        Ok(transaction.to_xdr_base64())
+    }
+
+    /// Sends the signed challenge back and returns the token.
+    pub async fn submit_challenge(&self, signed_challenge_xdr: &str) -> Result<WebAuthToken, Box<dyn std::error::Error>> {
+        let response = reqwest::Client::new()
+            .post(&self.endpoint)
+            .json(&serde_json::json!({ "transaction": signed_challenge_xdr }))
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            return Err(format!("Authentication failed ({}): {}", status, body["error"].as_str().unwrap_or("")).into());
+        }
+        let token: TokenResponse = response.json().await?;
+        WebAuthToken::from_jwt(&token.token)
+    }
+
+    /// Runs the whole flow: challenge, validation, signing and token exchange.
+    ///
+    /// # Arguments
+    /// * `account` - The account to authenticate (G...).
+    /// * `signer_secret_keys` - Secret keys to sign with; usually just the account's own.
+    pub async fn authenticate(
+        &self,
+        account: &str,
+        signer_secret_keys: &[&str],
+    ) -> Result<WebAuthToken, Box<dyn std::error::Error>> {
+        if signer_secret_keys.is_empty() {
+            return Err("At least one signer is required".into());
+        }
+        let signers = signer_secret_keys
+            .iter()
+            .map(|secret| Keypair::from_secret(secret))
+            .collect::<Result<Vec<_>, _>>()?;
+        let challenge = self.get_challenge(account).await?;
+        let signed_challenge = self.sign_challenge(&challenge, &signers)?;
+        self.submit_challenge(&signed_challenge).await
+    }
+}
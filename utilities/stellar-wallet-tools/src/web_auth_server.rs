@@ -0,0 +1,233 @@
+// E:\Rust\Projects\wallet\utilities\stellar_wallet_tools\src\web_auth_server.rs
+// Server side of Stellar Web Authentication (SEP-0010): issues challenges and
+// verifies the signed responses sent back by wallets.
+
+use crate::network::NetworkProfile;
+use crate::web_auth::{read_challenge, url_authority, validate_challenge, ChallengeError, CLIENT_DOMAIN_KEY, WEB_AUTH_DOMAIN_KEY};
+use crate::{horizon, strkey, tx};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rand::RngCore;
+use serde::Deserialize;
+use stellar_sdk::{Keypair, ManageDataOperation, Memo};
+
+/// Default validity of a challenge, in seconds.
+pub const DEFAULT_CHALLENGE_TTL_SECONDS: i64 = 900;
+
+/// The account proven by a verified challenge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedAccount {
+    pub account: String,
+    pub home_domain: String,
+    /// The ID memo of a shared (custodial) account, if the challenge carried one.
+    pub memo: Option<u64>,
+    /// The account's signers whose signatures were counted.
+    pub signers: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AccountSigners {
+    signers: Vec<AccountSigner>,
+    thresholds: Thresholds,
+}
+
+#[derive(Deserialize)]
+struct AccountSigner {
+    key: String,
+    weight: u32,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+struct Thresholds {
+    med_threshold: u32,
+}
+
+/// Issues and verifies SEP-10 challenges for one server signing key.
+pub struct WebAuthServer {
+    signing_keypair: Keypair,
+    signing_key: String,
+    /// Home domains this server authenticates for; the first is the default.
+    home_domains: Vec<String>,
+    /// `host[:port]` of the auth endpoint, put in the `web_auth_domain` operation.
+    web_auth_domain: String,
+    network: NetworkProfile,
+    challenge_ttl: i64,
+}
+
+impl WebAuthServer {
+    /// Creates a server.
+    ///
+    /// # Arguments
+    /// * `signing_secret_key` - Secret of the `SIGNING_KEY` published in stellar.toml.
+    /// * `home_domains` - Home domains accepted in challenges (at least one).
+    /// * `web_auth_endpoint` - The public URL of the auth endpoint.
+    /// * `network` - The network; its Horizon is used to look up client signers.
+    pub fn new(
+        signing_secret_key: &str,
+        home_domains: &[&str],
+        web_auth_endpoint: &str,
+        network: &NetworkProfile,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if home_domains.is_empty() {
+            return Err("At least one home domain is required".into());
+        }
+        let signing_keypair = Keypair::from_secret(signing_secret_key)?;
+        Ok(WebAuthServer {
+            signing_key: signing_keypair.public_key().to_string(),
+            signing_keypair,
+            home_domains: home_domains.iter().map(|d| d.to_string()).collect(),
+            web_auth_domain: url_authority(web_auth_endpoint)?,
+            network: network.clone(),
+            challenge_ttl: DEFAULT_CHALLENGE_TTL_SECONDS,
+        })
+    }
+
+    /// Sets how long challenges stay valid, in seconds.
+    pub fn challenge_ttl(mut self, seconds: i64) -> Self {
+        self.challenge_ttl = seconds;
+        self
+    }
+
+    /// The server's public signing key (G...).
+    pub fn signing_key(&self) -> &str {
+        &self.signing_key
+    }
+
+    /// Builds a signed challenge for `client_account`.
+    ///
+    /// # Arguments
+    /// * `client_account` - The account to authenticate (G...).
+    /// * `home_domain` - The requested home domain, or `None` for the default one.
+    /// * `memo` - ID memo identifying a user of a shared account.
+    /// * `now` - Current UNIX time; the challenge is valid from now for the TTL.
+    ///
+    /// # Returns
+    /// A `Result` containing the base64-encoded challenge envelope.
+    pub fn build_challenge(
+        &self,
+        client_account: &str,
+        home_domain: Option<&str>,
+        memo: Option<u64>,
+        now: i64,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        strkey::decode_account_id(client_account)?;
+        let home_domain = match home_domain {
+            Some(domain) if self.home_domains.iter().any(|d| d == domain) => domain,
+            Some(domain) => return Err(format!("Home domain '{}' is not served here", domain).into()),
+            None => &self.home_domains[0],
+        };
+
+        let mut nonce = [0u8; 48];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let operations = vec![
+            ManageDataOperation::new(format!("{} auth", home_domain), Some(BASE64.encode(nonce).into_bytes()))
+                .with_source_account(client_account.to_string())
+                .into(),
+            ManageDataOperation::new(WEB_AUTH_DOMAIN_KEY.to_string(), Some(self.web_auth_domain.clone().into_bytes()))
+                .with_source_account(self.signing_key.clone())
+                .into(),
+        ];
+        let transaction = tx::build_transaction_at_sequence(
+            &self.network,
+            &self.signing_key,
+            0,
+            Some((now, now + self.challenge_ttl)),
+            memo.map(Memo::id),
+            operations,
+        )?;
+        Ok(transaction.sign(&self.signing_keypair)?.to_xdr_base64())
+    }
+
+    /// Verifies a challenge signed by the client and returns the authenticated account.
+    ///
+    /// The challenge must be one of ours and still valid. If the client account
+    /// exists, the signatures of its signers must reach its medium threshold; if it
+    /// doesn't exist yet, it must be signed by the account's own key. Any signature
+    /// from another key is rejected.
+    pub async fn verify_challenge(
+        &self,
+        signed_challenge_xdr: &str,
+        now: i64,
+    ) -> Result<AuthenticatedAccount, Box<dyn std::error::Error>> {
+        let view = read_challenge(signed_challenge_xdr, &self.network.network_passphrase)?;
+        let first = view.operations.first().ok_or_else(|| ChallengeError("the challenge has no operations".to_string()))?;
+        let client_account = first
+            .source_account
+            .clone()
+            .ok_or_else(|| ChallengeError("the first operation has no source account".to_string()))?;
+        let home_domain = self
+            .home_domains
+            .iter()
+            .find(|domain| first.name == format!("{} auth", domain))
+            .ok_or_else(|| ChallengeError(format!("unknown home domain in '{}'", first.name)))?
+            .clone();
+
+        let challenge = validate_challenge(
+            signed_challenge_xdr,
+            &self.signing_key,
+            &self.network.network_passphrase,
+            &home_domain,
+            &self.web_auth_domain,
+            &client_account,
+            true,
+            now,
+        )?;
+
+        let mut candidates = vec![self.signing_key.clone()];
+        // A client_domain operation must be signed by the wallet's signing key.
+        let client_domain_key = challenge
+            .operations
+            .iter()
+            .find(|op| op.name == CLIENT_DOMAIN_KEY)
+            .and_then(|op| op.source_account.clone());
+        if let Some(key) = &client_domain_key {
+            if !challenge.is_signed_by(key) {
+                return Err(Box::new(ChallengeError("the client domain key didn't sign".to_string())));
+            }
+            candidates.push(key.clone());
+        }
+
+        let account_url = format!("{}/accounts/{}", self.network.horizon_url, client_account);
+        let signers = match horizon::get_json_optional::<AccountSigners>(&account_url, &[]).await? {
+            None => {
+                // The account doesn't exist yet: only its master key can prove control.
+                candidates.push(client_account.clone());
+                let signed = challenge.signers_among(&candidates)?;
+                if !signed.contains(&client_account) {
+                    return Err(Box::new(ChallengeError("not signed by the client account".to_string())));
+                }
+                vec![client_account.clone()]
+            }
+            Some(account) => {
+                let ed25519_signers: Vec<&AccountSigner> = account
+                    .signers
+                    .iter()
+                    .filter(|s| s.kind == "ed25519_public_key" && s.weight > 0)
+                    .collect();
+                candidates.extend(ed25519_signers.iter().map(|s| s.key.clone()));
+                let signed = challenge.signers_among(&candidates)?;
+
+                let client_signers: Vec<String> = signed
+                    .into_iter()
+                    .filter(|key| *key != self.signing_key && Some(key) != client_domain_key.as_ref())
+                    .collect();
+                let weight: u32 = ed25519_signers
+                    .iter()
+                    .filter(|s| client_signers.contains(&s.key))
+                    .map(|s| s.weight)
+                    .sum();
+                if weight == 0 || weight < account.thresholds.med_threshold {
+                    return Err(Box::new(ChallengeError(format!(
+                        "signers' weight {} doesn't reach the medium threshold {}",
+                        weight, account.thresholds.med_threshold
+                    ))));
+                }
+                client_signers
+            }
+        };
+
+        Ok(AuthenticatedAccount { account: client_account, home_domain, memo: challenge.memo_id, signers })
+    }
+}